@@ -0,0 +1,447 @@
+use anyhow::{anyhow, Result};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+pub const DEFAULT_PORT: u16 = 30815;
+
+// How long a connection waits for the UI thread to handle its request
+const REPLY_TIMEOUT: Duration = Duration::from_secs(10);
+const MAX_BODY_SIZE: usize = 64 * 1024;
+// Request line plus headers; real clients send far less
+const MAX_HEADER_SIZE: usize = 8 * 1024;
+const MAX_HEADER_LINES: usize = 64;
+// Connections handled at once; more are turned away with 503
+const MAX_CONNECTIONS: usize = 8;
+
+/// Something an API client asked the panel to do. Server ids are the ids from servers.json.
+pub enum ApiCommand {
+    ListServers,
+    Status(String),
+    Start(String),
+    Stop(String),
+    Command(String, String),
+}
+
+pub struct ApiResponse {
+    pub status: u16,
+    pub body: Value,
+}
+
+impl ApiResponse {
+    pub fn ok(body: Value) -> Self {
+        Self { status: 200, body }
+    }
+
+    pub fn error(status: u16, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            body: json!({ "error": message.into() }),
+        }
+    }
+}
+
+/// A request waiting for the UI thread, which owns the server list and processes
+pub struct ApiRequest {
+    pub command: ApiCommand,
+    reply: Sender<ApiResponse>,
+}
+
+impl ApiRequest {
+    pub fn respond(self, response: ApiResponse) {
+        // The connection may have timed out already; nothing to do then
+        let _ = self.reply.send(response);
+    }
+}
+
+/// Local HTTP control API, bound to 127.0.0.1 and guarded by a bearer token.
+/// Stops listening when dropped.
+pub struct ApiServer {
+    port: u16,
+    token: String,
+    requests: Receiver<ApiRequest>,
+    shutdown: Arc<AtomicBool>,
+    listener_thread: Option<thread::JoinHandle<()>>,
+}
+
+impl ApiServer {
+    pub fn start(port: u16, token: String, ctx: egui::Context) -> Result<Self> {
+        if token.is_empty() {
+            return Err(anyhow!("An API token is required"));
+        }
+
+        let listener = TcpListener::bind(("127.0.0.1", port))?;
+        // Non-blocking accept so the thread notices shutdown
+        listener.set_nonblocking(true)?;
+
+        let (tx, rx) = channel();
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let thread_shutdown = shutdown.clone();
+        let thread_token = token.clone();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let listener_thread = thread::spawn(move || {
+            while !thread_shutdown.load(Ordering::Relaxed) {
+                match listener.accept() {
+                    Ok((stream, _)) => {
+                        let Some(slot) = ConnectionSlot::take(&connections) else {
+                            let _ = stream.set_nonblocking(false);
+                            let _ = stream.set_write_timeout(Some(Duration::from_secs(1)));
+                            let _ = write_response(&stream, ApiResponse::error(503, "Too many connections"));
+                            continue;
+                        };
+                        let tx = tx.clone();
+                        let token = thread_token.clone();
+                        let ctx = ctx.clone();
+                        thread::spawn(move || {
+                            handle_connection(stream, &token, &tx, &ctx);
+                            drop(slot);
+                        });
+                    }
+                    Err(_) => thread::sleep(Duration::from_millis(50)),
+                }
+            }
+        });
+
+        Ok(Self {
+            port,
+            token,
+            requests: rx,
+            shutdown,
+            listener_thread: Some(listener_thread),
+        })
+    }
+
+    /// Whether this server was started with the given settings
+    pub fn matches(&self, port: u16, token: &str) -> bool {
+        self.port == port && self.token == token
+    }
+
+    /// Requests received since the last call
+    pub fn pending_requests(&self) -> Vec<ApiRequest> {
+        self.requests.try_iter().collect()
+    }
+}
+
+impl Drop for ApiServer {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::Relaxed);
+        if let Some(thread) = self.listener_thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+/// One of the `MAX_CONNECTIONS` connections in progress, given back when dropped
+struct ConnectionSlot(Arc<AtomicUsize>);
+
+impl ConnectionSlot {
+    fn take(count: &Arc<AtomicUsize>) -> Option<Self> {
+        count
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |n| (n < MAX_CONNECTIONS).then_some(n + 1))
+            .ok()
+            .map(|_| Self(count.clone()))
+    }
+}
+
+impl Drop for ConnectionSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::AcqRel);
+    }
+}
+
+/// The API as the settings describe it. It is started when enabled and then keeps its
+/// port and token until restarted on purpose, so editing the port doesn't rebind on every
+/// change; a failed start is kept as an error instead of being retried every frame.
+#[derive(Default)]
+pub struct ApiHost {
+    server: Option<ApiServer>,
+    error: Option<String>,
+}
+
+impl ApiHost {
+    pub fn sync(&mut self, enabled: bool, port: u16, token: &str, ctx: &egui::Context) {
+        if !enabled {
+            self.server = None;
+            self.error = None;
+            return;
+        }
+        if self.server.is_some() || self.error.is_some() {
+            return;
+        }
+        match ApiServer::start(port, token.to_string(), ctx.clone()) {
+            Ok(server) => self.server = Some(server),
+            Err(e) => self.error = Some(format!("Could not listen on port {}: {}", port, e)),
+        }
+    }
+
+    /// Stop the API (and forget a failed start) so the next `sync` starts it with the
+    /// current settings
+    pub fn restart(&mut self) {
+        // Drop the old listener first so a restart on the same port can bind
+        self.server = None;
+        self.error = None;
+    }
+
+    pub fn running_port(&self) -> Option<u16> {
+        self.server.as_ref().map(|server| server.port)
+    }
+
+    /// Whether it runs with a different port or token than given
+    pub fn is_outdated(&self, port: u16, token: &str) -> bool {
+        self.server.as_ref().is_some_and(|server| !server.matches(port, token))
+    }
+
+    pub fn error(&self) -> Option<&str> {
+        self.error.as_deref()
+    }
+
+    pub fn pending_requests(&self) -> Vec<ApiRequest> {
+        self.server.as_ref().map(ApiServer::pending_requests).unwrap_or_default()
+    }
+}
+
+fn handle_connection(stream: TcpStream, token: &str, requests: &Sender<ApiRequest>, ctx: &egui::Context) {
+    let _ = stream.set_nonblocking(false);
+    let _ = stream.set_read_timeout(Some(Duration::from_secs(5)));
+
+    let response = match read_request(&stream) {
+        Ok(request) => dispatch(request, token, requests, ctx),
+        Err(response) => response,
+    };
+    let _ = write_response(&stream, response);
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+fn read_request(stream: &TcpStream) -> std::result::Result<HttpRequest, ApiResponse> {
+    let reader = BufReader::new(stream);
+    read_request_from(reader)
+}
+
+/// Parse a request, reading at most `MAX_HEADER_SIZE` bytes and `MAX_HEADER_LINES` lines
+/// before the body
+fn read_request_from(mut reader: impl BufRead) -> std::result::Result<HttpRequest, ApiResponse> {
+    let bad = |message: &str| ApiResponse::error(400, message);
+    let mut budget = MAX_HEADER_SIZE;
+
+    let request_line = read_header_line(&mut reader, &mut budget)?.unwrap_or_default();
+    let mut parts = request_line.split_whitespace();
+    let method = parts.next().ok_or_else(|| bad("Malformed request line"))?.to_string();
+    let path = parts.next().ok_or_else(|| bad("Malformed request line"))?.to_string();
+
+    let mut authorization = None;
+    let mut content_length = 0;
+    let mut lines = 0;
+    while let Some(line) = read_header_line(&mut reader, &mut budget)? {
+        let line = line.trim_end();
+        if line.is_empty() {
+            break;
+        }
+        lines += 1;
+        if lines > MAX_HEADER_LINES {
+            return Err(ApiResponse::error(431, "Too many request headers"));
+        }
+        if let Some((name, value)) = line.split_once(':') {
+            let value = value.trim();
+            if name.eq_ignore_ascii_case("authorization") {
+                authorization = Some(value.to_string());
+            } else if name.eq_ignore_ascii_case("content-length") {
+                content_length = value.parse().map_err(|_| bad("Invalid Content-Length"))?;
+            }
+        }
+    }
+
+    if content_length > MAX_BODY_SIZE {
+        return Err(ApiResponse::error(413, "Request body too large"));
+    }
+    let mut body = vec![0; content_length];
+    reader.read_exact(&mut body).map_err(|e| bad(&e.to_string()))?;
+
+    Ok(HttpRequest {
+        method,
+        path,
+        authorization,
+        body,
+    })
+}
+
+/// One line of the request head, taken from what is left of `budget`. None at the end of
+/// the stream.
+fn read_header_line(reader: &mut impl BufRead, budget: &mut usize) -> std::result::Result<Option<String>, ApiResponse> {
+    if *budget == 0 {
+        return Err(ApiResponse::error(431, "Request headers too large"));
+    }
+    let mut line = Vec::new();
+    let read = reader
+        .take(*budget as u64)
+        .read_until(b'\n', &mut line)
+        .map_err(|e| ApiResponse::error(400, e.to_string()))?;
+    *budget -= read;
+    if read == 0 {
+        return Ok(None);
+    }
+    if !line.ends_with(b"\n") {
+        // Out of budget in the middle of a line
+        return Err(ApiResponse::error(431, "Request headers too large"));
+    }
+    String::from_utf8(line)
+        .map(Some)
+        .map_err(|_| ApiResponse::error(400, "Request headers are not UTF-8"))
+}
+
+fn dispatch(request: HttpRequest, token: &str, requests: &Sender<ApiRequest>, ctx: &egui::Context) -> ApiResponse {
+    let authorized = request
+        .authorization
+        .as_deref()
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .is_some_and(|given| constant_time_eq(given.trim().as_bytes(), token.as_bytes()));
+    if !authorized {
+        return ApiResponse::error(401, "Missing or invalid bearer token");
+    }
+
+    let command = match parse_route(&request) {
+        Ok(command) => command,
+        Err(response) => return response,
+    };
+
+    let (reply_tx, reply_rx) = channel();
+    if requests.send(ApiRequest { command, reply: reply_tx }).is_err() {
+        return ApiResponse::error(503, "Panel is shutting down");
+    }
+    // Wake the UI thread so the request is handled even when the window is idle
+    ctx.request_repaint();
+
+    reply_rx
+        .recv_timeout(REPLY_TIMEOUT)
+        .unwrap_or_else(|_| ApiResponse::error(503, "Panel did not respond in time"))
+}
+
+/// Compare without returning early at the first difference, so the time taken doesn't
+/// tell how much of a guessed token was right. Only the length can leak.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    if a.len() != b.len() {
+        return false;
+    }
+    a.iter().zip(b).fold(0u8, |diff, (x, y)| diff | (x ^ y)) == 0
+}
+
+fn parse_route(request: &HttpRequest) -> std::result::Result<ApiCommand, ApiResponse> {
+    let path = request.path.split('?').next().unwrap_or_default();
+    let segments: Vec<&str> = path.split('/').filter(|s| !s.is_empty()).collect();
+
+    match (request.method.as_str(), segments.as_slice()) {
+        ("GET", ["servers"]) => Ok(ApiCommand::ListServers),
+        ("GET", ["servers", id]) => Ok(ApiCommand::Status(id.to_string())),
+        ("POST", ["servers", id, "start"]) => Ok(ApiCommand::Start(id.to_string())),
+        ("POST", ["servers", id, "stop"]) => Ok(ApiCommand::Stop(id.to_string())),
+        ("POST", ["servers", id, "command"]) => {
+            let body: Value = serde_json::from_slice(&request.body)
+                .map_err(|_| ApiResponse::error(400, "Expected a JSON body like {\"command\": \"status\"}"))?;
+            let command = body
+                .get("command")
+                .and_then(Value::as_str)
+                .filter(|c| !c.trim().is_empty())
+                .ok_or_else(|| ApiResponse::error(400, "Missing \"command\" field"))?;
+            Ok(ApiCommand::Command(id.to_string(), command.trim().to_string()))
+        }
+        _ => Err(ApiResponse::error(404, "Unknown endpoint")),
+    }
+}
+
+fn write_response(mut stream: &TcpStream, response: ApiResponse) -> Result<()> {
+    let reason = match response.status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        409 => "Conflict",
+        413 => "Payload Too Large",
+        431 => "Request Header Fields Too Large",
+        503 => "Service Unavailable",
+        _ => "Internal Server Error",
+    };
+    let body = response.body.to_string();
+    write!(
+        stream,
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        response.status,
+        reason,
+        body.len(),
+        body
+    )?;
+    stream.flush()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status_of(raw: &[u8]) -> u16 {
+        match read_request_from(raw) {
+            Ok(_) => 200,
+            Err(response) => response.status,
+        }
+    }
+
+    #[test]
+    fn reads_a_normal_request() {
+        let request = read_request_from(
+            &b"POST /servers/a/command HTTP/1.1\r\nAuthorization: Bearer abc\r\nContent-Length: 2\r\n\r\n{}"[..],
+        )
+        .unwrap_or_else(|_| panic!("request was rejected"));
+        assert_eq!(request.method, "POST");
+        assert_eq!(request.path, "/servers/a/command");
+        assert_eq!(request.authorization.as_deref(), Some("Bearer abc"));
+        assert_eq!(request.body, b"{}");
+    }
+
+    #[test]
+    fn rejects_oversized_headers() {
+        let long = format!("GET / HTTP/1.1\r\nX-Long: {}\r\n\r\n", "a".repeat(MAX_HEADER_SIZE));
+        assert_eq!(status_of(long.as_bytes()), 431);
+
+        let endless = "a".repeat(MAX_HEADER_SIZE * 4);
+        assert_eq!(status_of(endless.as_bytes()), 431);
+
+        let many = format!("GET / HTTP/1.1\r\n{}\r\n", "X: y\r\n".repeat(MAX_HEADER_LINES + 1));
+        assert_eq!(status_of(many.as_bytes()), 431);
+    }
+
+    #[test]
+    fn rejects_bad_requests() {
+        assert_eq!(status_of(b""), 400);
+        assert_eq!(status_of(b"GET / HTTP/1.1\r\nContent-Length: x\r\n\r\n"), 400);
+        let big = format!("POST / HTTP/1.1\r\nContent-Length: {}\r\n\r\n", MAX_BODY_SIZE + 1);
+        assert_eq!(status_of(big.as_bytes()), 413);
+    }
+
+    #[test]
+    fn compares_tokens() {
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secre"));
+        assert!(!constant_time_eq(b"", b"secret"));
+    }
+
+    #[test]
+    fn limits_connections() {
+        let count = Arc::new(AtomicUsize::new(0));
+        let slots: Vec<_> = (0..MAX_CONNECTIONS).map(|_| ConnectionSlot::take(&count).unwrap()).collect();
+        assert!(ConnectionSlot::take(&count).is_none());
+        drop(slots);
+        assert_eq!(count.load(Ordering::Acquire), 0);
+        assert!(ConnectionSlot::take(&count).is_some());
+    }
+}
@@ -0,0 +1,193 @@
+use crate::server::{ImportMode, ServerEntry, ServerList};
+use anyhow::{anyhow, Context, Result};
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+// Layout of a bundle zip: the server list as servers.json, plus each server's config
+// under configs/<server id>/ when included
+const LIST_ENTRY: &str = "servers.json";
+const CONFIG_DIR: &str = "configs";
+const CONFIG_FILE: &str = "ServerConfig.toml";
+
+pub struct BundleExport {
+    pub servers: usize,
+    pub configs: usize,
+}
+
+/// Write the server list, and optionally every readable ServerConfig.toml, to a zip at `target`
+pub fn export(list: &ServerList, target: &Path, include_configs: bool) -> Result<BundleExport> {
+    let mut zip = zip::ZipWriter::new(File::create(target)?);
+    let options = zip::write::SimpleFileOptions::default();
+
+    zip.start_file(LIST_ENTRY, options)?;
+    zip.write_all(serde_json::to_string_pretty(list)?.as_bytes())?;
+
+    let mut configs = 0;
+    if include_configs {
+        for server in &list.servers {
+            // A server whose folder is gone is still exported, just without its config
+            let Ok(contents) = fs::read(server.path.join(CONFIG_FILE)) else {
+                continue;
+            };
+            zip.start_file(format!("{}/{}/{}", CONFIG_DIR, server.id, CONFIG_FILE), options)?;
+            zip.write_all(&contents)?;
+            configs += 1;
+        }
+    }
+
+    zip.finish()?;
+    Ok(BundleExport {
+        servers: list.servers.len(),
+        configs,
+    })
+}
+
+/// One server from a bundle, with the folder it will be imported into
+pub struct BundleServer {
+    pub entry: ServerEntry,
+    /// The config stored in the bundle, if it was exported with configs
+    pub config: Option<String>,
+    /// Unticked entries are left out of the import
+    pub include: bool,
+}
+
+impl BundleServer {
+    /// Whether the folder exists on this machine. Paths from another machine usually don't.
+    pub fn folder_exists(&self) -> bool {
+        self.entry.path.is_dir()
+    }
+
+    /// Whether importing this entry would replace a ServerConfig.toml already in its folder
+    pub fn has_local_config(&self) -> bool {
+        self.entry.path.join(CONFIG_FILE).exists()
+    }
+
+    /// Whether the entry can be imported as is: the folder exists and has a config, or
+    /// the bundle brings one
+    pub fn is_importable(&self) -> bool {
+        self.folder_exists() && (self.config.is_some() || self.has_local_config())
+    }
+}
+
+/// Read a bundle written by `export`
+pub fn read(path: &Path) -> Result<Vec<BundleServer>> {
+    let mut archive = zip::ZipArchive::new(File::open(path)?)?;
+
+    let list: ServerList = {
+        let mut file = archive
+            .by_name(LIST_ENTRY)
+            .map_err(|_| anyhow!("Not a server bundle: {} is missing", LIST_ENTRY))?;
+        let mut contents = String::new();
+        file.read_to_string(&mut contents)?;
+        serde_json::from_str(&contents).context("servers.json in the bundle is invalid")?
+    };
+
+    let mut configs = HashMap::new();
+    for server in &list.servers {
+        let name = format!("{}/{}/{}", CONFIG_DIR, server.id, CONFIG_FILE);
+        if let Ok(mut file) = archive.by_name(&name) {
+            let mut contents = String::new();
+            file.read_to_string(&mut contents)?;
+            configs.insert(server.id.clone(), contents);
+        }
+    }
+
+    Ok(list
+        .servers
+        .into_iter()
+        .map(|entry| {
+            let config = configs.remove(&entry.id);
+            let mut server = BundleServer {
+                entry,
+                config,
+                include: true,
+            };
+            server.include = server.is_importable();
+            server
+        })
+        .collect())
+}
+
+/// Point every entry whose folder doesn't exist at the folder of the same name inside
+/// `base`, if there is one. Returns how many entries were remapped.
+pub fn remap_missing(servers: &mut [BundleServer], base: &Path) -> usize {
+    let mut remapped = 0;
+    for server in servers.iter_mut().filter(|s| !s.folder_exists()) {
+        let Some(folder) = server.entry.path.file_name() else {
+            continue;
+        };
+        let candidate = base.join(folder);
+        if candidate.is_dir() {
+            set_folder(server, candidate);
+            remapped += 1;
+        }
+    }
+    remapped
+}
+
+/// Use `path` as the server's folder on this machine
+pub fn set_folder(server: &mut BundleServer, path: PathBuf) {
+    server.entry.path = path;
+    server.include = server.is_importable();
+}
+
+/// A server bundle being imported, while folders are matched up in the import window
+pub struct BundleImport {
+    pub servers: Vec<BundleServer>,
+    /// Replace ServerConfig.toml files already in the target folders with the bundled ones
+    pub overwrite_configs: bool,
+}
+
+/// Result of writing a bundle's configs, before its entries are added to the list
+pub struct WrittenBundle {
+    /// Included servers, to add to the list
+    pub entries: Vec<ServerEntry>,
+    /// Configs written into server folders
+    pub configs: usize,
+    /// Servers unticked in the import window
+    pub left_out: usize,
+}
+
+impl BundleImport {
+    /// Write the bundled configs and hand back the entries to add to `list`. A merge skips
+    /// servers already in the list, so their configs are left as they are too.
+    pub fn write(mut self, list: &ServerList, mode: ImportMode) -> Result<WrittenBundle> {
+        if mode == ImportMode::Merge {
+            for server in &mut self.servers {
+                if list.servers.iter().any(|s| s.path == server.entry.path) {
+                    server.config = None;
+                }
+            }
+        }
+        let configs = write_configs(&self.servers, self.overwrite_configs)?;
+        let left_out = self.servers.iter().filter(|s| !s.include).count();
+        let entries = self.servers.into_iter().filter(|s| s.include).map(|s| s.entry).collect();
+        Ok(WrittenBundle { entries, configs, left_out })
+    }
+}
+
+/// Write the bundled configs of the included servers into their folders. Existing
+/// configs are only replaced with `overwrite`, after a backup. Returns how many were written.
+pub fn write_configs(servers: &[BundleServer], overwrite: bool) -> Result<usize> {
+    let mut written = 0;
+    for server in servers.iter().filter(|s| s.include) {
+        let Some(contents) = &server.config else {
+            continue;
+        };
+        if server.has_local_config() {
+            if !overwrite {
+                continue;
+            }
+            server
+                .entry
+                .backup_config()
+                .with_context(|| format!("Could not back up the config of {}", server.entry.display_name()))?;
+        }
+        fs::write(server.entry.path.join(CONFIG_FILE), contents)
+            .with_context(|| format!("Could not write the config of {}", server.entry.display_name()))?;
+        written += 1;
+    }
+    Ok(written)
+}
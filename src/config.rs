@@ -1,14 +1,17 @@
 use serde::{Deserialize, Serialize};
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
 pub struct ServerConfig {
     #[serde(rename = "General")]
     pub general: GeneralConfig,
     #[serde(rename = "Misc")]
     pub misc: MiscConfig,
+    /// Sections the panel doesn't edit (newer BeamMP options, plugin settings), written back unchanged
+    #[serde(flatten)]
+    pub extra: toml::Table,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GeneralConfig {
     #[serde(rename = "Port")]
     pub port: u16,
@@ -40,14 +43,20 @@ pub struct GeneralConfig {
     pub description: String,
     #[serde(rename = "ResourceFolder")]
     pub resource_folder: String,
+    /// Keys the panel doesn't know, written back unchanged
+    #[serde(flatten)]
+    pub extra: toml::Table,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct MiscConfig {
     #[serde(rename = "ImScaredOfUpdates")]
     pub im_scared_of_updates: bool,
     #[serde(rename = "UpdateReminderTime")]
     pub update_reminder_time: String,
+    /// Keys the panel doesn't know, written back unchanged
+    #[serde(flatten)]
+    pub extra: toml::Table,
 }
 
 impl Default for GeneralConfig {
@@ -68,6 +77,7 @@ impl Default for GeneralConfig {
             map: "/levels/gridmap_v2/info.json".to_string(),
             description: "BeamMP Default Description".to_string(),
             resource_folder: "Resources".to_string(),
+            extra: toml::Table::new(),
         }
     }
 }
@@ -77,16 +87,78 @@ impl Default for MiscConfig {
         Self {
             im_scared_of_updates: true,
             update_reminder_time: "30s".to_string(),
+            extra: toml::Table::new(),
         }
     }
 }
 
-impl Default for ServerConfig {
-    fn default() -> Self {
-        Self {
-            general: GeneralConfig::default(),
-            misc: MiscConfig::default(),
-        }
+/// Check that an AuthKey looks like a key from the BeamMP Keymaster, which are
+/// UUIDs (8-4-4-4-12 hex digits). Returns what is wrong with it otherwise.
+pub fn validate_auth_key(key: &str) -> Result<(), &'static str> {
+    if key.trim().is_empty() {
+        return Err("Auth key is empty, the server can't start without one");
     }
+    if key.trim() != key {
+        return Err("Auth key has leading or trailing spaces");
+    }
+
+    let groups: Vec<&str> = key.split('-').collect();
+    let lengths_match = groups.len() == 5
+        && groups.iter().map(|g| g.len()).eq([8, 4, 4, 4, 12]);
+    if !lengths_match || !groups.iter().all(|g| g.chars().all(|c| c.is_ascii_hexdigit())) {
+        return Err("Auth key doesn't look like a Keymaster key (xxxxxxxx-xxxx-xxxx-xxxx-xxxxxxxxxxxx)");
+    }
+
+    Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn accepts_keymaster_key() {
+        assert_eq!(validate_auth_key("0a1b2c3d-4e5f-6a7b-8c9d-0e1f2a3b4c5d"), Ok(()));
+        assert_eq!(validate_auth_key("0A1B2C3D-4E5F-6A7B-8C9D-0E1F2A3B4C5D"), Ok(()));
+    }
+
+    #[test]
+    fn rejects_malformed_keys() {
+        for key in [
+            "",
+            "   ",
+            " 0a1b2c3d-4e5f-6a7b-8c9d-0e1f2a3b4c5d",
+            "0a1b2c3d-4e5f-6a7b-8c9d-0e1f2a3b4c5d\n",
+            "0a1b2c3-4e5f-6a7b-8c9d-0e1f2a3b4c5d",
+            "0a1b2c3d-4e5f-6a7b-8c9d-0e1f2a3b4c5d0",
+            "0a1b2c3d-4e5f6a7b-8c9d-0e1f2a3b4c5d",
+            "0a1b2c3d-4e5f-6a7b-8c9d-0e1f2a3b4c5d-0000",
+            "0a1b2c3d-4e5f-6a7b-8c9d-0e1f2a3b4c5g",
+            "0a1b2c3d_4e5f_6a7b_8c9d_0e1f2a3b4c5d",
+        ] {
+            assert!(validate_auth_key(key).is_err(), "{:?} was accepted", key);
+        }
+    }
+
+    #[test]
+    fn keeps_unknown_sections_and_keys() {
+        let mut text = toml::to_string(&ServerConfig::default()).unwrap();
+        text = text.replace("[General]\n", "[General]\nAllowCustomVehicles = false\n");
+        text = text.replace("[Misc]\n", "[Misc]\nTelemetry = \"off\"\n");
+        text.push_str("\n[HTTP]\nHTTPServerEnabled = true\nHTTPServerPort = 8080\nHTTPServerIP = \"127.0.0.1\"\n");
+
+        let config: ServerConfig = toml::from_str(&text).unwrap();
+        assert_eq!(config.general.extra["AllowCustomVehicles"], toml::Value::Boolean(false));
+        assert_eq!(config.misc.extra["Telemetry"], toml::Value::String("off".into()));
+        assert_eq!(config.extra["HTTP"]["HTTPServerPort"], toml::Value::Integer(8080));
+
+        let saved = toml::to_string_pretty(&config).unwrap();
+        let reloaded: toml::Table = toml::from_str(&saved).unwrap();
+        assert_eq!(reloaded["General"]["AllowCustomVehicles"], toml::Value::Boolean(false));
+        assert_eq!(reloaded["Misc"]["Telemetry"], toml::Value::String("off".into()));
+        assert_eq!(reloaded["HTTP"]["HTTPServerEnabled"], toml::Value::Boolean(true));
+        assert_eq!(reloaded["HTTP"]["HTTPServerPort"], toml::Value::Integer(8080));
+        assert_eq!(reloaded["HTTP"]["HTTPServerIP"], toml::Value::String("127.0.0.1".into()));
+        assert_eq!(toml::from_str::<ServerConfig>(&saved).unwrap(), config);
+    }
+}
@@ -0,0 +1,230 @@
+use anyhow::{anyhow, Result};
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+// Progress is reported at most this often, in bytes, so big files don't flood the channel
+const PROGRESS_STEP: u64 = 256 * 1024;
+
+/// Agent for all panel downloads. `timeout` applies to connecting and to each read, so a
+/// large file on a slow but steady connection is never cut off.
+pub fn agent(timeout: Duration) -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout_connect(timeout)
+        .timeout_read(timeout)
+        .user_agent(concat!("BeamMP-Panel/", env!("CARGO_PKG_VERSION")))
+        .build()
+}
+
+pub enum DownloadState {
+    Queued,
+    Downloading { received: u64, total: Option<u64> },
+    /// Downloaded, waiting to be installed
+    Downloaded,
+    Installed(String),
+    Failed(String),
+}
+
+pub struct DownloadItem {
+    pub url: String,
+    pub state: DownloadState,
+}
+
+enum Message {
+    Progress { index: usize, received: u64, total: Option<u64> },
+    Finished { index: usize, result: Result<PathBuf, String> },
+}
+
+/// A list of URLs downloaded one after another on a background thread into a temporary
+/// folder, which is removed again when the batch is dropped.
+pub struct DownloadBatch {
+    pub items: Vec<DownloadItem>,
+    rx: Receiver<Message>,
+    cancel: Arc<AtomicBool>,
+    dir: PathBuf,
+}
+
+impl DownloadBatch {
+    pub fn start(urls: Vec<String>, timeout: Duration, ctx: &egui::Context) -> Self {
+        let dir = std::env::temp_dir().join(format!("beammp-panel-downloads-{}", uuid::Uuid::new_v4().simple()));
+        let (tx, rx) = channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let thread_urls = urls.clone();
+        let thread_dir = dir.clone();
+        let thread_cancel = cancel.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let agent = agent(timeout);
+            for (index, url) in thread_urls.iter().enumerate() {
+                let result = if thread_cancel.load(Ordering::Relaxed) {
+                    Err(anyhow!("Cancelled"))
+                } else {
+                    download(&agent, url, &thread_dir.join(index.to_string()), index, &tx, &thread_cancel, &ctx)
+                };
+                let _ = tx.send(Message::Finished { index, result: result.map_err(|e| e.to_string()) });
+                ctx.request_repaint();
+            }
+            if thread_cancel.load(Ordering::Relaxed) {
+                let _ = fs::remove_dir_all(&thread_dir);
+            }
+        });
+
+        Self {
+            items: urls
+                .into_iter()
+                .map(|url| DownloadItem { url, state: DownloadState::Queued })
+                .collect(),
+            rx,
+            cancel,
+            dir,
+        }
+    }
+
+    /// Apply progress from the download thread. Returns the items that finished
+    /// downloading since the last call and are ready to install.
+    pub fn poll(&mut self) -> Vec<(usize, PathBuf)> {
+        let mut downloaded = Vec::new();
+        while let Ok(message) = self.rx.try_recv() {
+            match message {
+                Message::Progress { index, received, total } => {
+                    self.items[index].state = DownloadState::Downloading { received, total };
+                }
+                Message::Finished { index, result: Ok(path) } => {
+                    self.items[index].state = DownloadState::Downloaded;
+                    downloaded.push((index, path));
+                }
+                Message::Finished { index, result: Err(e) } => {
+                    self.items[index].state = DownloadState::Failed(e);
+                }
+            }
+        }
+        downloaded
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.items
+            .iter()
+            .all(|item| matches!(item.state, DownloadState::Installed(_) | DownloadState::Failed(_)))
+    }
+
+    /// Stop after the current chunk; the remaining URLs are marked as cancelled
+    pub fn cancel(&self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
+
+impl Drop for DownloadBatch {
+    fn drop(&mut self) {
+        self.cancel();
+        // A thread still running removes the folder itself once it sees the cancel
+        if self.is_finished() {
+            let _ = fs::remove_dir_all(&self.dir);
+        }
+    }
+}
+
+fn download(
+    agent: &ureq::Agent,
+    url: &str,
+    dir: &Path,
+    index: usize,
+    tx: &Sender<Message>,
+    cancel: &AtomicBool,
+    ctx: &egui::Context,
+) -> Result<PathBuf> {
+    let response = agent.get(url).call().map_err(|e| match e {
+        ureq::Error::Status(code, response) => anyhow!("HTTP {} {}", code, response.status_text()),
+        ureq::Error::Transport(e) => anyhow!("{}", e),
+    })?;
+
+    let name = response
+        .header("Content-Disposition")
+        .and_then(disposition_file_name)
+        .or_else(|| url_file_name(response.get_url()))
+        .unwrap_or_else(|| "mod.zip".to_string());
+    let total = response.header("Content-Length").and_then(|len| len.parse().ok());
+
+    fs::create_dir_all(dir)?;
+    let path = dir.join(&name);
+    let mut file = File::create(&path)?;
+    let mut reader = response.into_reader();
+    let mut buf = vec![0; 64 * 1024];
+    let mut received = 0;
+    let mut reported = 0;
+    loop {
+        if cancel.load(Ordering::Relaxed) {
+            return Err(anyhow!("Cancelled"));
+        }
+        let read = reader.read(&mut buf)?;
+        if read == 0 {
+            break;
+        }
+        file.write_all(&buf[..read])?;
+        received += read as u64;
+        if received - reported >= PROGRESS_STEP {
+            reported = received;
+            let _ = tx.send(Message::Progress { index, received, total });
+            ctx.request_repaint();
+        }
+    }
+    if total.is_some_and(|total| received < total) {
+        return Err(anyhow!("The connection closed after {} of {} bytes", received, total.unwrap_or_default()));
+    }
+    Ok(path)
+}
+
+/// File name from a header like `attachment; filename="mod.zip"`
+fn disposition_file_name(header: &str) -> Option<String> {
+    let value = header
+        .split(';')
+        .map(str::trim)
+        .find_map(|part| part.strip_prefix("filename="))?;
+    safe_file_name(value.trim_matches('"'))
+}
+
+/// Last path segment of a URL, without query string and with %XX escapes decoded
+fn url_file_name(url: &str) -> Option<String> {
+    let path = url.split(['?', '#']).next()?;
+    let segment = path.rsplit('/').next()?;
+    safe_file_name(&percent_decode(segment))
+}
+
+/// `name` if it can be used as a file name as is, without any folder parts
+fn safe_file_name(name: &str) -> Option<String> {
+    let name = name.trim();
+    let valid = !name.is_empty()
+        && name != "."
+        && name != ".."
+        && !name.contains(['/', '\\', ':', '*', '?', '"', '<', '>', '|']);
+    valid.then(|| name.to_string())
+}
+
+fn percent_decode(text: &str) -> String {
+    let bytes = text.as_bytes();
+    let mut out = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| std::str::from_utf8(hex).ok())
+            .and_then(|hex| u8::from_str_radix(hex, 16).ok());
+        match escaped {
+            Some(byte) => {
+                out.push(byte);
+                i += 3;
+            }
+            None => {
+                out.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+    String::from_utf8_lossy(&out).into_owned()
+}
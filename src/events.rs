@@ -0,0 +1,145 @@
+use crate::server;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+
+// Keep the most recent events per server so events.json stays small
+const MAX_EVENTS_PER_SERVER: usize = 500;
+
+/// New events are written to events.json at most this often; the rest waits for exit
+const FLUSH_INTERVAL: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum EventKind {
+    Started,
+    Stopped,
+    Crashed,
+    ConfigApplied,
+    ModChanged,
+    Updated,
+    PlayerJoined,
+    PlayerLeft,
+}
+
+impl EventKind {
+    pub const ALL: [EventKind; 8] = [
+        EventKind::Started,
+        EventKind::Stopped,
+        EventKind::Crashed,
+        EventKind::ConfigApplied,
+        EventKind::ModChanged,
+        EventKind::Updated,
+        EventKind::PlayerJoined,
+        EventKind::PlayerLeft,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            EventKind::Started => "Start",
+            EventKind::Stopped => "Stop",
+            EventKind::Crashed => "Crash",
+            EventKind::ConfigApplied => "Config",
+            EventKind::ModChanged => "Mods",
+            EventKind::Updated => "Update",
+            EventKind::PlayerJoined => "Join",
+            EventKind::PlayerLeft => "Leave",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerEvent {
+    /// Unix timestamp in seconds
+    pub timestamp: i64,
+    pub kind: EventKind,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct EventLog {
+    servers: HashMap<String, Vec<ServerEvent>>,
+    /// Events recorded since the last save
+    #[serde(skip)]
+    dirty: bool,
+    #[serde(skip)]
+    last_saved: Option<Instant>,
+}
+
+impl EventLog {
+    fn get_log_path() -> Result<PathBuf> {
+        Ok(server::config_dir()?.join("events.json"))
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::get_log_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::get_log_path()?;
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+
+    pub fn record(&mut self, server_id: &str, kind: EventKind, message: String) {
+        let events = self.servers.entry(server_id.to_string()).or_default();
+        events.push(ServerEvent {
+            timestamp: chrono::Local::now().timestamp(),
+            kind,
+            message,
+        });
+
+        if events.len() > MAX_EVENTS_PER_SERVER {
+            let excess = events.len() - MAX_EVENTS_PER_SERVER;
+            events.drain(0..excess);
+        }
+        self.dirty = true;
+    }
+
+    /// Save unsaved events if the last save was long enough ago. Called every frame, so
+    /// a burst of events (players joining, a restart) costs one write instead of many.
+    pub fn flush_if_due(&mut self) -> Result<()> {
+        if self.last_saved.is_some_and(|at| at.elapsed() < FLUSH_INTERVAL) {
+            return Ok(());
+        }
+        self.flush()
+    }
+
+    /// Whether there are events that still need saving
+    pub fn is_dirty(&self) -> bool {
+        self.dirty
+    }
+
+    /// Save unsaved events now, e.g. on exit
+    pub fn flush(&mut self) -> Result<()> {
+        if !self.dirty {
+            return Ok(());
+        }
+        // Marked as saved even on failure, so a broken disk is retried on the timer
+        // rather than every frame
+        self.dirty = false;
+        self.last_saved = Some(Instant::now());
+        self.save().inspect_err(|_| self.dirty = true)
+    }
+
+    pub fn events_for(&self, server_id: &str) -> &[ServerEvent] {
+        self.servers
+            .get(server_id)
+            .map(|events| events.as_slice())
+            .unwrap_or(&[])
+    }
+
+    pub fn remove_server(&mut self, server_id: &str) {
+        self.servers.remove(server_id);
+        self.dirty = true;
+    }
+}
@@ -0,0 +1,75 @@
+use anyhow::{anyhow, Result};
+use serde_json::Value;
+use std::net::IpAddr;
+use std::time::Duration;
+
+/// A server as it appears in the BeamMP server list
+#[derive(Debug, Clone)]
+pub struct ListedServer {
+    pub name: String,
+    pub players: u32,
+    pub max_players: u32,
+    pub map: String,
+    pub version: String,
+}
+
+/// Look for a server in the public list at `url`. Entries are matched on port and, once the
+/// public IP is known, on address; otherwise on the name with color codes removed.
+pub fn find_server(url: &str, timeout: Duration, name: &str, port: u16, public_ip: Option<IpAddr>) -> Result<Option<ListedServer>> {
+    let response = crate::download::agent(timeout).get(url).call().map_err(|e| match e {
+        ureq::Error::Status(code, response) => anyhow!("The server list answered HTTP {} {}", code, response.status_text()),
+        ureq::Error::Transport(e) => anyhow!("Could not reach the server list ({}). Check your internet connection.", e),
+    })?;
+    let entries: Vec<Value> = serde_json::from_str(&response.into_string()?)
+        .map_err(|e| anyhow!("The server list sent an unexpected answer: {}", e))?;
+
+    let name = strip_color_codes(name);
+    let found = entries.iter().find(|entry| {
+        if field(entry, "port") != port.to_string() {
+            return false;
+        }
+        match public_ip {
+            Some(ip) => field(entry, "ip").parse::<IpAddr>().is_ok_and(|listed| listed == ip),
+            None => strip_color_codes(&field(entry, "sname")) == name,
+        }
+    });
+
+    Ok(found.map(|entry| ListedServer {
+        name: strip_color_codes(&field(entry, "sname")),
+        players: field(entry, "players").parse().unwrap_or(0),
+        max_players: field(entry, "maxplayers").parse().unwrap_or(0),
+        map: field(entry, "map"),
+        version: field(entry, "version"),
+    }))
+}
+
+/// The list sends most numbers as strings, so both are read as text
+fn field(entry: &Value, key: &str) -> String {
+    match entry.get(key) {
+        Some(Value::String(s)) => s.trim().to_string(),
+        Some(Value::Number(n)) => n.to_string(),
+        _ => String::new(),
+    }
+}
+
+/// Server names may contain `^` followed by a color or style character
+fn strip_color_codes(name: &str) -> String {
+    let mut out = String::with_capacity(name.len());
+    let mut chars = name.chars();
+    while let Some(c) = chars.next() {
+        if c == '^' {
+            chars.next();
+        } else {
+            out.push(c);
+        }
+    }
+    out.trim().to_string()
+}
+
+/// The most recent line in which the server reports the backend refusing its auth key
+pub fn auth_error(lines: &[String]) -> Option<&str> {
+    lines.iter().rev().map(String::as_str).find(|line| {
+        let lower = line.to_lowercase();
+        lower.contains("auth key") && (lower.contains("refused") || lower.contains("invalid"))
+    })
+}
@@ -0,0 +1,114 @@
+use crate::ui::console::LineNumbering;
+use std::fs::File;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::PathBuf;
+
+/// Name of the log file BeamMP-Server writes into its working directory
+pub const SERVER_LOG_FILE: &str = "Server.log";
+
+// How much of an existing log is shown when tailing starts
+const INITIAL_TAIL_BYTES: u64 = 64 * 1024;
+// Upper bound per poll, so a huge burst doesn't stall a frame
+const MAX_READ_PER_POLL: u64 = 1024 * 1024;
+
+/// Follows a log file as it grows, like `tail -f`. Handles the file appearing later and
+/// being truncated or replaced (rotation) by starting over from the top.
+pub struct LogTail {
+    pub server_id: String,
+    path: PathBuf,
+    /// Read position, None until the file has been found
+    offset: Option<u64>,
+    /// Text after the last newline, completed by a later read
+    partial: String,
+    pub lines: Vec<String>,
+    pub numbering: LineNumbering,
+    max_lines: usize,
+}
+
+impl LogTail {
+    pub fn new(server_id: String, path: PathBuf, max_lines: usize) -> Self {
+        Self {
+            server_id,
+            path,
+            offset: None,
+            partial: String::new(),
+            lines: Vec::new(),
+            numbering: LineNumbering::new(),
+            max_lines,
+        }
+    }
+
+    /// Read whatever was appended since the last call. Returns whether lines were added.
+    pub fn poll(&mut self) -> bool {
+        let Ok(mut file) = File::open(&self.path) else {
+            return false;
+        };
+        let Ok(len) = file.metadata().map(|m| m.len()) else {
+            return false;
+        };
+
+        let mut skip_first_line = false;
+        let offset = match self.offset {
+            // First time: show only the end of a long log, starting at a whole line
+            None => {
+                let start = len.saturating_sub(INITIAL_TAIL_BYTES);
+                skip_first_line = start > 0;
+                start
+            }
+            Some(offset) if len < offset => {
+                self.partial.clear();
+                self.push_line(format!("--- {} was truncated or replaced, reading from the start ---", SERVER_LOG_FILE));
+                0
+            }
+            Some(offset) => offset,
+        };
+        if offset == len {
+            self.offset = Some(offset);
+            return false;
+        }
+
+        let mut buf = Vec::new();
+        let read = file
+            .seek(SeekFrom::Start(offset))
+            .and_then(|_| file.take(MAX_READ_PER_POLL).read_to_end(&mut buf));
+        if read.is_err() {
+            return false;
+        }
+        self.offset = Some(offset + buf.len() as u64);
+
+        let mut text = std::mem::take(&mut self.partial);
+        text.push_str(&String::from_utf8_lossy(&buf));
+        let mut pieces: Vec<&str> = text.split('\n').collect();
+        // The last piece has no newline yet; keep it for the next read
+        self.partial = pieces.pop().unwrap_or_default().to_string();
+        if skip_first_line && !pieces.is_empty() {
+            pieces.remove(0);
+        }
+
+        let added = !pieces.is_empty();
+        for line in pieces {
+            self.push_line(line.trim_end_matches('\r').to_string());
+        }
+        // Once per read rather than per line, which would be slow with a long scrollback
+        self.trim();
+        added
+    }
+
+    /// Change how many lines are kept, dropping the oldest ones beyond it
+    pub fn set_max_lines(&mut self, max_lines: usize) {
+        self.max_lines = max_lines;
+        self.trim();
+    }
+
+    fn push_line(&mut self, line: String) {
+        self.lines.push(line);
+    }
+
+    fn trim(&mut self) {
+        if self.lines.len() > self.max_lines {
+            let excess = self.lines.len() - self.max_lines;
+            self.lines.drain(..excess);
+            self.numbering.dropped(excess);
+        }
+    }
+}
@@ -1,23 +1,76 @@
 #![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
 
 mod config;
+mod events;
 mod server;
 mod mods;
 mod ui;
 mod process;
+mod settings;
+mod api;
+mod players;
+mod session_log;
+mod schedule;
+mod bundle;
+mod log_tail;
+mod download;
+mod mod_search;
+mod listing;
+mod updates;
+mod notifications;
+mod tray;
+#[cfg(test)]
+mod test_util;
 
 use eframe::egui;
-use server::ServerList;
-use process::ServerProcess;
+use events::{EventKind, EventLog};
+use server::{ImportMode, ImportPreview, ServerList};
+use process::{ExternalProcess, ServerProcess};
+use settings::{AppSettings, WindowGeometry};
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+/// Maximum console lines ingested per frame; the rest wait for the next repaint
+const MAX_LINES_PER_FRAME: usize = 500;
+/// Minimum time between two `list` commands, manual or automatic
+const PLAYER_REFRESH_COOLDOWN: Duration = Duration::from_secs(2);
+/// How often every running server is asked for its players, for the counts in the server list
+const PLAYER_COUNT_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+/// Quiet time after the last config edit before autosave writes it
+const CONFIG_AUTOSAVE_DELAY: Duration = Duration::from_secs(2);
+/// Assume the server is up if no ready line showed up by then (custom builds, changed log format)
+const READY_TIMEOUT: Duration = Duration::from_secs(30);
+/// Highlight notifications for one server are at most this frequent, so a chatty rule doesn't flood the desktop
+const HIGHLIGHT_NOTIFY_INTERVAL: Duration = Duration::from_secs(30);
+/// How often to poll a running server for output when nothing else triggers a repaint
+const OUTPUT_POLL_INTERVAL: Duration = Duration::from_millis(100);
+const DEFAULT_WINDOW_SIZE: [f32; 2] = [1200.0, 700.0];
+/// How long the status bar offers to undo a mod change
+const UNDO_OFFER_DURATION: Duration = Duration::from_secs(15);
+/// Mod changes remembered for undo
+const MOD_UNDO_HISTORY: usize = 20;
 
 fn main() -> eframe::Result<()> {
     // Load and set the window icon
     let icon = load_icon();
     
+    let settings = AppSettings::load().unwrap_or_default();
+
     let mut viewport_builder = egui::ViewportBuilder::default()
-        .with_inner_size([1200.0, 700.0])
+        .with_inner_size(DEFAULT_WINDOW_SIZE)
         .with_title("BeamMP Panel");
+
+    // Restore the last window geometry; an off-screen position is fixed up on the first frame
+    if let Some(geometry) = settings.window {
+        viewport_builder = viewport_builder
+            .with_inner_size(geometry.size)
+            .with_maximized(geometry.maximized);
+        if let Some(position) = geometry.position {
+            viewport_builder = viewport_builder.with_position(position);
+        }
+    }
     
     if let Some(icon_data) = icon {
         viewport_builder = viewport_builder.with_icon(Arc::new(icon_data));
@@ -31,7 +84,7 @@ fn main() -> eframe::Result<()> {
     eframe::run_native(
         "BeamMP Panel",
         options,
-        Box::new(|_cc| Ok(Box::new(BeamMpManagerApp::new()))),
+        Box::new(|_cc| Ok(Box::new(BeamMpManagerApp::new(settings)))),
     )
 }
 
@@ -61,25 +114,251 @@ struct BeamMpManagerApp {
     mods_cache: Option<ModsCache>,
     current_mod_type: ModType,
     current_mod_filter: ModFilter,
+    mod_search: String,
     delete_confirmation: Option<DeleteConfirmation>,
-    running_process: Option<RunningProcess>,
-    terminal_output: Vec<String>,
-    auto_scroll_terminal: bool,
-    player_list: Vec<String>,
-    kick_player_name: String,
-    kick_reason: String,
-    broadcast_message: String,
+    mod_copy: Option<ModCopy>,
+    mod_trash_view: Option<ui::mod_trash::TrashView>,
+    /// Latest last
+    mod_history: Vec<ModUndo>,
+    running: Vec<RunningProcess>,
+    /// Servers started outside the panel that are being watched
+    attached: Vec<AttachedProcess>,
+    /// Console output per server id, kept after the run ends until cleared or restarted
+    consoles: HashMap<String, ConsoleBuffer>,
+    console_view: ui::console::ConsoleView,
+    /// Server.log of the selected server, while the console shows it
+    server_log_tail: Option<log_tail::LogTail>,
+    control_inputs: ui::control_tab::ControlInputs,
     mod_details_view: Option<ModDetailsView>,
+    config_backups_view: Option<ConfigBackupsView>,
+    raw_config: Option<ui::config_tab::RawConfigEditor>,
+    share_view: Option<ui::share::ShareView>,
+    setup_wizard: Option<ui::setup_wizard::SetupWizard>,
+    rename_server: Option<RenameServer>,
+    macro_editor: Option<ui::control_tab::MacroEditor>,
+    import_confirmation: Option<ImportPreview>,
+    bundle_import: Option<bundle::BundleImport>,
+    mod_downloads: Option<ui::mod_downloads::ModDownloads>,
+    mod_repository_search: mod_search::ModSearch,
+    disk_usage: HashMap<String, DiskUsageState>,
+    server_update_check: updates::UpdateCheck,
+    server_update: Option<updates::ServerUpdate>,
+    event_log: EventLog,
+    event_filter: EventFilter,
+    show_settings: bool,
+    port_conflict: Option<PortConflictPrompt>,
+    unsaved_changes: Option<UnsavedChangesPrompt>,
+    /// Unsaved config edits were dealt with, so the next close request goes through
+    close_confirmed: bool,
+    /// Asks what to do with running servers before the panel closes
+    quit_prompt: bool,
+    /// Running servers were dealt with, so the next close request goes through
+    quit_confirmed: bool,
+    /// "Stop and Quit" was picked: close once the last server has stopped
+    quit_after_stop: bool,
+    config_autosave: Option<PendingAutosave>,
+    settings: AppSettings,
+    console_detached: bool,
+    mod_file_cache: mods::ModFileCache,
+    /// Levels the selected server's Map setting can use, for the Config tab
+    level_catalog: Option<LevelCatalog>,
+    api: api::ApiHost,
+    /// Why the panel data directory can't be written, if it can't
+    config_dir_error: Option<String>,
+    /// Mods found in both trees at startup, waiting for the user to pick a copy
+    split_mods_prompt: Option<Vec<SplitModEntry>>,
+    console_input: ui::console::ConsoleInput,
+    console_filter: ui::console::ConsoleFilter,
+    /// Whether the restored window position has been checked against the current monitor
+    window_position_checked: bool,
+    /// Shown once at startup when servers.json was damaged or unreadable
+    server_list_problem: Option<String>,
+    /// UI scale and console font size currently applied to the context
+    applied_appearance: Option<(f32, Option<f32>)>,
+    /// When each server last sent a notification for a highlight rule
+    highlight_notified: HashMap<String, Instant>,
+    tray: Option<tray::Tray>,
+    /// Hide the window once the tray icon is up ("Start minimized")
+    hide_on_start: bool,
+    /// Quit was picked in the tray menu, so closing must not just hide the window
+    quit_requested: bool,
+}
+
+struct SplitModEntry {
+    server_id: String,
+    server_name: String,
+    split: mods::SplitMod,
+}
+
+struct PortConflictPrompt {
+    server_index: usize,
+    port: u16,
+    used_by: Vec<String>,
+    free_port: Option<u16>,
+}
+
+/// Asks what to do with a server's unapplied config edits before leaving it
+struct UnsavedChangesPrompt {
+    server_index: usize,
+    /// What happens once the edits are saved or discarded
+    then: AfterUnsaved,
+}
+
+enum AfterUnsaved {
+    SelectServer(usize),
+    CloseWindow,
+}
+
+/// Config edits waiting for the autosave delay to pass without further changes
+struct PendingAutosave {
+    server_id: String,
+    /// The edited config when it last changed
+    seen: config::ServerConfig,
+    changed_at: Instant,
+    /// Saving was tried for `seen` (it may have been refused, e.g. for a port conflict)
+    attempted: bool,
+}
+
+struct LevelCatalog {
+    server_id: String,
+    resource_folder: String,
+    levels: Vec<mods::AvailableLevel>,
 }
 
 struct ModDetailsView {
     mod_name: String,
     details: Option<mods::ModDetailInfo>,
+    contents: ui::zip_tree::ZipTreeView,
+}
+
+/// Lifecycle of a server as the panel sees it. Advanced by `update_terminal`, which polls
+/// the process every frame.
+#[derive(Clone, Copy, PartialEq)]
+enum ServerState {
+    Stopped,
+    /// Launched, but not yet reporting that it is listening (see READY_TIMEOUT)
+    Starting,
+    Running,
+    /// `exit` was sent; the process is killed if it is still running at the deadline
+    Stopping { deadline: Instant },
+    /// Started outside the panel and only watched (see AttachedProcess)
+    Attached,
+}
+
+impl ServerState {
+    fn label(self) -> &'static str {
+        match self {
+            ServerState::Stopped => "stopped",
+            ServerState::Starting => "starting",
+            ServerState::Running => "running",
+            ServerState::Stopping { .. } => "stopping",
+            ServerState::Attached => "attached",
+        }
+    }
+}
+
+/// A server the panel found running but didn't start. Read-only: no commands can be sent,
+/// and its output is only available through Server.log.
+struct AttachedProcess {
+    server_id: String,
+    process: ExternalProcess,
+}
+
+/// Size of a server folder, calculated on request
+enum DiskUsageState {
+    Calculating(std::sync::mpsc::Receiver<server::DiskUsage>),
+    Done(server::DiskUsage),
+}
+
+struct RenameServer {
+    server_index: usize,
+    name: String,
+}
+
+struct ConfigBackupsView {
+    server_index: usize,
+    backups: Vec<server::ConfigBackup>,
 }
 
 struct RunningProcess {
     server_id: String,
     process: ServerProcess,
+    start_time: Instant,
+    state: ServerState,
+    player_list: Vec<String>,
+    player_parser: players::PlayerListParser,
+    /// Players who joined and left during this run
+    player_history: players::PlayerHistory,
+    chat: players::ChatLog,
+    /// Whether a `list` response has arrived yet; until then the player count is unknown
+    players_known: bool,
+    last_player_refresh: Option<Instant>,
+    /// Scheduled restarts are counted from here: the start, or the last schedule change or skip
+    restart_anchor: chrono::DateTime<chrono::Local>,
+    /// Last countdown point announced to players, in seconds before the restart
+    restart_warned: Option<i64>,
+    /// End of a "Restart with Warning" countdown
+    manual_restart_at: Option<chrono::DateTime<chrono::Local>>,
+    /// Start the server again once the current stop completes
+    restart_after_stop: bool,
+    /// Config as saved when the server started; a different saved config needs a restart
+    started_config: Option<config::ServerConfig>,
+}
+
+impl RunningProcess {
+    /// The next restart, manual or scheduled, with the seconds before it at which players
+    /// are warned
+    fn next_restart(&self, server: &server::ServerEntry, countdown: &[u32]) -> Option<(chrono::DateTime<chrono::Local>, Vec<i64>)> {
+        let manual = self.manual_restart_at.map(|at| (at, schedule::countdown_points(countdown)));
+        let scheduled = server.restart.next_restart(self.restart_anchor)
+            .map(|at| (at, server.restart.warning_points(countdown)));
+        manual.into_iter().chain(scheduled).min_by_key(|(at, _)| *at)
+    }
+}
+
+/// Console output of one server's current or most recent run
+struct ConsoleBuffer {
+    /// The last lines up to the scrollback setting, for display
+    lines: Vec<String>,
+    /// Local time each of `lines` was received, kept apart so logs and exports stay as the server wrote them
+    received: Vec<chrono::NaiveTime>,
+    /// Full output of the run on disk, None if the file couldn't be created
+    log: Option<session_log::SessionLog>,
+    output_rate: ui::console::OutputRate,
+    numbering: ui::console::LineNumbering,
+}
+
+impl ConsoleBuffer {
+    fn new(log: Option<session_log::SessionLog>) -> Self {
+        Self {
+            lines: Vec::new(),
+            received: Vec::new(),
+            log,
+            output_rate: ui::console::OutputRate::default(),
+            numbering: ui::console::LineNumbering::new(),
+        }
+    }
+
+    fn push(&mut self, lines: Vec<String>, max_lines: usize) {
+        if let Some(log) = &mut self.log {
+            log.append(&lines);
+        }
+        let now = chrono::Local::now().time();
+        self.received.extend(std::iter::repeat_n(now, lines.len()));
+        self.lines.extend(lines);
+        if self.lines.len() > max_lines {
+            let excess = self.lines.len() - max_lines;
+            self.lines.drain(0..excess);
+            self.received.drain(0..excess);
+            self.numbering.dropped(excess);
+        }
+    }
+
+    fn clear(&mut self) {
+        self.lines.clear();
+        self.received.clear();
+        self.numbering = ui::console::LineNumbering::new();
+    }
 }
 
 #[derive(PartialEq)]
@@ -87,6 +366,14 @@ enum Tab {
     Config,
     Mods,
     Control,
+    Schedule,
+    Events,
+}
+
+#[derive(Default)]
+struct EventFilter {
+    search: String,
+    hidden_kinds: HashSet<EventKind>,
 }
 
 struct StatusMessage {
@@ -98,6 +385,21 @@ struct ModsCache {
     server_id: String,
     mods: Vec<mods::ModEntry>,
     mod_type: ModType,
+    /// Groups of identical mods (indices into `mods`), first entry is the one to keep
+    duplicates: Vec<Vec<usize>>,
+    /// Levels/vehicles shipped by more than one enabled client mod
+    conflicts: Vec<mods::Conflict>,
+    /// Archive contents per client mod, aligned with `mods` (None for server mods or unreadable ZIPs)
+    details: Vec<Option<mods::ModDetailInfo>>,
+    /// Indices into `mods` ticked for bulk actions; reset whenever the cache is rebuilt
+    selected: HashSet<usize>,
+    /// The resource folder that was scanned and what's wrong with it, if anything
+    resource_folder: String,
+    folder_issue: Option<mods::ResourceFolderIssue>,
+    /// Mods of this type in the default Resources folder when the configured one has none
+    default_folder_mods: Vec<mods::ModEntry>,
+    /// Server mods were also looked for inside folders without Lua scripts
+    nested: bool,
 }
 
 #[derive(PartialEq, Clone, Copy)]
@@ -106,6 +408,75 @@ enum ModType {
     Client,
 }
 
+impl ModType {
+    /// Folder inside Resources holding this kind of mod
+    fn folder(self) -> &'static str {
+        match self {
+            ModType::Server => "Server",
+            ModType::Client => "Client",
+        }
+    }
+
+    fn scan(self, server_path: &std::path::Path, resource_folder: &str, nested: bool) -> anyhow::Result<Vec<mods::ModEntry>> {
+        match self {
+            ModType::Server => mods::scan_server_mods(server_path, resource_folder, nested),
+            ModType::Client => mods::scan_client_mods(server_path, resource_folder),
+        }
+    }
+}
+
+/// A change to a server's mods that Undo can reverse
+struct ModUndo {
+    server_id: String,
+    mod_type: ModType,
+    change: ModChange,
+    at: Instant,
+}
+
+enum ModChange {
+    /// Mods moved into (`enabled`) or out of the enabled tree
+    SetEnabled { names: Vec<String>, enabled: bool },
+    /// Mods moved into the server's trash, by where they are in it
+    Deleted(Vec<PathBuf>),
+}
+
+impl ModUndo {
+    fn describe(&self) -> String {
+        match &self.change {
+            ModChange::SetEnabled { names, enabled: true } => format!("enable {} mod(s)", names.len()),
+            ModChange::SetEnabled { names, enabled: false } => format!("disable {} mod(s)", names.len()),
+            ModChange::Deleted(paths) => format!("delete {} mod(s)", paths.len()),
+        }
+    }
+}
+
+/// Mods of the selected server being copied to another one
+struct ModCopy {
+    source_server_id: String,
+    mod_type: ModType,
+    /// Relative path and location of each mod
+    mods: Vec<(String, PathBuf)>,
+    target_server_id: Option<String>,
+    /// Mods the target already has, once Copy was clicked and some were found
+    existing: Option<Vec<String>>,
+}
+
+impl ModCopy {
+    /// Mods the target server already has, enabled or disabled
+    fn existing_in_target(&self, servers: &[server::ServerEntry]) -> Vec<String> {
+        let Some(target) = self.target_server_id.as_ref()
+            .and_then(|id| servers.iter().find(|s| &s.id == id))
+        else {
+            return Vec::new();
+        };
+        let resource_folder = target.get_resource_folder();
+        self.mods.iter()
+            .filter(|(name, _)| mods::copy_target(&target.path, &resource_folder, self.mod_type.folder(), name).1)
+            .map(|(name, _)| name.clone())
+            .collect()
+    }
+}
+
 #[derive(PartialEq, Clone, Copy)]
 enum ModFilter {
     All,
@@ -116,12 +487,78 @@ enum ModFilter {
 enum DeleteConfirmation {
     Server(usize),
     Mod(usize),
+    Mods(Vec<usize>),
+}
+
+/// Quote names with spaces so the server console sees them as a single argument
+fn quote_player_name(name: &str) -> String {
+    if name.contains(' ') {
+        format!("\"{}\"", name)
+    } else {
+        name.to_string()
+    }
+}
+
+/// Lines the console shows for a server: its Server.log while that view is on (always for
+/// attached servers), otherwise
+/// the output of its current or last run. None when there is nothing to show.
+fn console_lines<'a>(
+    show_server_log: bool,
+    server_log: &'a Option<log_tail::LogTail>,
+    consoles: &'a HashMap<String, ConsoleBuffer>,
+    server_id: &str,
+    timestamps: bool,
+) -> Option<ui::console::ConsoleLines<'a>> {
+    if show_server_log {
+        // Server.log has the server's own timestamps
+        return server_log.as_ref().filter(|t| t.server_id == server_id).map(|t| ui::console::ConsoleLines {
+            lines: &t.lines,
+            received: None,
+            numbering: t.numbering,
+        });
+    }
+    consoles.get(server_id).map(|c| ui::console::ConsoleLines {
+        lines: &c.lines,
+        received: timestamps.then_some(c.received.as_slice()),
+        numbering: c.numbering,
+    })
 }
 
 impl BeamMpManagerApp {
-    fn new() -> Self {
-        let server_list = ServerList::load().unwrap_or_default();
-        
+    fn new(settings: AppSettings) -> Self {
+        let hide_on_start = settings.tray_icon && settings.start_minimized;
+        let (server_list, server_list_problem) = match ServerList::load() {
+            Ok((list, None)) => (list, None),
+            Ok((list, Some(recovery))) => {
+                let problem = Self::describe_recovery(&list, &recovery);
+                (list, Some(problem))
+            }
+            Err(e) => (
+                ServerList::default(),
+                Some(format!("The server list could not be read: {}", e)),
+            ),
+        };
+        let config_dir_error = server::check_config_dir_writable().err().map(|e| e.to_string());
+
+        // Look for mods left in both trees by an interrupted enable/disable
+        let split_mods: Vec<SplitModEntry> = server_list
+            .servers
+            .iter()
+            .flat_map(|server| {
+                mods::find_split_mods(&server.path, &server.get_resource_folder())
+                    .into_iter()
+                    .map(|split| SplitModEntry {
+                        server_id: server.id.clone(),
+                        server_name: server.display_name().to_string(),
+                        split,
+                    })
+            })
+            .collect();
+
+        // Guide new users, but not when the list is only empty because it couldn't be read
+        let setup_wizard = (server_list.servers.is_empty() && server_list_problem.is_none() && settings.show_setup_wizard)
+            .then(ui::setup_wizard::SetupWizard::new);
+
         Self {
             server_list,
             selected_server_index: None,
@@ -130,27 +567,119 @@ impl BeamMpManagerApp {
             mods_cache: None,
             current_mod_type: ModType::Client, // Default to Client mods
             current_mod_filter: ModFilter::All,
+            mod_search: String::new(),
             delete_confirmation: None,
-            running_process: None,
-            terminal_output: Vec::with_capacity(1000), // Preallocate
-            auto_scroll_terminal: true,
-            player_list: Vec::with_capacity(32), // Preallocate for typical player counts
-            kick_player_name: String::new(),
-            kick_reason: String::new(),
-            broadcast_message: String::new(),
+            mod_copy: None,
+            mod_trash_view: None,
+            mod_history: Vec::new(),
+            running: Vec::new(),
+            attached: Vec::new(),
+            consoles: HashMap::new(),
+            console_view: ui::console::ConsoleView::default(),
+            server_log_tail: None,
+            control_inputs: ui::control_tab::ControlInputs::default(),
             mod_details_view: None,
+            config_backups_view: None,
+            raw_config: None,
+            share_view: None,
+            setup_wizard,
+            rename_server: None,
+            macro_editor: None,
+            import_confirmation: None,
+            bundle_import: None,
+            mod_downloads: None,
+            mod_repository_search: mod_search::ModSearch::default(),
+            disk_usage: HashMap::new(),
+            server_update_check: updates::UpdateCheck::NotChecked,
+            server_update: None,
+            event_log: EventLog::load().unwrap_or_default(),
+            event_filter: EventFilter::default(),
+            show_settings: false,
+            port_conflict: None,
+            unsaved_changes: None,
+            close_confirmed: false,
+            quit_prompt: false,
+            quit_confirmed: false,
+            quit_after_stop: false,
+            config_autosave: None,
+            settings,
+            console_detached: false,
+            mod_file_cache: mods::ModFileCache::default(),
+            level_catalog: None,
+            api: api::ApiHost::default(),
+            config_dir_error,
+            split_mods_prompt: (!split_mods.is_empty()).then_some(split_mods),
+            console_input: ui::console::ConsoleInput::default(),
+            console_filter: ui::console::ConsoleFilter::default(),
+            window_position_checked: false,
+            applied_appearance: None,
+            highlight_notified: HashMap::new(),
+            tray: None,
+            hide_on_start,
+            quit_requested: false,
+            server_list_problem,
+        }
+    }
+
+    /// Explain a servers.json recovery, and persist the recovered list once the
+    /// damaged file is safely backed up
+    fn describe_recovery(list: &ServerList, recovery: &server::ListRecovery) -> String {
+        let mut text = format!(
+            "servers.json could not be parsed ({}).\n\nRecovered {} server(s)",
+            recovery.error, recovery.recovered
+        );
+        if recovery.dropped > 0 {
+            text.push_str(&format!(", {} damaged entr{} skipped", recovery.dropped, if recovery.dropped == 1 { "y was" } else { "ies were" }));
+        }
+        text.push('.');
+
+        match &recovery.backup_path {
+            Some(backup) => {
+                text.push_str(&format!("\n\nThe original file was backed up to {}", backup.display()));
+                if let Err(e) = list.save() {
+                    text.push_str(&format!("\n\nSaving the recovered list failed: {}", e));
+                }
+            }
+            None => {
+                // Without a backup, leave the damaged file alone so it can be fixed by hand
+                text.push_str("\n\nThe original file could not be backed up and has been left untouched. Fix it by hand before changing the server list, or your edits will replace it.");
+            }
         }
+        text
     }
 
     fn set_status(&mut self, text: String, is_error: bool) {
         self.status_message = Some(StatusMessage { text, is_error });
     }
 
+    /// Write recorded events to disk now and then, off the path of every single event
+    fn flush_event_log(&mut self, ctx: &egui::Context) {
+        if let Err(e) = self.event_log.flush_if_due() {
+            self.set_status(format!("Failed to save event history: {}", e), true);
+        }
+        if self.event_log.is_dirty() {
+            ctx.request_repaint_after(Duration::from_secs(1));
+        }
+    }
+
+    fn server_name(&self, server_id: &str) -> String {
+        self.server_list.servers.iter()
+            .find(|s| s.id == server_id)
+            .map_or_else(|| "BeamMP server".to_string(), |s| s.display_name().to_string())
+    }
+
     fn add_server(&mut self) {
         if let Some(path) = rfd::FileDialog::new().pick_folder() {
             match self.server_list.add_server(path) {
-                Ok(name) => {
-                    self.set_status(format!("Added server: {}", name), false);
+                Ok(added) => {
+                    if added.missing_executable {
+                        self.set_status(format!(
+                            "Added server: {}, but its folder has no BeamMP-Server executable yet",
+                            added.name
+                        ), true);
+                    } else {
+                        self.set_status(format!("Added server: {}", added.name), false);
+                    }
                     if let Err(e) = self.server_list.save() {
                         self.set_status(format!("Failed to save server list: {}", e), true);
                     }
@@ -162,8 +691,230 @@ impl BeamMpManagerApp {
         }
     }
 
+    fn handle_setup_wizard_action(&mut self, action: ui::setup_wizard::WizardAction) {
+        use ui::setup_wizard::WizardAction;
+        match action {
+            WizardAction::None => {}
+            WizardAction::AddExistingServer => {
+                self.add_server();
+                if !self.server_list.servers.is_empty() {
+                    self.select_server(self.server_list.servers.len() - 1);
+                    self.close_setup_wizard();
+                }
+            }
+            WizardAction::PickFolder => {
+                if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                    if let Some(wizard) = &mut self.setup_wizard {
+                        wizard.folder = Some(path);
+                    }
+                }
+            }
+            WizardAction::CreateServer => self.create_server_from_wizard(),
+            WizardAction::BinaryInstalled(tag) => {
+                let Some(server_id) = self.setup_wizard.as_ref().and_then(|w| w.server_id.clone()) else {
+                    return;
+                };
+                if let Some((major, minor, patch)) = updates::parse_version(&tag) {
+                    self.store_server_version(&server_id, format!("{}.{}.{}", major, minor, patch));
+                }
+                if let Some(server) = self.server_list.servers.iter_mut().find(|s| s.id == server_id) {
+                    server.check_executable();
+                }
+                self.event_log.record(&server_id, EventKind::Updated, format!("Installed BeamMP-Server {}", tag));
+            }
+            WizardAction::Finish => self.close_setup_wizard(),
+        }
+    }
+
+    /// Write the wizard's config into its folder, unless there is one already, and add the server
+    fn create_server_from_wizard(&mut self) {
+        let Some(wizard) = &self.setup_wizard else {
+            return;
+        };
+        let Some(folder) = wizard.folder.clone() else {
+            return;
+        };
+        if !wizard.has_existing_config() {
+            if let Err(e) = server::create_server_folder(&folder, &wizard.config) {
+                self.set_status(format!("Failed to create the server: {}", e), true);
+                return;
+            }
+        }
+
+        let added = match self.server_list.add_server(folder) {
+            Ok(added) => added,
+            Err(e) => {
+                self.set_status(format!("Failed to add server: {}", e), true);
+                return;
+            }
+        };
+        if let Err(e) = self.server_list.save() {
+            self.set_status(format!("Failed to save server list: {}", e), true);
+        } else {
+            self.set_status(format!("Added server: {}", added.name), false);
+        }
+
+        let idx = self.server_list.servers.len() - 1;
+        let server = &self.server_list.servers[idx];
+        if let Some(wizard) = &mut self.setup_wizard {
+            wizard.server_id = Some(server.id.clone());
+            if let Some(config) = &server.loaded_config {
+                wizard.config = config.clone();
+            }
+            wizard.step = ui::setup_wizard::WizardStep::Binary;
+        }
+        self.select_server(idx);
+    }
+
+    fn close_setup_wizard(&mut self) {
+        if self.setup_wizard.take().is_some_and(|wizard| wizard.dont_show_again) {
+            self.settings.show_setup_wizard = false;
+            if let Err(e) = self.settings.save() {
+                self.set_status(format!("Failed to save settings: {}", e), true);
+            }
+        }
+    }
+
+    /// Move panel data to a user-chosen folder after the default one turned out read-only
+    fn relocate_config_dir(&mut self) {
+        let Some(dir) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+
+        server::set_config_dir_override(dir.clone());
+        if let Err(e) = server::check_config_dir_writable() {
+            self.config_dir_error = Some(e.to_string());
+            self.set_status(format!("{} is not writable either: {}", dir.display(), e), true);
+            return;
+        }
+
+        // Write what was loaded from the old location so nothing is lost
+        let result = self.server_list.save()
+            .and_then(|_| self.settings.save())
+            .and_then(|_| self.event_log.save());
+        match result {
+            Ok(_) => {
+                self.config_dir_error = None;
+                let message = match server::remember_config_dir(&dir) {
+                    Ok(_) => format!("Panel data is now saved to {}, also after a restart", dir.display()),
+                    Err(e) => format!(
+                        "Panel data is now saved to {} for this session. It could not be remembered for the next start ({}); set {} to keep using it.",
+                        dir.display(),
+                        e,
+                        server::CONFIG_DIR_ENV,
+                    ),
+                };
+                self.set_status(message, false);
+            }
+            Err(e) => {
+                self.config_dir_error = Some(e.to_string());
+                self.set_status(format!("Failed to save panel data to {}: {}", dir.display(), e), true);
+            }
+        }
+    }
+
+    fn import_server_list(&mut self) {
+        if let Some(path) = rfd::FileDialog::new()
+            .add_filter("Server list", &["json"])
+            .pick_file()
+        {
+            match ServerList::read_import(&path) {
+                Ok(entries) if entries.is_empty() => {
+                    self.set_status("The selected file contains no servers".to_string(), true);
+                }
+                Ok(entries) => {
+                    self.import_confirmation = Some(self.server_list.preview_import(entries));
+                }
+                Err(e) => {
+                    self.set_status(format!("Failed to read server list: {}", e), true);
+                }
+            }
+        }
+    }
+
+    /// Add imported entries to the list and save it. Returns a summary for the status
+    /// bar, or None if saving failed (the error is already shown).
+    fn apply_import(&mut self, entries: Vec<server::ServerEntry>, mode: ImportMode) -> Option<String> {
+        let summary = self.server_list.apply_import(entries, mode);
+        self.selected_server_index = None;
+        self.mods_cache = None;
+
+        if let Err(e) = self.server_list.save() {
+            self.set_status(format!("Failed to save server list: {}", e), true);
+            return None;
+        }
+
+        Some(summary.describe(mode))
+    }
+
+    fn export_bundle(&mut self, include_configs: bool) {
+        let stamp = chrono::Local::now().format("%Y%m%d");
+        let Some(target) = rfd::FileDialog::new()
+            .add_filter("Server bundle", &["zip"])
+            .set_file_name(format!("beammp-servers-{}.zip", stamp))
+            .save_file()
+        else {
+            return;
+        };
+        match bundle::export(&self.server_list, &target, include_configs) {
+            Ok(export) => {
+                let configs = if include_configs {
+                    format!(" and {} config(s)", export.configs)
+                } else {
+                    String::new()
+                };
+                self.set_status(format!("Exported {} server(s){} to {}", export.servers, configs, target.display()), false);
+            }
+            Err(e) => self.set_status(format!("Failed to export servers: {}", e), true),
+        }
+    }
+
+    fn import_bundle(&mut self) {
+        let Some(path) = rfd::FileDialog::new()
+            .add_filter("Server bundle", &["zip"])
+            .pick_file()
+        else {
+            return;
+        };
+        match bundle::read(&path) {
+            Ok(servers) if servers.is_empty() => {
+                self.set_status("The bundle contains no servers".to_string(), true);
+            }
+            Ok(servers) => {
+                self.bundle_import = Some(bundle::BundleImport { servers, overwrite_configs: false });
+            }
+            Err(e) => self.set_status(format!("Failed to read server bundle: {}", e), true),
+        }
+    }
+
+    fn apply_bundle_import(&mut self, import: bundle::BundleImport, mode: ImportMode) {
+        let written = match import.write(&self.server_list, mode) {
+            Ok(written) => written,
+            Err(e) => {
+                self.set_status(format!("Import stopped: {}", e), true);
+                return;
+            }
+        };
+
+        let Some(mut text) = self.apply_import(written.entries, mode) else {
+            return;
+        };
+        if written.configs > 0 {
+            text.push_str(&format!(", wrote {} config(s)", written.configs));
+        }
+        if written.left_out > 0 {
+            text.push_str(&format!(", left out {} without a folder on this machine", written.left_out));
+        }
+        self.set_status(text, false);
+    }
+
     fn remove_selected_server(&mut self) {
         if let Some(idx) = self.selected_server_index {
+            if let Some(server) = self.server_list.servers.get(idx) {
+                self.event_log.remove_server(&server.id);
+                let server_id = server.id.clone();
+                self.attached.retain(|a| a.server_id != server_id);
+            }
             self.server_list.remove_server(idx);
             self.selected_server_index = None;
             self.mods_cache = None;
@@ -176,20 +927,49 @@ impl BeamMpManagerApp {
     }
 
     fn reload_mods(&mut self, mod_type: ModType) {
+        // Mods may have been added, removed or toggled
+        self.level_catalog = None;
         if let Some(idx) = self.selected_server_index {
             if let Some(server) = self.server_list.servers.get(idx) {
                 let resource_folder = server.get_resource_folder();
-                let result = match mod_type {
-                    ModType::Server => mods::scan_server_mods(&server.path, &resource_folder),
-                    ModType::Client => mods::scan_client_mods(&server.path, &resource_folder),
-                };
+                let nested = self.settings.nested_server_mods;
+                let result = mod_type.scan(&server.path, &resource_folder, nested);
                 
                 match result {
                     Ok(mods) => {
+                        let folder_issue = mods::check_resource_folder(&server.path, &resource_folder);
+                        // Mods left in Resources while the config points at an empty or missing folder
+                        let default_folder_mods = if mods.is_empty() && folder_issue.is_some() {
+                            mod_type.scan(&server.path, mods::DEFAULT_RESOURCE_FOLDER, nested).unwrap_or_default()
+                        } else {
+                            Vec::new()
+                        };
+                        // Server mods are folders; duplicate and conflict detection only cover client ZIPs
+                        let (duplicates, conflicts) = match mod_type {
+                            ModType::Client => (
+                                mods::find_duplicate_mods(&mods, &mut self.mod_file_cache),
+                                mods::find_mod_conflicts(&mods, &mut self.mod_file_cache),
+                            ),
+                            ModType::Server => (Vec::new(), Vec::new()),
+                        };
+                        let details = mods.iter()
+                            .map(|m| match mod_type {
+                                ModType::Client => self.mod_file_cache.details(&m.full_path).ok(),
+                                ModType::Server => None,
+                            })
+                            .collect();
                         self.mods_cache = Some(ModsCache {
                             server_id: server.id.clone(),
                             mods,
                             mod_type,
+                            duplicates,
+                            conflicts,
+                            details,
+                            selected: HashSet::new(),
+                            resource_folder,
+                            folder_issue,
+                            default_folder_mods,
+                            nested,
                         });
                     }
                     Err(e) => {
@@ -201,162 +981,2631 @@ impl BeamMpManagerApp {
         }
     }
 
-    fn start_server(&mut self, server_id: String, server_path: std::path::PathBuf) {
-        match ServerProcess::start(&server_path) {
-            Ok(process) => {
-                self.terminal_output.clear();
-                self.terminal_output.push(format!("Starting server at {}...", server_path.display()));
-                self.running_process = Some(RunningProcess {
-                    server_id,
-                    process,
+    /// Save the edited config of a server. When `check_ports` is set and another
+    /// managed server already uses the chosen port, ask for confirmation first.
+    fn apply_config(&mut self, idx: usize, check_ports: bool) {
+        let Some(port) = self.server_list.servers.get(idx)
+            .and_then(|s| s.edited_config.as_ref())
+            .map(|c| c.general.port)
+        else {
+            return;
+        };
+
+        if port == 0 {
+            self.set_status("Port must be between 1 and 65535".to_string(), true);
+            return;
+        }
+
+        if check_ports {
+            let used_by = self.server_list.servers_using_port(port, idx);
+            if !used_by.is_empty() {
+                self.port_conflict = Some(PortConflictPrompt {
+                    server_index: idx,
+                    port,
+                    used_by,
+                    free_port: self.server_list.find_free_port(port, idx),
                 });
-                self.set_status("Server started".to_string(), false);
+                return;
+            }
+        }
+
+        let Some(server) = self.server_list.servers.get_mut(idx) else {
+            return;
+        };
+        match server.save_config() {
+            Ok(_) => {
+                let server_id = server.id.clone();
+                self.set_status("Configuration saved!".to_string(), false);
+                self.event_log.record(&server_id, EventKind::ConfigApplied, "Configuration saved".to_string());
             }
             Err(e) => {
-                self.set_status(format!("Failed to start server: {}", e), true);
+                self.set_status(format!("Failed to save config: {}", e), true);
             }
         }
     }
 
-    fn stop_server(&mut self) {
-        if let Some(mut running) = self.running_process.take() {
-            match running.process.stop() {
-                Ok(_) => {
-                    self.terminal_output.push("Server stopped.".to_string());
-                    self.set_status("Server stopped".to_string(), false);
+    /// Open the Raw view with ServerConfig.toml as it is on disk
+    fn open_raw_config(&mut self, idx: usize) {
+        let Some(server) = self.server_list.servers.get(idx) else {
+            return;
+        };
+        match server.read_raw_config() {
+            Ok(text) => self.raw_config = Some(ui::config_tab::RawConfigEditor::new(server.id.clone(), text)),
+            Err(e) => self.set_status(format!("Failed to read the config: {}", e), true),
+        }
+    }
+
+    fn save_raw_config(&mut self, idx: usize) {
+        let (Some(server), Some(editor)) = (self.server_list.servers.get_mut(idx), &mut self.raw_config) else {
+            return;
+        };
+        match server.save_raw_config(&editor.text) {
+            Ok(()) => {
+                editor.mark_saved();
+                let server_id = server.id.clone();
+                if server.config_error.is_some() {
+                    self.set_status("Saved, but the config doesn't parse. Fix it before starting the server.".to_string(), true);
+                } else {
+                    self.set_status("Configuration saved!".to_string(), false);
                 }
-                Err(e) => {
-                    self.set_status(format!("Failed to stop server: {}", e), true);
+                self.event_log.record(&server_id, EventKind::ConfigApplied, "Configuration saved in the raw editor".to_string());
+            }
+            Err(e) => self.set_status(format!("Failed to save config: {}", e), true),
+        }
+    }
+
+    /// Save the config without any prompts, for autosave. Returns false when it was left
+    /// unsaved because the port is invalid or used by another server.
+    fn autosave_config(&mut self, idx: usize) -> bool {
+        let Some(port) = self.server_list.servers.get(idx)
+            .and_then(|s| s.edited_config.as_ref())
+            .map(|c| c.general.port)
+        else {
+            return false;
+        };
+        if port == 0 || !self.server_list.servers_using_port(port, idx).is_empty() {
+            return false;
+        }
+        self.apply_config(idx, false);
+        self.server_list.servers.get(idx).is_some_and(|s| !s.is_config_dirty())
+    }
+
+    /// With autosave on, save the selected server's config once it has stopped changing
+    fn run_config_autosave(&mut self, ctx: &egui::Context) {
+        let dirty = self.settings.autosave_config
+            .then_some(self.selected_server_index)
+            .flatten()
+            .and_then(|idx| self.server_list.servers.get(idx).map(|s| (idx, s)))
+            .filter(|(_, s)| s.is_config_dirty());
+        let Some((idx, server)) = dirty else {
+            self.config_autosave = None;
+            return;
+        };
+        let Some(edited) = &server.edited_config else {
+            return;
+        };
+
+        match &mut self.config_autosave {
+            Some(pending) if pending.server_id == server.id && pending.seen == *edited => {
+                if pending.attempted {
+                    return;
+                }
+                let remaining = CONFIG_AUTOSAVE_DELAY.saturating_sub(pending.changed_at.elapsed());
+                if remaining.is_zero() {
+                    pending.attempted = true;
+                    self.autosave_config(idx);
+                } else {
+                    ctx.request_repaint_after(remaining);
                 }
             }
+            _ => {
+                self.config_autosave = Some(PendingAutosave {
+                    server_id: server.id.clone(),
+                    seen: edited.clone(),
+                    changed_at: Instant::now(),
+                    attempted: false,
+                });
+                ctx.request_repaint_after(CONFIG_AUTOSAVE_DELAY);
+            }
         }
     }
 
-    fn update_terminal(&mut self) -> bool {
-        // Check if process is still running and read output
-        // Returns true if terminal was updated (for conditional repainting)
-        if let Some(running) = &mut self.running_process {
-            if !running.process.is_running() {
-                self.terminal_output.push("Server process exited.".to_string());
-                self.running_process = None;
-                return true;
+    /// Select a server from the list. Unapplied config edits of the current one are saved
+    /// with autosave on, otherwise the user is asked what to do with them first.
+    fn select_server(&mut self, idx: usize) {
+        if let Some(current) = self.selected_server_index.filter(|&current| current != idx) {
+            let dirty = self.server_list.servers.get(current).is_some_and(|s| s.is_config_dirty());
+            if dirty && !(self.settings.autosave_config && self.autosave_config(current)) {
+                self.unsaved_changes = Some(UnsavedChangesPrompt {
+                    server_index: current,
+                    then: AfterUnsaved::SelectServer(idx),
+                });
+                return;
+            }
+        }
+        self.selected_server_index = Some(idx);
+        self.mods_cache = None;
+        self.show_settings = false;
+    }
+
+    /// Keep the window open while a server has unapplied config edits (that autosave
+    /// couldn't save) and ask about them. Returns whether the close was cancelled.
+    fn guard_close(&mut self, ctx: &egui::Context) -> bool {
+        if self.close_confirmed {
+            return false;
+        }
+        // The selected server first, it is the one being edited
+        let dirty = self.selected_server_index
+            .filter(|&idx| self.server_list.servers.get(idx).is_some_and(|s| s.is_config_dirty()))
+            .or_else(|| self.server_list.servers.iter().position(|s| s.is_config_dirty()));
+        let Some(idx) = dirty else {
+            return false;
+        };
+        if self.settings.autosave_config && self.autosave_config(idx) {
+            return false;
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+        self.unsaved_changes = Some(UnsavedChangesPrompt { server_index: idx, then: AfterUnsaved::CloseWindow });
+        true
+    }
+
+    /// Keep the window open while servers the panel started are running and ask whether
+    /// to stop them. Returns whether the close was cancelled.
+    fn guard_running_servers(&mut self, ctx: &egui::Context) -> bool {
+        if self.quit_confirmed || self.running.is_empty() {
+            return false;
+        }
+        ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+        // While stopping for Stop and Quit, the panel closes by itself
+        if !self.quit_after_stop {
+            self.quit_prompt = true;
+        }
+        true
+    }
+
+    /// Stop every running server for Stop and Quit; the window closes once they are all gone
+    fn stop_all_and_quit(&mut self) {
+        let ids: Vec<String> = self.running.iter().map(|r| r.server_id.clone()).collect();
+        for id in &ids {
+            self.cancel_restart_countdown(id);
+            if let Some(running) = self.running.iter_mut().find(|r| &r.server_id == id) {
+                // A scheduled restart in progress must not bring it back
+                running.restart_after_stop = false;
+            }
+            self.stop_server(id);
+        }
+        self.quit_after_stop = true;
+        self.set_status(format!("Stopping {} server(s) before quitting...", ids.len()), false);
+    }
+
+    /// Make sure no server the panel started outlives it, and record how each one ended
+    fn stop_servers_on_exit(&mut self) {
+        let mut running = std::mem::take(&mut self.running);
+        let killed = process::stop_all_on_exit(
+            running
+                .iter_mut()
+                .map(|r| (&mut r.process, matches!(r.state, ServerState::Stopping { .. }))),
+        );
+
+        for (running, killed) in running.iter().zip(killed) {
+            let message = if killed {
+                format!(
+                    "Server killed when the panel closed, it did not exit within {} seconds",
+                    process::EXIT_STOP_TIMEOUT.as_secs()
+                )
             } else {
-                let new_lines = running.process.read_output();
-                let has_new_output = !new_lines.is_empty();
-                self.terminal_output.extend(new_lines);
-                
-                // Limit terminal output to last 1000 lines
-                if self.terminal_output.len() > 1000 {
-                    self.terminal_output.drain(0..self.terminal_output.len() - 1000);
+                "Server stopped when the panel closed".to_string()
+            };
+            self.record_runtime(&running.server_id, running.start_time);
+            self.event_log.record(&running.server_id, EventKind::Stopped, message);
+        }
+    }
+
+    fn finish_quit_after_stop(&mut self, ctx: &egui::Context) {
+        if self.quit_after_stop && self.running.is_empty() {
+            self.quit_after_stop = false;
+            self.quit_confirmed = true;
+            ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+        }
+    }
+
+    fn move_server(&mut self, from: usize, to: usize) {
+        if to == from || to == from + 1 {
+            return;
+        }
+        // Keep the same server selected, wherever it ends up
+        let selected_id = self.selected_server_id();
+        self.server_list.move_server(from, to);
+        self.selected_server_index = selected_id
+            .and_then(|id| self.server_list.servers.iter().position(|s| s.id == id));
+        if let Err(e) = self.server_list.save() {
+            self.set_status(format!("Failed to save server list: {}", e), true);
+        }
+    }
+
+    /// Set or clear (None or blank) the panel-only nickname of a server
+    fn set_server_nickname(&mut self, idx: usize, nickname: Option<String>) {
+        let Some(server) = self.server_list.servers.get_mut(idx) else {
+            return;
+        };
+        server.custom_name = nickname
+            .map(|name| name.trim().to_string())
+            .filter(|name| !name.is_empty() && *name != server.name);
+        let name = server.display_name().to_string();
+        match self.server_list.save() {
+            Ok(_) => self.set_status(format!("Server shown as \"{}\"", name), false),
+            Err(e) => self.set_status(format!("Failed to save server list: {}", e), true),
+        }
+    }
+
+    /// Rebuild the level list if it belongs to another server or resource folder
+    fn refresh_level_catalog(&mut self, idx: usize) {
+        let Some(server) = self.server_list.servers.get(idx) else {
+            return;
+        };
+        let resource_folder = server.get_resource_folder();
+        let current = self.level_catalog.as_ref()
+            .is_some_and(|c| c.server_id == server.id && c.resource_folder == resource_folder);
+        if current {
+            return;
+        }
+        let levels = mods::available_levels(&server.path, &resource_folder, &mut self.mod_file_cache);
+        self.level_catalog = Some(LevelCatalog {
+            server_id: server.id.clone(),
+            resource_folder,
+            levels,
+        });
+    }
+
+    /// Let the user pick where a moved server folder is now
+    fn locate_server_folder(&mut self, idx: usize) {
+        let Some(server) = self.server_list.servers.get(idx) else {
+            return;
+        };
+        if self.server_state(&server.id) != ServerState::Stopped {
+            self.set_status("Stop the server before changing its folder".to_string(), true);
+            return;
+        }
+        let Some(path) = rfd::FileDialog::new().pick_folder() else {
+            return;
+        };
+        if let Some(other) = self.server_list.servers.iter().enumerate().find(|(i, s)| *i != idx && s.path == path) {
+            self.set_status(format!("That folder is already in the list as \"{}\"", other.1.display_name()), true);
+            return;
+        }
+
+        let server = &mut self.server_list.servers[idx];
+        match server.relocate(path) {
+            Ok(_) => {
+                let text = format!("\"{}\" now points to {}", server.display_name(), server.path.display());
+                self.mods_cache = None;
+                match self.server_list.save() {
+                    Ok(_) => self.set_status(text, false),
+                    Err(e) => self.set_status(format!("Failed to save server list: {}", e), true),
                 }
-                return has_new_output;
             }
+            Err(e) => self.set_status(format!("Failed to use that folder: {}", e), true),
         }
-        false
     }
 
-    fn send_server_command(&mut self, command: &str) {
-        if let Some(running) = &self.running_process {
-            match running.process.send_command(command) {
-                Ok(_) => {
-                    self.terminal_output.push(format!("> {}", command));
-                    self.set_status(format!("Command sent: {}", command), false);
-                }
-                Err(e) => {
-                    self.set_status(format!("Failed to send command: {}", e), true);
+    fn restore_config_backup(&mut self, idx: usize, backup: &server::ConfigBackup) {
+        let Some(server) = self.server_list.servers.get_mut(idx) else {
+            return;
+        };
+        match server.restore_config_backup(&backup.path) {
+            Ok(_) => {
+                let server_id = server.id.clone();
+                let when = ui::format_timestamp(backup.created);
+                self.set_status(format!("Config restored from the backup of {}", when), false);
+                self.event_log.record(&server_id, EventKind::ConfigApplied, format!("Configuration restored from the backup of {}", when));
+            }
+            Err(e) => {
+                self.set_status(format!("Failed to restore config: {}", e), true);
+            }
+        }
+    }
+
+    fn pick_free_port(&mut self, idx: usize) {
+        let Some(port) = self.server_list.servers.get(idx)
+            .and_then(|s| s.edited_config.as_ref())
+            .map(|c| c.general.port)
+        else {
+            return;
+        };
+
+        match self.server_list.find_free_port(port, idx) {
+            Some(free_port) => {
+                if let Some(config) = self.server_list.servers.get_mut(idx).and_then(|s| s.edited_config.as_mut()) {
+                    config.general.port = free_port;
+                }
+                self.set_status(format!("Port changed to {} (not saved yet)", free_port), false);
+            }
+            None => {
+                self.set_status("No free port found".to_string(), true);
+            }
+        }
+    }
+
+    /// Move mods into the server's trash, from where Undo can bring them back
+    fn delete_mods(&mut self, indices: &[usize]) {
+        let Some(cache) = &self.mods_cache else {
+            return;
+        };
+        let Some(server) = self.server_list.servers.iter().find(|s| s.id == cache.server_id) else {
+            return;
+        };
+
+        let server_id = cache.server_id.clone();
+        let mod_type = cache.mod_type;
+        let mut deleted = Vec::new();
+        let mut trashed = Vec::new();
+        let mut last_error = None;
+        for mod_entry in indices.iter().filter_map(|idx| cache.mods.get(*idx)) {
+            match mods::trash_mod(&server.path, &mod_entry.full_path) {
+                Ok(path) => {
+                    deleted.push(mod_entry.relative_path.clone());
+                    trashed.push(path);
+                }
+                Err(e) => last_error = Some(format!("{}: {}", mod_entry.relative_path, e)),
+            }
+        }
+        // Bring the trash back under its limit by dropping the oldest deletions, never this one
+        let trash_limit = self.settings.mod_trash_limit_mb.saturating_mul(1024 * 1024);
+        if let Err(e) = mods::prune_trash(&server.path, trash_limit, &trashed) {
+            last_error.get_or_insert(format!("Failed to clean up the mod trash: {}", e));
+        }
+        self.forget_removed_trash(&server_id);
+
+        for name in &deleted {
+            self.event_log.record(&server_id, EventKind::ModChanged, format!("Deleted mod {}", name));
+        }
+        if !trashed.is_empty() {
+            self.push_mod_undo(server_id, mod_type, ModChange::Deleted(trashed));
+        }
+
+        match last_error {
+            Some(e) => self.set_status(format!("Deleted {} mod(s). {}", deleted.len(), e), true),
+            None => self.set_status(format!("Deleted {} mod(s)", deleted.len()), false),
+        }
+        self.reload_mods(mod_type);
+    }
+
+    /// Drop undo steps for deleted mods that have since left the server's trash
+    fn forget_removed_trash(&mut self, server_id: &str) {
+        for undo in self.mod_history.iter_mut().filter(|undo| undo.server_id == server_id) {
+            if let ModChange::Deleted(paths) = &mut undo.change {
+                paths.retain(|path| path.exists());
+            }
+        }
+        self.mod_history.retain(|undo| !matches!(&undo.change, ModChange::Deleted(paths) if paths.is_empty()));
+    }
+
+    fn push_mod_undo(&mut self, server_id: String, mod_type: ModType, change: ModChange) {
+        if self.mod_history.len() == MOD_UNDO_HISTORY {
+            self.mod_history.remove(0);
+        }
+        self.mod_history.push(ModUndo { server_id, mod_type, change, at: Instant::now() });
+    }
+
+    /// Reverse the latest mod change: move toggled mods back, or restore deleted ones from the trash
+    fn undo_mod_change(&mut self) {
+        let Some(undo) = self.mod_history.pop() else {
+            return;
+        };
+        let Some(server) = self.server_list.servers.iter().find(|s| s.id == undo.server_id) else {
+            self.set_status("The server of that change is no longer in the list".to_string(), true);
+            return;
+        };
+        let resource_folder = server.get_resource_folder();
+
+        let mut reverted = Vec::new();
+        let mut last_error = None;
+        match &undo.change {
+            ModChange::SetEnabled { names, enabled } => {
+                for name in names {
+                    let result = match (undo.mod_type, enabled) {
+                        (ModType::Server, true) => mods::disable_server_mod(&server.path, &resource_folder, name),
+                        (ModType::Server, false) => mods::enable_server_mod(&server.path, &resource_folder, name),
+                        (ModType::Client, true) => mods::disable_client_mod(&server.path, &resource_folder, name),
+                        (ModType::Client, false) => mods::enable_client_mod(&server.path, &resource_folder, name),
+                    };
+                    match result {
+                        Ok(_) => reverted.push(name.clone()),
+                        Err(e) => last_error = Some(format!("{}: {}", name, e)),
+                    }
+                }
+            }
+            ModChange::Deleted(paths) => {
+                for path in paths {
+                    let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                    match mods::restore_mod(&server.path, path) {
+                        Ok(_) => reverted.push(name),
+                        Err(e) => last_error = Some(e.to_string()),
+                    }
+                }
+            }
+        }
+
+        let verb = match &undo.change {
+            ModChange::SetEnabled { enabled: true, .. } => "Disabled",
+            ModChange::SetEnabled { enabled: false, .. } => "Enabled",
+            ModChange::Deleted(_) => "Restored",
+        };
+        for name in &reverted {
+            self.event_log.record(&undo.server_id, EventKind::ModChanged, format!("{} mod {} (undo)", verb, name));
+        }
+        let text = format!("Undo: {} {} mod(s)", verb.to_lowercase(), reverted.len());
+        match last_error {
+            Some(e) => self.set_status(format!("{}, failed: {}", text, e), true),
+            None => self.set_status(text, false),
+        }
+        if self.mods_cache.as_ref().is_some_and(|c| c.server_id == undo.server_id && c.mod_type == undo.mod_type) {
+            self.reload_mods(undo.mod_type);
+        }
+    }
+
+    fn handle_trash_action(&mut self, action: ui::mod_trash::TrashAction) {
+        let Some(view) = &self.mod_trash_view else {
+            return;
+        };
+        let Some(server) = self.server_list.servers.iter().find(|s| s.id == view.server_id) else {
+            return;
+        };
+        let server_id = server.id.clone();
+        let server_path = server.path.clone();
+
+        match action {
+            ui::mod_trash::TrashAction::None => return,
+            ui::mod_trash::TrashAction::Restore(path) => match mods::restore_mod(&server_path, &path) {
+                Ok(restored) => {
+                    let name = restored.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                    self.event_log.record(&server_id, EventKind::ModChanged, format!("Restored mod {} from the trash", name));
+                    self.set_status(format!("Restored {}", name), false);
+                }
+                Err(e) => self.set_status(format!("Failed to restore mod: {}", e), true),
+            },
+            ui::mod_trash::TrashAction::Purge(path) => {
+                match mods::purge_trashed(&server_path, &path) {
+                    Ok(_) => self.set_status("Mod permanently deleted".to_string(), false),
+                    Err(e) => self.set_status(format!("Failed to delete mod: {}", e), true),
+                }
+                self.forget_removed_trash(&server_id);
+            }
+            ui::mod_trash::TrashAction::EmptyTrash => {
+                match mods::empty_trash(&server_path) {
+                    Ok(freed) => self.set_status(format!("Emptied the mod trash, freed {}", ui::format_bytes(freed)), false),
+                    Err(e) => self.set_status(format!("Failed to empty the mod trash: {}", e), true),
+                }
+                self.mod_history.retain(|undo| !(undo.server_id == server_id && matches!(undo.change, ModChange::Deleted(_))));
+            }
+        }
+
+        if let Some(view) = &mut self.mod_trash_view {
+            view.refresh(&server_path);
+        }
+        if let Some(mod_type) = self.mods_cache.as_ref().filter(|c| c.server_id == server_id).map(|c| c.mod_type) {
+            self.reload_mods(mod_type);
+        }
+    }
+
+    fn empty_mod_trash(&mut self) {
+        let mut freed = 0;
+        let mut last_error = None;
+        for server in &self.server_list.servers {
+            match mods::empty_trash(&server.path) {
+                Ok(bytes) => freed += bytes,
+                Err(e) => last_error = Some(format!("{}: {}", server.display_name(), e)),
+            }
+        }
+        // Whatever they would restore is gone
+        self.mod_history.retain(|undo| !matches!(undo.change, ModChange::Deleted(_)));
+        let text = format!("Emptied the mod trash, freed {}", ui::format_bytes(freed));
+        match last_error {
+            Some(e) => self.set_status(format!("{}. Failed: {}", text, e), true),
+            None => self.set_status(text, false),
+        }
+    }
+
+    /// Create the configured resource folder of the selected server when it doesn't exist
+    fn create_resource_folder(&mut self) {
+        let Some(server) = self.selected_server_index.and_then(|idx| self.server_list.servers.get(idx)) else {
+            return;
+        };
+        let server_id = server.id.clone();
+        let resource_folder = server.get_resource_folder();
+        match mods::create_resource_folder(&server.path, &resource_folder) {
+            Ok(()) => {
+                self.event_log.record(&server_id, EventKind::ModChanged, format!("Created the resource folder {}", resource_folder));
+                self.set_status(format!("Created the resource folder {}", resource_folder), false);
+            }
+            Err(e) => self.set_status(format!("Failed to create {}: {}", resource_folder, e), true),
+        }
+        self.reload_mods(self.current_mod_type);
+    }
+
+    /// Point the selected server's ResourceFolder back at Resources. Only changes the edited
+    /// config unless `save` is set, so the Mods tab shows Resources and the Config tab shows
+    /// the change waiting to be applied.
+    fn use_default_resource_folder(&mut self, save: bool) {
+        let Some(idx) = self.selected_server_index else {
+            return;
+        };
+        let Some(config) = self.server_list.servers.get_mut(idx).and_then(|s| s.edited_config.as_mut()) else {
+            return;
+        };
+        config.general.resource_folder = mods::DEFAULT_RESOURCE_FOLDER.to_string();
+        if save {
+            self.apply_config(idx, false);
+        } else {
+            self.set_status(
+                format!("Showing the mods in {}. Apply the config to make the server load them from there.", mods::DEFAULT_RESOURCE_FOLDER),
+                false,
+            );
+        }
+        self.reload_mods(self.current_mod_type);
+    }
+
+    /// Copy the mods into the target server, replacing the ones it already has only with `overwrite`
+    fn copy_mods(&mut self, copy: ModCopy, overwrite: bool) {
+        let Some(target) = copy.target_server_id.as_ref()
+            .and_then(|id| self.server_list.servers.iter().find(|s| &s.id == id))
+        else {
+            return;
+        };
+        let target_id = target.id.clone();
+        let target_name = target.display_name().to_string();
+        let target_path = target.path.clone();
+        let resource_folder = target.get_resource_folder();
+        let source_name = self.server_name(&copy.source_server_id);
+
+        let mut copied = Vec::new();
+        let mut files = 0;
+        let mut skipped = 0;
+        let mut last_error = None;
+        for (name, source) in &copy.mods {
+            let (target_file, exists) = mods::copy_target(&target_path, &resource_folder, copy.mod_type.folder(), name);
+            if exists && !overwrite {
+                skipped += 1;
+                continue;
+            }
+            match mods::copy_mod(source, &target_file) {
+                Ok(count) => {
+                    files += count;
+                    copied.push(name.clone());
+                }
+                Err(e) => last_error = Some(format!("{}: {}", name, e)),
+            }
+        }
+
+        for name in &copied {
+            self.event_log.record(&target_id, EventKind::ModChanged, format!("Copied mod {} from {}", name, source_name));
+        }
+        let mut text = format!("Copied {} mod(s), {} file(s), to {}", copied.len(), files, target_name);
+        if skipped > 0 {
+            text.push_str(&format!(", skipped {} it already had", skipped));
+        }
+        match last_error {
+            Some(e) => self.set_status(format!("{}. Failed: {}", text, e), true),
+            None => self.set_status(text, false),
+        }
+    }
+
+    fn set_mod_enabled(&mut self, mod_idx: usize, enable: bool) {
+        self.set_mods_enabled(&[mod_idx], enable);
+    }
+
+    /// Enable or disable several mods at once. Mods already in the requested state are skipped.
+    fn set_mods_enabled(&mut self, indices: &[usize], enable: bool) {
+        let Some(server) = self.selected_server_index.and_then(|idx| self.server_list.servers.get(idx)) else {
+            return;
+        };
+        let Some(cache) = &self.mods_cache else {
+            return;
+        };
+        let mod_type = cache.mod_type;
+        let targets: Vec<&mods::ModEntry> = indices.iter()
+            .filter_map(|idx| cache.mods.get(*idx))
+            .filter(|m| m.enabled != enable)
+            .collect();
+
+        // The cache can be stale after changes outside the panel; check before moving anything
+        if targets.iter().any(|m| !m.full_path.exists() || m.disabled_copy.is_some()) {
+            self.reload_mods(mod_type);
+            self.set_status("Mod state changed on disk, the list has been refreshed".to_string(), true);
+            return;
+        }
+
+        let resource_folder = server.get_resource_folder();
+        let mut changed = Vec::new();
+        let mut last_error = None;
+        let mut failed = 0;
+        let mut collided = false;
+        for mod_entry in targets {
+            let result = match (mod_type, enable) {
+                (ModType::Server, true) => mods::enable_server_mod(&server.path, &resource_folder, &mod_entry.relative_path),
+                (ModType::Server, false) => mods::disable_server_mod(&server.path, &resource_folder, &mod_entry.relative_path),
+                (ModType::Client, true) => mods::enable_client_mod(&server.path, &resource_folder, &mod_entry.relative_path),
+                (ModType::Client, false) => mods::disable_client_mod(&server.path, &resource_folder, &mod_entry.relative_path),
+            };
+            match result {
+                Ok(_) => changed.push(mod_entry.relative_path.clone()),
+                Err(e) => {
+                    failed += 1;
+                    collided |= e.downcast_ref::<mods::TargetExists>().is_some();
+                    last_error = Some(e);
+                }
+            }
+        }
+
+        let server_id = server.id.clone();
+        let verb = if enable { "enable" } else { "disable" };
+        // The other tree already has the mod; after a reload it shows up with both copies
+        // and the choice of which one to keep
+        let last_error = last_error.map(|e| match e.downcast_ref::<mods::TargetExists>() {
+            Some(exists) => format!(
+                "{} already exists in the {} folder, choose which copy to keep in the list",
+                exists.0.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default(),
+                if enable { "enabled" } else { "disabled" },
+            ),
+            None => e.to_string(),
+        });
+        let past = if enable { "Enabled" } else { "Disabled" };
+        for name in &changed {
+            self.event_log.record(&server_id, EventKind::ModChanged, format!("{} mod {}", past, name));
+        }
+        if !changed.is_empty() {
+            self.push_mod_undo(server_id, mod_type, ModChange::SetEnabled { names: changed.clone(), enabled: enable });
+        }
+
+        match (indices.len(), last_error) {
+            (1, Some(e)) => self.set_status(format!("Failed to {}: {}", verb, e), true),
+            (1, None) => self.set_status(format!("Mod {}d", verb), false),
+            (_, Some(e)) => self.set_status(
+                format!("{} {} mod(s), {} failed: {}", past, changed.len(), failed, e),
+                true,
+            ),
+            (_, None) => self.set_status(format!("{} {} mod(s)", past, changed.len()), false),
+        }
+        if !changed.is_empty() || collided {
+            self.reload_mods(mod_type);
+        }
+    }
+
+    /// Settle a mod found in both trees by deleting the copy that isn't kept
+    fn resolve_split_mod(&mut self, mod_idx: usize, keep_enabled: bool) {
+        let Some(cache) = &self.mods_cache else {
+            return;
+        };
+        let Some(mod_entry) = cache.mods.get(mod_idx) else {
+            return;
+        };
+        let Some(disabled_copy) = mod_entry.disabled_copy.clone() else {
+            return;
+        };
+
+        let server_id = cache.server_id.clone();
+        let mod_type = cache.mod_type;
+        let name = mod_entry.relative_path.clone();
+        let (remove, kept) = if keep_enabled {
+            (disabled_copy, "enabled")
+        } else {
+            (mod_entry.full_path.clone(), "disabled")
+        };
+
+        match mods::delete_mod(&remove) {
+            Ok(_) => {
+                self.event_log.record(&server_id, EventKind::ModChanged, format!("Kept {} copy of {}", kept, name));
+                self.set_status(format!("Kept the {} copy of {}", kept, name), false);
+            }
+            Err(e) => {
+                self.set_status(format!("Failed to remove the other copy of {}: {}", name, e), true);
+            }
+        }
+        self.reload_mods(mod_type);
+    }
+
+    /// Keep one copy of every mod from the startup split-mod prompt
+    fn resolve_split_mods(&mut self, entries: Vec<SplitModEntry>, keep_enabled: bool) {
+        let kept = if keep_enabled { "enabled" } else { "disabled" };
+        let mut resolved = 0;
+        let mut failed = 0;
+
+        for entry in entries {
+            let remove = if keep_enabled { &entry.split.disabled_path } else { &entry.split.enabled_path };
+            match mods::delete_mod(remove) {
+                Ok(_) => {
+                    resolved += 1;
+                    self.event_log.record(
+                        &entry.server_id,
+                        EventKind::ModChanged,
+                        format!("Kept {} copy of {}", kept, entry.split.name),
+                    );
+                }
+                Err(_) => failed += 1,
+            }
+        }
+
+        if failed > 0 {
+            self.set_status(format!("Resolved {} mod(s), {} failed", resolved, failed), true);
+        } else {
+            self.set_status(format!("Kept the {} copy of {} mod(s)", kept, resolved), false);
+        }
+        self.mods_cache = None;
+    }
+
+    fn add_client_mods(&mut self, files: Vec<PathBuf>) {
+        let Some(server) = self.selected_server_index.and_then(|idx| self.server_list.servers.get(idx)) else {
+            return;
+        };
+
+        let resource_folder = server.get_resource_folder();
+        let server_id = server.id.clone();
+        let allow_unknown = self.settings.allow_unknown_mod_structure;
+        let mut added = Vec::new();
+        let mut errors = Vec::new();
+
+        for file in files {
+            match mods::add_client_mod(&server.path, &resource_folder, &file, allow_unknown) {
+                Ok(_) => added.push(file),
+                Err(e) => errors.push(format!("{}: {}", file.display(), e)),
+            }
+        }
+
+        for file in &added {
+            let name = file.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            self.event_log.record(&server_id, EventKind::ModChanged, format!("Added client mod {}", name));
+        }
+
+        if !added.is_empty() {
+            self.set_status(format!("Added {} client mod(s)", added.len()), false);
+            self.reload_mods(ModType::Client);
+        }
+
+        if !errors.is_empty() {
+            self.set_status(format!("Errors: {}", errors.join(", ")), true);
+        }
+    }
+
+    fn start_mod_downloads(&mut self, urls: Vec<String>, ctx: &egui::Context) {
+        let Some(downloads) = &mut self.mod_downloads else {
+            return;
+        };
+        if urls.is_empty() {
+            return;
+        }
+        let timeout = Duration::from_secs(self.settings.download_timeout_secs.max(1) as u64);
+        downloads.batch = Some(download::DownloadBatch::start(urls, timeout, ctx));
+    }
+
+    /// Install mods the download thread has finished, into the server the window was opened for
+    fn poll_mod_downloads(&mut self) {
+        let Some(downloads) = &mut self.mod_downloads else {
+            return;
+        };
+        let Some(batch) = &mut downloads.batch else {
+            return;
+        };
+        let downloaded = batch.poll();
+        if downloaded.is_empty() {
+            return;
+        }
+
+        let server = self.server_list.servers.iter().find(|s| s.id == downloads.server_id);
+        let mut installed = Vec::new();
+        for (index, path) in downloaded {
+            let name = path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+            let result = match server {
+                Some(server) => mods::add_client_mod(
+                    &server.path,
+                    &server.get_resource_folder(),
+                    &path,
+                    self.settings.allow_unknown_mod_structure,
+                ),
+                None => Err(anyhow::anyhow!("The server was removed from the list")),
+            };
+            let _ = std::fs::remove_file(&path);
+            batch.items[index].state = match result {
+                Ok(()) => {
+                    installed.push(name.clone());
+                    download::DownloadState::Installed(name)
+                }
+                Err(e) => download::DownloadState::Failed(e.to_string()),
+            };
+        }
+
+        let server_id = downloads.server_id.clone();
+        for name in &installed {
+            self.event_log.record(&server_id, EventKind::ModChanged, format!("Downloaded client mod {}", name));
+        }
+        if !installed.is_empty() {
+            // Rescanned on the next frame, whichever view is open
+            self.mods_cache = None;
+            self.level_catalog = None;
+        }
+    }
+
+    /// Add up the server's folder on a background thread
+    fn calculate_disk_usage(&mut self, idx: usize, ctx: &egui::Context) {
+        let Some(server) = self.server_list.servers.get(idx) else {
+            return;
+        };
+        let (tx, rx) = std::sync::mpsc::channel();
+        let path = server.path.clone();
+        let resource_folder = server.get_resource_folder();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(server::disk_usage(&path, &resource_folder));
+            ctx.request_repaint();
+        });
+        self.disk_usage.insert(server.id.clone(), DiskUsageState::Calculating(rx));
+    }
+
+    fn poll_disk_usage(&mut self) {
+        for state in self.disk_usage.values_mut() {
+            if let DiskUsageState::Calculating(rx) = state {
+                if let Ok(usage) = rx.try_recv() {
+                    *state = DiskUsageState::Done(usage);
+                }
+            }
+        }
+    }
+
+    fn check_server_updates(&mut self, ctx: &egui::Context) {
+        let url = self.settings.server_release_url.trim().to_string();
+        let timeout = Duration::from_secs(self.settings.download_timeout_secs.max(1) as u64);
+        let servers = self
+            .server_list
+            .servers
+            .iter()
+            .filter(|s| s.server_version.is_none())
+            .filter(|s| !self.running.iter().any(|r| r.server_id == s.id))
+            .map(|s| (s.id.clone(), s.path.clone()))
+            .collect();
+        self.server_update_check.start(url, timeout, servers, ctx);
+    }
+
+    fn poll_server_update_check(&mut self) {
+        let Some(report) = self.server_update_check.poll() else {
+            return;
+        };
+        let versions: Vec<(String, String)> = report
+            .versions
+            .iter()
+            .filter_map(|(id, version)| Some((id.clone(), version.clone().ok()?)))
+            .collect();
+        let release = report.release.as_ref().map(|r| r.tag_name.clone()).map_err(String::clone);
+        for (id, version) in versions {
+            self.store_server_version(&id, version);
+        }
+
+        match release {
+            Ok(tag) => {
+                let behind = self.server_list.servers.iter().filter(|s| self.available_update(s).is_some()).count();
+                let message = match behind {
+                    0 => format!("BeamMP-Server {} is the latest release; no server needs updating", tag),
+                    n => format!("BeamMP-Server {} is available for {} server(s)", tag, n),
+                };
+                self.set_status(message, false);
+            }
+            Err(e) => self.set_status(format!("Update check failed: {}", e), true),
+        }
+    }
+
+    fn available_update(&self, server: &server::ServerEntry) -> Option<&updates::Release> {
+        self.server_update_check.available_for(server)
+    }
+
+    /// Move the open update window along: wait for the server to stop, download the new
+    /// binary, then swap it in
+    fn run_server_update(&mut self, ctx: &egui::Context) {
+        let Some(server_id) = self.server_update.as_ref().map(|u| u.server_id.clone()) else {
+            return;
+        };
+        let stopped = self.server_state(&server_id) == ServerState::Stopped;
+        let timeout = Duration::from_secs(self.settings.download_timeout_secs.max(1) as u64);
+        let Some(server_path) = self.server_list.servers.iter().find(|s| s.id == server_id).map(|s| s.path.clone()) else {
+            self.server_update = None;
+            return;
+        };
+        let Some(update) = &mut self.server_update else {
+            return;
+        };
+        if !update.advance(stopped, &server_path, timeout, ctx) {
+            return;
+        }
+
+        let tag = update.release.tag_name.clone();
+        if let Some((major, minor, patch)) = updates::parse_version(&tag) {
+            self.store_server_version(&server_id, format!("{}.{}.{}", major, minor, patch));
+        }
+        if let Some(server) = self.server_list.servers.iter_mut().find(|s| s.id == server_id) {
+            server.check_executable();
+        }
+        self.event_log.record(&server_id, EventKind::Updated, format!("Updated BeamMP-Server to {}", tag));
+    }
+
+    fn package_client_mod(&mut self, folder: PathBuf) {
+        let Some(server) = self.selected_server_index.and_then(|idx| self.server_list.servers.get(idx)) else {
+            return;
+        };
+
+        let server_id = server.id.clone();
+        let result = mods::package_client_mod(
+            &server.path,
+            &server.get_resource_folder(),
+            &folder,
+            self.settings.allow_unknown_mod_structure,
+        );
+        match result {
+            Ok(target) => {
+                let name = target.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default();
+                self.event_log.record(&server_id, EventKind::ModChanged, format!("Packaged {} into client mod {}", folder.display(), name));
+                self.set_status(format!("Packaged {} into Client/{}", folder.display(), name), false);
+                self.reload_mods(ModType::Client);
+            }
+            Err(e) => self.set_status(format!("Failed to package {}: {}", folder.display(), e), true),
+        }
+    }
+
+    /// Install .zip files dropped onto the window as client mods of the selected server
+    fn handle_dropped_files(&mut self, ctx: &egui::Context) {
+        let dropped: Vec<PathBuf> = ctx.input(|i| i.raw.dropped_files.iter().filter_map(|f| f.path.clone()).collect());
+        if dropped.is_empty() {
+            return;
+        }
+        if self.selected_server_index.is_none() {
+            self.set_status("Select a server before dropping client mods onto the window".to_string(), true);
+            return;
+        }
+
+        let (zips, rejected): (Vec<PathBuf>, Vec<PathBuf>) = dropped
+            .into_iter()
+            .partition(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("zip")));
+        if !zips.is_empty() {
+            self.add_client_mods(zips);
+        }
+        if !rejected.is_empty() {
+            let names: Vec<String> = rejected
+                .iter()
+                .map(|path| path.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default())
+                .collect();
+            self.set_status(format!("Only .zip files can be installed as client mods, skipped: {}", names.join(", ")), true);
+        }
+    }
+
+    /// Dim the window and say where dropped files will go while they are dragged over it
+    fn show_drop_overlay(&self, ctx: &egui::Context) {
+        if ctx.input(|i| i.raw.hovered_files.is_empty()) {
+            return;
+        }
+        let text = match self.selected_server_index.and_then(|idx| self.server_list.servers.get(idx)) {
+            Some(server) => format!("Drop .zip files to add them as client mods of {}", server.display_name()),
+            None => "Select a server first to add client mods".to_string(),
+        };
+
+        let painter = ctx.layer_painter(egui::LayerId::new(egui::Order::Foreground, egui::Id::new("drop_overlay")));
+        let screen = ctx.screen_rect();
+        painter.rect_filled(screen, 0.0, egui::Color32::from_black_alpha(192));
+        painter.text(
+            screen.center(),
+            egui::Align2::CENTER_CENTER,
+            text,
+            egui::FontId::proportional(20.0),
+            egui::Color32::WHITE,
+        );
+    }
+
+    fn start_server(&mut self, server_id: String, server_path: std::path::PathBuf) {
+        // An empty key only fails later with a cryptic console error
+        let auth_key_missing = self.server_list.servers.iter()
+            .find(|s| s.id == server_id)
+            .and_then(|s| s.loaded_config.as_ref())
+            .is_some_and(|c| c.general.auth_key.trim().is_empty());
+        if auth_key_missing {
+            self.set_status("Set an Auth Key in the Config tab before starting the server".to_string(), true);
+            return;
+        }
+
+        if self.server_state(&server_id) != ServerState::Stopped {
+            self.set_status("This server is already running".to_string(), true);
+            return;
+        }
+
+        if !server_path.is_dir() {
+            self.set_status("The server folder no longer exists. Locate it from the Config tab.".to_string(), true);
+            return;
+        }
+
+        // The same folder can be in the list twice; both entries would run the same files
+        let same_folder = self.server_list.servers.iter()
+            .filter(|s| s.id != server_id && s.path == server_path)
+            .find(|s| self.server_state(&s.id) != ServerState::Stopped);
+        if let Some(other) = same_folder {
+            self.set_status(format!("This folder is already running as \"{}\"", other.display_name()), true);
+            return;
+        }
+
+        // A copy started from a terminal would make the new one fail on the port, or worse,
+        // run two servers on the same files
+        let own_pids: Vec<u32> = self.running.iter().map(|r| r.process.pid()).collect();
+        if let Some(external) = ExternalProcess::find(&server_path, &own_pids) {
+            self.set_status(format!(
+                "BeamMP-Server is already running from this folder (PID {}). Use Attach to watch it instead of starting a second copy.",
+                external.pid()
+            ), true);
+            return;
+        }
+
+        // Two servers on one port: the second would fail to bind with a less obvious error
+        let port = self.server_list.servers.iter()
+            .find(|s| s.id == server_id)
+            .and_then(|s| s.loaded_config.as_ref())
+            .map(|c| c.general.port);
+        if let Some(port) = port {
+            let port_taken_by = self.server_list.servers.iter()
+                .filter(|s| s.id != server_id && self.running_process(&s.id).is_some())
+                .find(|s| s.loaded_config.as_ref().is_some_and(|c| c.general.port == port));
+            if let Some(other) = port_taken_by {
+                self.set_status(format!("Port {} is already used by running server \"{}\"", port, other.display_name()), true);
+                return;
+            }
+            // Something outside the panel (another program, a server started by hand)
+            if !server::port_is_free(port) {
+                self.set_status(format!("Port {} is already in use on this machine. Stop whatever is using it or pick another port.", port), true);
+                return;
+            }
+        }
+
+        match ServerProcess::start(&server_path) {
+            Ok(process) => {
+                let server_name = self.server_list.servers.iter()
+                    .find(|s| s.id == server_id)
+                    .map(|s| s.display_name().to_string())
+                    .unwrap_or_default();
+                let log = match session_log::SessionLog::create(&server_name) {
+                    Ok(log) => Some(log),
+                    Err(e) => {
+                        self.set_status(format!("Console log file unavailable: {}", e), true);
+                        None
+                    }
+                };
+                // A new run replaces the output of the previous one
+                self.consoles.insert(server_id.clone(), ConsoleBuffer::new(log));
+                self.push_console_line(&server_id, format!("Starting server at {}...", server_path.display()));
+                self.event_log.record(&server_id, EventKind::Started, "Server started".to_string());
+                if let Some(server) = self.server_list.servers.iter_mut().find(|s| s.id == server_id) {
+                    server.last_started = Some(chrono::Local::now().timestamp());
+                }
+                if let Err(e) = self.server_list.save() {
+                    self.set_status(format!("Failed to save server list: {}", e), true);
+                }
+                let started_config = self.server_list.servers.iter()
+                    .find(|s| s.id == server_id)
+                    .and_then(|s| s.loaded_config.clone());
+                self.running.push(RunningProcess {
+                    server_id,
+                    process,
+                    start_time: Instant::now(),
+                    state: ServerState::Starting,
+                    player_list: Vec::new(),
+                    player_parser: players::PlayerListParser::default(),
+                    player_history: players::PlayerHistory::default(),
+                    chat: players::ChatLog::default(),
+                    players_known: false,
+                    last_player_refresh: None,
+                    restart_anchor: chrono::Local::now(),
+                    restart_warned: None,
+                    manual_restart_at: None,
+                    restart_after_stop: false,
+                    started_config,
+                });
+                self.set_status("Server started".to_string(), false);
+            }
+            Err(e) => {
+                self.set_status(format!("Failed to start server: {}", e), true);
+            }
+        }
+    }
+
+    /// Watch a BeamMP-Server that was started outside the panel from this server's folder
+    fn attach_server(&mut self, server_id: &str, server_path: &std::path::Path) {
+        if self.server_state(server_id) != ServerState::Stopped {
+            return;
+        }
+        let own_pids: Vec<u32> = self.running.iter().map(|r| r.process.pid()).collect();
+        let Some(process) = ExternalProcess::find(server_path, &own_pids) else {
+            self.set_status("No running BeamMP-Server found for this folder".to_string(), true);
+            return;
+        };
+        let pid = process.pid();
+        self.attached.push(AttachedProcess {
+            server_id: server_id.to_string(),
+            process,
+        });
+        self.event_log.record(server_id, EventKind::Started, format!("Attached to a server started outside the panel (PID {})", pid));
+        self.set_status(format!("Attached to PID {}. Read-only: output comes from Server.log and commands can't be sent.", pid), false);
+    }
+
+    /// Stop watching an attached server; the server itself keeps running
+    fn detach_server(&mut self, server_id: &str) {
+        self.attached.retain(|a| a.server_id != server_id);
+        self.set_status("Detached. The server keeps running.".to_string(), false);
+    }
+
+    /// Drop attached servers whose process has exited
+    fn poll_attached(&mut self) {
+        let mut exited = Vec::new();
+        self.attached.retain_mut(|attached| {
+            let alive = attached.process.is_running();
+            if !alive {
+                exited.push(attached.server_id.clone());
+            }
+            alive
+        });
+        for server_id in exited {
+            self.event_log.record(&server_id, EventKind::Stopped, "Attached server process exited".to_string());
+            self.set_status("An attached server has stopped".to_string(), false);
+        }
+    }
+
+    /// Add a finished run to the server's persisted runtime total
+    fn record_runtime(&mut self, server_id: &str, start_time: Instant) {
+        if let Some(server) = self.server_list.servers.iter_mut().find(|s| s.id == server_id) {
+            server.total_runtime_secs += start_time.elapsed().as_secs();
+            if let Err(e) = self.server_list.save() {
+                self.set_status(format!("Failed to save server list: {}", e), true);
+            }
+        }
+    }
+
+    /// Remember the BeamMP-Server version a server's binary reported
+    fn store_server_version(&mut self, server_id: &str, version: String) {
+        let Some(server) = self.server_list.servers.iter_mut().find(|s| s.id == server_id) else {
+            return;
+        };
+        if server.server_version.as_ref() == Some(&version) {
+            return;
+        }
+        server.server_version = Some(version);
+        if let Err(e) = self.server_list.save() {
+            self.set_status(format!("Failed to save server list: {}", e), true);
+        }
+    }
+
+    /// Ask the running server to exit. `update_terminal` finishes the stop once the process
+    /// is gone, killing it if it outlives the grace period.
+    fn stop_server(&mut self, server_id: &str) {
+        let Some(running) = self.running.iter_mut().find(|r| r.server_id == server_id) else {
+            return;
+        };
+        if matches!(running.state, ServerState::Stopping { .. }) {
+            return;
+        }
+
+        let grace = Duration::from_secs(self.settings.stop_grace_secs.max(1) as u64);
+        let deadline = match running.process.request_stop() {
+            Ok(_) => Instant::now() + grace,
+            // stdin is gone, so `exit` can never arrive
+            Err(_) => Instant::now(),
+        };
+        running.state = ServerState::Stopping { deadline };
+        self.set_status("Stopping server...".to_string(), false);
+    }
+
+    /// State of the given server; anything without a process is Stopped
+    fn server_state(&self, server_id: &str) -> ServerState {
+        match self.running_process(server_id) {
+            Some(running) => running.state,
+            None if self.attached.iter().any(|a| a.server_id == server_id) => ServerState::Attached,
+            None => ServerState::Stopped,
+        }
+    }
+
+    fn running_process(&self, server_id: &str) -> Option<&RunningProcess> {
+        self.running.iter().find(|r| r.server_id == server_id)
+    }
+
+    fn selected_server_id(&self) -> Option<String> {
+        self.selected_server_index
+            .and_then(|idx| self.server_list.servers.get(idx))
+            .map(|s| s.id.clone())
+    }
+
+    fn connect_with_client(&mut self, idx: usize) {
+        let Some(client_path) = self.settings.client_path.clone() else {
+            self.set_status("Set the BeamMP launcher path in Settings first".to_string(), true);
+            return;
+        };
+        let Some(port) = self.server_list.servers.get(idx)
+            .and_then(|s| s.loaded_config.as_ref())
+            .map(|c| c.general.port)
+        else {
+            self.set_status("Server config is not loaded".to_string(), true);
+            return;
+        };
+
+        let ip = "127.0.0.1";
+        match process::launch_client(&client_path, &self.settings.client_launch_args, ip, port) {
+            Ok(_) => {
+                self.set_status(format!("Launched BeamMP client, join {}:{}", ip, port), false);
+            }
+            Err(e) => {
+                self.set_status(format!("Failed to launch BeamMP client: {}", e), true);
+            }
+        }
+    }
+
+    /// Poll every running server: drain its output into its console and finish
+    /// stops and exits. Returns true if any console changed (for conditional repainting).
+    fn update_terminal(&mut self) -> bool {
+        let mut changed = false;
+        let mut idx = 0;
+        while idx < self.running.len() {
+            let (output_changed, exited) = self.poll_process(idx);
+            changed |= output_changed;
+            if !exited {
+                idx += 1;
+            }
+        }
+        changed
+    }
+
+    /// Returns whether the console changed and whether the process was removed
+    fn poll_process(&mut self, idx: usize) -> (bool, bool) {
+        let running = &mut self.running[idx];
+        let server_id = running.server_id.clone();
+
+        if let Some(status) = running.process.exit_status() {
+            let start_time = running.start_time;
+            let stop_requested = matches!(running.state, ServerState::Stopping { .. });
+            let restart = running.restart_after_stop;
+            // Output printed right before exiting is still queued
+            let last_lines = running.process.read_remaining_output();
+            self.running.remove(idx);
+            self.push_console_lines(&server_id, last_lines);
+            self.record_runtime(&server_id, start_time);
+            if stop_requested && restart {
+                self.push_console_line(&server_id, "Server stopped for a scheduled restart.".to_string());
+                self.event_log.record(&server_id, EventKind::Stopped, "Server stopped for a scheduled restart".to_string());
+                let path = self.server_list.servers.iter()
+                    .find(|s| s.id == server_id)
+                    .map(|s| s.path.clone());
+                if let Some(path) = path {
+                    self.start_server(server_id, path);
+                }
+            } else if stop_requested {
+                // A kill after the grace period is still a requested stop, not a crash
+                self.push_console_line(&server_id, "Server stopped.".to_string());
+                self.event_log.record(&server_id, EventKind::Stopped, "Server stopped from the panel".to_string());
+                self.set_status("Server stopped".to_string(), false);
+            } else if status.success() {
+                self.push_console_line(&server_id, "Server process exited.".to_string());
+                self.event_log.record(&server_id, EventKind::Stopped, "Server process exited".to_string());
+            } else {
+                self.push_console_line(&server_id, "Server process exited.".to_string());
+                self.event_log.record(&server_id, EventKind::Crashed, format!("Server exited unexpectedly ({})", status));
+                if self.settings.notify_on_crash {
+                    notifications::send(
+                        format!("{} crashed", self.server_name(&server_id)),
+                        format!("The server exited unexpectedly ({}).", status),
+                    );
+                }
+            }
+            return (true, true);
+        }
+
+        let stop_overdue = matches!(running.state, ServerState::Stopping { deadline } if Instant::now() >= deadline);
+        if stop_overdue {
+            let grace = self.settings.stop_grace_secs;
+            if let Err(e) = running.process.kill() {
+                self.set_status(format!("Failed to stop server: {}", e), true);
+            } else {
+                self.push_console_line(&server_id, format!("Server did not exit within {} seconds, killed it.", grace));
+            }
+            return (true, false);
+        }
+
+        running.process.sample_usage();
+        let new_lines = running.process.read_output(MAX_LINES_PER_FRAME);
+
+        let running = &mut self.running[idx];
+        // The version banner is among the first lines; later ones could be chat
+        let banner_version = (running.state == ServerState::Starting)
+            .then(|| new_lines.iter().find_map(|line| process::banner_version(line)))
+            .flatten();
+        let mut player_events = Vec::new();
+        let mut became_ready = false;
+        if running.state == ServerState::Starting
+            && (new_lines.iter().any(|line| process::is_ready_line(line))
+                || running.start_time.elapsed() >= READY_TIMEOUT)
+        {
+            running.state = ServerState::Running;
+            became_ready = true;
+        }
+        for line in &new_lines {
+            if let Some(players) = running.player_parser.feed(line) {
+                running.player_list = players;
+                running.players_known = true;
+            }
+            if let Some(event) = players::parse_player_event(line) {
+                player_events.push(match &event {
+                    players::PlayerEvent::Joined(name) => (EventKind::PlayerJoined, format!("{} joined", name)),
+                    players::PlayerEvent::Left(name) => (EventKind::PlayerLeft, format!("{} left", name)),
+                });
+                running.player_history.record(event);
+            }
+            if let Some(chat) = players::parse_chat_line(line) {
+                running.chat.push(chat);
+            }
+        }
+        for (kind, message) in player_events {
+            self.event_log.record(&server_id, kind, message);
+        }
+        if became_ready {
+            self.set_status("Server is ready".to_string(), false);
+        }
+        if let Some(version) = banner_version {
+            self.store_server_version(&server_id, version);
+        }
+        self.notify_highlights(&server_id, &new_lines);
+
+        let has_new_output = !new_lines.is_empty();
+        let console = self.consoles.entry(server_id).or_insert_with(|| ConsoleBuffer::new(None));
+        console.output_rate.record(new_lines.len());
+        console.push(new_lines, self.settings.scrollback_lines());
+        (has_new_output, false)
+    }
+
+    /// Desktop notification for new lines matching a highlight rule that asks for one
+    fn notify_highlights(&mut self, server_id: &str, lines: &[String]) {
+        let mut matches = lines.iter().filter(|line| self.console_view.highlighter.notifies(line));
+        let Some(first) = matches.next() else {
+            return;
+        };
+        if self.highlight_notified.get(server_id).is_some_and(|at| at.elapsed() < HIGHLIGHT_NOTIFY_INTERVAL) {
+            return;
+        }
+        let more = matches.count();
+        let body = if more > 0 {
+            format!("{}\n(and {} more)", first, more)
+        } else {
+            first.clone()
+        };
+        notifications::send(self.server_name(server_id), body);
+        self.highlight_notified.insert(server_id.to_string(), Instant::now());
+    }
+
+    fn push_console_line(&mut self, server_id: &str, line: String) {
+        self.push_console_lines(server_id, vec![line]);
+    }
+
+    fn push_console_lines(&mut self, server_id: &str, lines: Vec<String>) {
+        if lines.is_empty() {
+            return;
+        }
+        self.consoles
+            .entry(server_id.to_string())
+            .or_insert_with(|| ConsoleBuffer::new(None))
+            .push(lines, self.settings.scrollback_lines());
+    }
+
+    fn export_console_log(&mut self, server_id: &str) {
+        let Some(console) = self.consoles.get_mut(server_id) else {
+            return;
+        };
+        let default_name = format!("console-{}.log", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        let Some(target) = rfd::FileDialog::new()
+            .add_filter("Log files", &["log", "txt"])
+            .set_file_name(default_name)
+            .save_file()
+        else {
+            return;
+        };
+
+        let result = match &mut console.log {
+            Some(log) => log.export(&target),
+            // No log file for this run: fall back to what's still in memory
+            None => std::fs::write(&target, console.lines.join("\n")).map_err(Into::into),
+        };
+        match result {
+            Ok(_) => self.set_status(format!("Console log exported to {}", target.display()), false),
+            Err(e) => self.set_status(format!("Failed to export console log: {}", e), true),
+        }
+    }
+
+    fn export_player_history(&mut self, server_id: &str) {
+        let Some(running) = self.running_process(server_id) else {
+            return;
+        };
+        let csv = running.player_history.to_csv();
+        let default_name = format!("players-{}.csv", chrono::Local::now().format("%Y%m%d-%H%M%S"));
+        let Some(target) = rfd::FileDialog::new()
+            .add_filter("CSV files", &["csv"])
+            .set_file_name(default_name)
+            .save_file()
+        else {
+            return;
+        };
+
+        match std::fs::write(&target, csv) {
+            Ok(_) => self.set_status(format!("Player history exported to {}", target.display()), false),
+            Err(e) => self.set_status(format!("Failed to export player history: {}", e), true),
+        }
+    }
+
+    /// Keep the Server.log tail on the selected server while the console shows it.
+    /// Returns whether new lines were read.
+    fn sync_server_log_tail(&mut self) -> bool {
+        let selected = self.selected_server_index
+            .and_then(|idx| self.server_list.servers.get(idx))
+            .filter(|s| self.shows_server_log(&s.id));
+        let Some(server) = selected else {
+            self.server_log_tail = None;
+            return false;
+        };
+        if self.server_log_tail.as_ref().is_none_or(|t| t.server_id != server.id) {
+            self.server_log_tail = Some(log_tail::LogTail::new(
+                server.id.clone(),
+                server.path.join(log_tail::SERVER_LOG_FILE),
+                self.settings.scrollback_lines(),
+            ));
+        }
+        let max_lines = self.settings.scrollback_lines();
+        self.server_log_tail.as_mut().is_some_and(|t| {
+            t.set_max_lines(max_lines);
+            t.poll()
+        })
+    }
+
+    /// Whether the console shows the server's Server.log rather than its process output
+    fn shows_server_log(&self, server_id: &str) -> bool {
+        self.console_view.server_log || self.attached.iter().any(|a| a.server_id == server_id)
+    }
+
+    /// Console actions always apply to the selected server's console
+    fn handle_console_action(&mut self, action: ui::console::ConsoleAction) {
+        let Some(server_id) = self.selected_server_id() else {
+            return;
+        };
+        match action {
+            ui::console::ConsoleAction::Clear if self.shows_server_log(&server_id) => {
+                // Only clears the view; new lines keep coming from the file
+                if let Some(tail) = &mut self.server_log_tail {
+                    tail.lines.clear();
+                }
+            }
+            ui::console::ConsoleAction::Clear => {
+                if self.server_state(&server_id) == ServerState::Stopped {
+                    // Nothing more will arrive; dropping the buffer also hides the console
+                    self.consoles.remove(&server_id);
+                } else if let Some(console) = self.consoles.get_mut(&server_id) {
+                    console.clear();
+                }
+            }
+            ui::console::ConsoleAction::ToggleDetached => self.console_detached = !self.console_detached,
+            ui::console::ConsoleAction::ExportLog => self.export_console_log(&server_id),
+            ui::console::ConsoleAction::SendCommand(command) => {
+                self.console_input.push_history(&command);
+                self.send_console_input(&server_id, &command);
+            }
+            ui::console::ConsoleAction::None => {}
+        }
+    }
+
+    /// Render the popped-out console in its own native window. Closing that
+    /// window docks the console back into the main window.
+    fn show_detached_console(&mut self, ctx: &egui::Context) {
+        let mut action = ui::console::ConsoleAction::None;
+        let mut close_requested = false;
+        let server_id = self.selected_server_id().unwrap_or_default();
+        let lines = console_lines(self.shows_server_log(&server_id), &self.server_log_tail, &self.consoles, &server_id, self.settings.console_timestamps).unwrap_or_default();
+        let default_rate = ui::console::OutputRate::default();
+        let output_rate = self.consoles.get(&server_id).map_or(&default_rate, |c| &c.output_rate);
+
+        ctx.show_viewport_immediate(
+            egui::ViewportId::from_hash_of("detached_console"),
+            egui::ViewportBuilder::default()
+                .with_title("BeamMP Panel - Server Console")
+                .with_inner_size([900.0, 450.0]),
+            |ctx, class| {
+                if class == egui::ViewportClass::Embedded {
+                    // Backend can't open extra native windows; fall back to a floating egui window
+                    let mut open = true;
+                    egui::Window::new("Server Console")
+                        .open(&mut open)
+                        .default_size([800.0, 400.0])
+                        .show(ctx, |ui| {
+                            action = ui::console::show(ui, lines, &mut self.console_view, true, output_rate, &mut self.console_input, &mut self.console_filter);
+                        });
+                    close_requested = !open;
+                } else {
+                    egui::CentralPanel::default().show(ctx, |ui| {
+                        action = ui::console::show(ui, lines, &mut self.console_view, true, output_rate, &mut self.console_input, &mut self.console_filter);
+                    });
+                    close_requested = ctx.input(|i| i.viewport().close_requested());
+                }
+            },
+        );
+
+        if close_requested {
+            self.console_detached = false;
+        }
+        self.handle_console_action(action);
+    }
+
+    /// Send a console command, echoing it in the console. Failures are shown in the status
+    /// bar and also returned, for callers that have to tell someone else.
+    fn send_server_command(&mut self, server_id: &str, command: &str) -> anyhow::Result<()> {
+        let Some(running) = self.running_process(server_id) else {
+            self.set_status("No server is running".to_string(), true);
+            return Err(anyhow::anyhow!("No server is running"));
+        };
+        match running.process.send_command(command) {
+            Ok(_) => {
+                self.push_console_line(server_id, format!("> {}", command));
+                self.set_status(format!("Command sent: {}", command), false);
+                Ok(())
+            }
+            Err(e) => {
+                self.report_send_error(server_id, "command", &e);
+                Err(e)
+            }
+        }
+    }
+
+    /// Report a failed write to a server's console. A closed pipe means the server has
+    /// exited or is on its way out, so its exit is handled now rather than on the next poll.
+    fn report_send_error(&mut self, server_id: &str, what: &str, error: &anyhow::Error) {
+        if !process::is_pipe_closed(error) {
+            self.set_status(format!("Failed to send {}: {}", what, error), true);
+            return;
+        }
+        let removed = self.running.iter()
+            .position(|r| r.server_id == server_id)
+            .is_some_and(|idx| self.poll_process(idx).1);
+        let reason = if removed {
+            "the server is not running (pipe closed)"
+        } else {
+            "the server stopped reading commands (pipe closed) and is probably shutting down"
+        };
+        self.set_status(format!("Failed to send {}: {}", what, reason), true);
+    }
+
+    /// A line typed into the console. Sent as is, so it can also enter and leave the Lua console.
+    fn send_console_input(&mut self, server_id: &str, line: &str) {
+        let Some(running) = self.running_process(server_id) else {
+            self.set_status("No server is running".to_string(), true);
+            return;
+        };
+        match running.process.send_input(line) {
+            Ok(_) => self.push_console_line(server_id, format!("> {}", line)),
+            Err(e) => self.report_send_error(server_id, "command", &e),
+        }
+    }
+
+    /// Chat message to a single player, sent through the Lua console
+    fn whisper(&mut self, server_id: &str, name: &str, message: &str) {
+        let Some(running) = self.running_process(server_id) else {
+            self.set_status("No server is running".to_string(), true);
+            return;
+        };
+        match running.process.run_lua("", &process::private_message_lua(name, message)) {
+            Ok(_) => {
+                self.push_console_line(server_id, format!("> whisper {}: {}", name, message));
+                self.set_status(format!("Message sent to {}", name), false);
+            }
+            Err(e) => self.report_send_error(server_id, "message", &e),
+        }
+    }
+
+    fn run_lua(&mut self, server_id: &str, state: &str, code: &str) {
+        let Some(running) = self.running_process(server_id) else {
+            self.set_status("No server is running".to_string(), true);
+            return;
+        };
+        match running.process.run_lua(state, code) {
+            Ok(_) => {
+                let lines = code.trim().lines().map(|line| format!("lua> {}", line)).collect();
+                self.push_console_lines(server_id, lines);
+                self.set_status("Lua code sent".to_string(), false);
+            }
+            Err(e) => self.report_send_error(server_id, "Lua code", &e),
+        }
+    }
+
+    /// Broadcast a panel message to the players of a running server. Quiet: only echoed
+    /// to the console, since it isn't a response to anything the user clicked.
+    fn announce(&mut self, server_id: &str, message: &str) {
+        let command = format!("say {}", message);
+        let sent = self.running_process(server_id)
+            .is_some_and(|r| r.process.send_command(&command).is_ok());
+        if sent {
+            self.push_console_line(server_id, format!("> {}", command));
+        }
+    }
+
+    /// Warn players of upcoming restarts and restart the servers that are due. Runs every
+    /// frame; the countdown is just a comparison against the clock, nothing waits.
+    fn run_pending_restarts(&mut self) {
+        let now = chrono::Local::now();
+        let mut warnings = Vec::new();
+        let mut due = Vec::new();
+        for running in &mut self.running {
+            // Starting servers have no players to warn yet; stopping ones are already handled
+            if running.state != ServerState::Running {
+                continue;
+            }
+            let Some(server) = self.server_list.servers.iter().find(|s| s.id == running.server_id) else {
+                continue;
+            };
+            let Some((next, points)) = running.next_restart(server, &self.settings.restart_countdown_secs) else {
+                continue;
+            };
+
+            let remaining = (next - now).num_seconds();
+            if remaining <= 0 {
+                due.push(running.server_id.clone());
+                continue;
+            }
+            // The schedule was moved later after a warning went out: count down again
+            if running.restart_warned.is_some_and(|warned| remaining > warned) {
+                running.restart_warned = None;
+            }
+            let point = points.into_iter().rfind(|&point| remaining <= point);
+            if let Some(point) = point {
+                if running.restart_warned.is_none_or(|warned| point < warned) {
+                    running.restart_warned = Some(point);
+                    warnings.push((running.server_id.clone(), remaining));
+                }
+            }
+        }
+
+        for (server_id, remaining) in warnings {
+            self.announce(&server_id, &format!("Server restarting in {}", schedule::format_countdown(remaining)));
+        }
+        for server_id in due {
+            self.announce(&server_id, "Restarting the server now");
+            self.restart_now(&server_id);
+        }
+    }
+
+    /// Stop the server and start it again as soon as it has exited
+    fn restart_now(&mut self, server_id: &str) {
+        if let Some(running) = self.running.iter_mut().find(|r| r.server_id == server_id) {
+            running.restart_after_stop = true;
+        }
+        self.stop_server(server_id);
+    }
+
+    /// Whether the saved config differs from the one the running server started with
+    fn restart_required(&self, server: &server::ServerEntry) -> bool {
+        self.running_process(&server.id).is_some_and(|running| {
+            matches!(running.state, ServerState::Starting | ServerState::Running)
+                && running.manual_restart_at.is_none()
+                && running.started_config.is_some()
+                && server.loaded_config.is_some()
+                && running.started_config != server.loaded_config
+        })
+    }
+
+    fn restart_schedule_changed(&mut self, server_id: &str) {
+        if let Err(e) = self.server_list.save() {
+            self.set_status(format!("Failed to save server list: {}", e), true);
+        }
+
+        let Some(server) = self.server_list.servers.iter().find(|s| s.id == server_id) else {
+            return;
+        };
+        let Some(running) = self.running.iter_mut().find(|r| r.server_id == server_id) else {
+            return;
+        };
+        // A new time that already passed during this run would restart right away; count
+        // from now instead
+        let now = chrono::Local::now();
+        if server.restart.next_restart(running.restart_anchor).is_some_and(|next| next <= now) {
+            running.restart_anchor = now;
+        }
+        if !server.restart.enabled {
+            self.cancel_restart_countdown(server_id);
+        }
+    }
+
+    /// Move the server's restart schedule past its next restart
+    fn skip_scheduled_restart(&mut self, server_id: &str) {
+        let next = self.server_list.servers.iter()
+            .find(|s| s.id == server_id)
+            .zip(self.running_process(server_id))
+            .and_then(|(server, running)| server.restart.next_restart(running.restart_anchor));
+        let Some(next) = next else {
+            return;
+        };
+        self.cancel_restart_countdown(server_id);
+        if let Some(running) = self.running.iter_mut().find(|r| r.server_id == server_id) {
+            running.restart_anchor = next;
+        }
+        self.set_status(format!("Skipped the restart at {}", next.format("%Y-%m-%d %H:%M")), false);
+    }
+
+    /// Restart the server after counting down in chat; immediately if no countdown is set
+    fn restart_with_warning(&mut self, server_id: &str) {
+        let lead = schedule::countdown_points(&self.settings.restart_countdown_secs).first().copied().unwrap_or(0);
+        let Some(running) = self.running.iter_mut().find(|r| r.server_id == server_id) else {
+            return;
+        };
+        if running.manual_restart_at.is_some() {
+            return;
+        }
+        let at = chrono::Local::now() + chrono::Duration::seconds(lead);
+        running.manual_restart_at = Some(at);
+        self.set_status(format!("Restarting in {}", schedule::format_countdown(lead)), false);
+    }
+
+    /// Stop the countdown of the upcoming restart: a pending "Restart with Warning", or
+    /// otherwise the next scheduled restart
+    fn cancel_pending_restart(&mut self, server_id: &str) {
+        let Some(running) = self.running.iter_mut().find(|r| r.server_id == server_id) else {
+            return;
+        };
+        if running.manual_restart_at.take().is_some() {
+            self.cancel_restart_countdown(server_id);
+            self.set_status("Restart cancelled".to_string(), false);
+        } else {
+            self.skip_scheduled_restart(server_id);
+        }
+    }
+
+    /// Tell players a restart they were warned about won't happen
+    fn cancel_restart_countdown(&mut self, server_id: &str) {
+        let Some(running) = self.running.iter_mut().find(|r| r.server_id == server_id) else {
+            return;
+        };
+        if running.restart_warned.take().is_some() {
+            self.announce(server_id, "The scheduled server restart was cancelled");
+        }
+    }
+
+    fn refresh_player_list(&mut self, server_id: &str) {
+        let Some(running) = self.running.iter_mut().find(|r| r.server_id == server_id) else {
+            return;
+        };
+        if running.last_player_refresh.is_some_and(|t| t.elapsed() < PLAYER_REFRESH_COOLDOWN) {
+            self.set_status("Player list was just refreshed, try again in a moment".to_string(), true);
+            return;
+        }
+        running.last_player_refresh = Some(Instant::now());
+        let _ = self.send_server_command(server_id, "list");
+        // The list itself is filled in by update_terminal as the response arrives
+        if let Some(running) = self.running.iter_mut().find(|r| r.server_id == server_id) {
+            running.player_parser.expect_list();
+        }
+    }
+
+    /// Periodically re-issue `list` on every running server, for the player counts in
+    /// the server list. The server whose Control tab is open is refreshed at the rate
+    /// set in Settings instead.
+    fn auto_refresh_player_lists(&mut self) {
+        let control_tab_id = (self.current_tab == Tab::Control && !self.show_settings)
+            .then(|| self.selected_server_id())
+            .flatten();
+        let control_tab_interval = match self.settings.player_refresh_interval_secs {
+            0 => PLAYER_COUNT_REFRESH_INTERVAL,
+            secs => Duration::from_secs(secs as u64).max(PLAYER_REFRESH_COOLDOWN),
+        };
+
+        for running in &mut self.running {
+            if running.state != ServerState::Running {
+                continue;
+            }
+            let interval = if control_tab_id.as_deref() == Some(running.server_id.as_str()) {
+                control_tab_interval
+            } else {
+                PLAYER_COUNT_REFRESH_INTERVAL
+            };
+            if running.last_player_refresh.is_some_and(|t| t.elapsed() < interval) {
+                continue;
+            }
+
+            // Quiet send: no status message or echo for background refreshes
+            running.last_player_refresh = Some(Instant::now());
+            if running.process.send_command("list").is_ok() {
+                running.player_parser.expect_list();
+            }
+        }
+    }
+
+    /// Create or remove the tray icon so it matches the current settings
+    fn sync_tray(&mut self, ctx: &egui::Context) {
+        if !self.settings.tray_icon {
+            self.tray = None;
+            return;
+        }
+
+        if self.tray.is_none() {
+            let created = load_icon()
+                .ok_or_else(|| anyhow::anyhow!("the icon could not be loaded"))
+                .and_then(|icon| tray::Tray::new(ctx, &icon));
+            match created {
+                Ok(tray) => self.tray = Some(tray),
+                Err(e) => {
+                    // Turn the toggle back off instead of retrying every frame
+                    self.settings.tray_icon = false;
+                    self.set_status(format!("Failed to create the tray icon: {}", e), true);
+                    return;
+                }
+            }
+            // Only once there is an icon to bring the window back with
+            if std::mem::take(&mut self.hide_on_start) {
+                ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+            }
+        }
+
+        let events = self.tray.as_ref().map(|tray| tray.events()).unwrap_or_default();
+        for event in events {
+            ctx.send_viewport_cmd(egui::ViewportCommand::Visible(true));
+            ctx.send_viewport_cmd(egui::ViewportCommand::Minimized(false));
+            ctx.send_viewport_cmd(egui::ViewportCommand::Focus);
+            if event == tray::TrayEvent::Quit {
+                // Through the usual close path, which asks about unsaved config edits
+                self.quit_requested = true;
+                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+            }
+        }
+    }
+
+    /// Whether closing the window should hide it in the tray instead of quitting
+    fn hides_to_tray(&self) -> bool {
+        self.settings.minimize_to_tray
+            && self.tray.is_some()
+            && !self.quit_requested
+            && !self.close_confirmed
+            && !self.quit_confirmed
+    }
+
+    fn handle_api_requests(&mut self) {
+        for request in self.api.pending_requests() {
+            let response = self.api_response(&request.command);
+            request.respond(response);
+        }
+    }
+
+    fn server_json(&self, server: &server::ServerEntry) -> serde_json::Value {
+        let running = self.running_process(&server.id);
+        serde_json::json!({
+            "id": server.id,
+            "name": server.display_name(),
+            "path": server.path,
+            "port": server.loaded_config.as_ref().map(|c| c.general.port),
+            "running": running.is_some(),
+            "state": self.server_state(&server.id).label(),
+            "ready": running.is_some_and(|r| r.state == ServerState::Running),
+            "uptime_secs": running.map(|r| r.start_time.elapsed().as_secs()),
+            "last_started": server.last_started,
+            "total_runtime_secs": server.total_runtime_secs,
+        })
+    }
+
+    fn api_response(&mut self, command: &api::ApiCommand) -> api::ApiResponse {
+        use api::{ApiCommand, ApiResponse};
+
+        let find = |servers: &[server::ServerEntry], id: &str| servers.iter().position(|s| s.id == id);
+
+        match command {
+            ApiCommand::ListServers => {
+                let servers: Vec<_> = self.server_list.servers.iter().map(|s| self.server_json(s)).collect();
+                ApiResponse::ok(serde_json::Value::Array(servers))
+            }
+            ApiCommand::Status(id) => match find(&self.server_list.servers, id) {
+                Some(idx) => ApiResponse::ok(self.server_json(&self.server_list.servers[idx])),
+                None => ApiResponse::error(404, "No server with that id"),
+            },
+            ApiCommand::Start(id) => {
+                let Some(idx) = find(&self.server_list.servers, id) else {
+                    return ApiResponse::error(404, "No server with that id");
+                };
+                if self.server_state(id) != ServerState::Stopped {
+                    return ApiResponse::error(409, "Server is already running");
+                }
+                let path = self.server_list.servers[idx].path.clone();
+                self.start_server(id.clone(), path);
+                if self.running_process(id).is_some() {
+                    ApiResponse::ok(serde_json::json!({ "started": true }))
+                } else {
+                    let message = self.status_message.as_ref().map(|s| s.text.clone()).unwrap_or_default();
+                    ApiResponse::error(500, message)
+                }
+            }
+            ApiCommand::Stop(id) => {
+                if find(&self.server_list.servers, id).is_none() {
+                    return ApiResponse::error(404, "No server with that id");
+                }
+                match self.server_state(id) {
+                    ServerState::Stopped => return ApiResponse::error(409, "Server is not running"),
+                    ServerState::Stopping { .. } => return ApiResponse::error(409, "Server is already stopping"),
+                    ServerState::Attached => {
+                        return ApiResponse::error(409, "Server was started outside the panel and can't be stopped from it");
+                    }
+                    ServerState::Starting | ServerState::Running => {}
+                }
+                self.stop_server(id);
+                ApiResponse::ok(serde_json::json!({ "stopped": true }))
+            }
+            ApiCommand::Command(id, command) => {
+                if find(&self.server_list.servers, id).is_none() {
+                    return ApiResponse::error(404, "No server with that id");
+                }
+                match self.server_state(id) {
+                    ServerState::Stopped => return ApiResponse::error(409, "Server is not running"),
+                    ServerState::Starting | ServerState::Stopping { .. } => {
+                        return ApiResponse::error(409, "Server is still starting or is stopping");
+                    }
+                    ServerState::Attached => {
+                        return ApiResponse::error(409, "Server was started outside the panel; commands can't be sent to it");
+                    }
+                    ServerState::Running => {}
+                }
+                match self.send_server_command(id, command) {
+                    Ok(()) => ApiResponse::ok(serde_json::json!({ "sent": command })),
+                    Err(e) if process::is_pipe_closed(&e) => {
+                        ApiResponse::error(409, "Server stopped reading commands and is probably shutting down")
+                    }
+                    Err(e) => ApiResponse::error(500, format!("Failed to send the command: {}", e)),
+                }
+            }
+        }
+    }
+
+    /// Push the scale and console font settings into egui, on the first frame and after changes
+    fn apply_appearance(&mut self, ctx: &egui::Context) {
+        let appearance = (self.settings.ui_scale.clamp(0.75, 2.0), self.settings.console_font_size);
+        if self.applied_appearance == Some(appearance) {
+            return;
+        }
+        ctx.set_zoom_factor(appearance.0);
+        ui::console::register_font(ctx, appearance.1);
+        self.applied_appearance = Some(appearance);
+    }
+
+    /// Remember the window geometry for the next launch and, once at startup, move the
+    /// window back on screen if it was saved on a monitor setup that no longer exists
+    fn track_window_geometry(&mut self, ctx: &egui::Context) {
+        let (inner, outer, maximized, monitor, close_requested) = ctx.input(|i| {
+            let viewport = i.viewport();
+            (
+                viewport.inner_rect,
+                viewport.outer_rect,
+                viewport.maximized.unwrap_or(false),
+                viewport.monitor_size,
+                viewport.close_requested(),
+            )
+        });
+
+        if !self.window_position_checked {
+            if let Some(monitor) = monitor {
+                self.window_position_checked = true;
+                let saved_monitor = self.settings.window.and_then(|w| w.monitor_size);
+                if saved_monitor.is_some_and(|saved| saved != [monitor.x, monitor.y]) {
+                    let size = inner.map(|r| r.size()).unwrap_or(egui::Vec2::from(DEFAULT_WINDOW_SIZE));
+                    let centered = ((monitor - size) / 2.0).max(egui::Vec2::ZERO);
+                    ctx.send_viewport_cmd(egui::ViewportCommand::OuterPosition(centered.to_pos2()));
+                }
+            }
+        }
+
+        let mut geometry = self.settings.window.unwrap_or(WindowGeometry {
+            size: DEFAULT_WINDOW_SIZE,
+            position: None,
+            maximized: false,
+            monitor_size: None,
+        });
+        geometry.maximized = maximized;
+        // While maximized keep the restored size/position so un-maximizing next launch works
+        if !maximized {
+            if let Some(inner) = inner {
+                geometry.size = [inner.width(), inner.height()];
+            }
+            if let Some(outer) = outer {
+                geometry.position = Some([outer.min.x, outer.min.y]);
+            }
+            if let Some(monitor) = monitor {
+                geometry.monitor_size = Some([monitor.x, monitor.y]);
+            }
+        }
+        self.settings.window = Some(geometry);
+
+        if close_requested {
+            let _ = self.settings.save();
+        }
+    }
+}
+
+impl eframe::App for BeamMpManagerApp {
+    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
+        self.console_view.highlighter.update(&self.settings.highlight_rules);
+        self.console_view.scrollback = self.settings.scrollback_lines();
+        // Update terminal output and check if there were changes
+        let terminal_changed = self.update_terminal() | self.sync_server_log_tail();
+        self.poll_attached();
+        self.auto_refresh_player_lists();
+        self.api.sync(self.settings.api_enabled, self.settings.api_port, &self.settings.api_token, ctx);
+        self.handle_api_requests();
+        self.sync_tray(ctx);
+        self.run_pending_restarts();
+        self.track_window_geometry(ctx);
+        self.apply_appearance(ctx);
+        self.handle_dropped_files(ctx);
+        self.poll_mod_downloads();
+        self.poll_disk_usage();
+        self.flush_event_log(ctx);
+        if self.settings.check_server_updates && matches!(self.server_update_check, updates::UpdateCheck::NotChecked) {
+            self.check_server_updates(ctx);
+        }
+        self.poll_server_update_check();
+        self.run_server_update(ctx);
+        self.run_config_autosave(ctx);
+        self.finish_quit_after_stop(ctx);
+        if ctx.input(|i| i.viewport().close_requested()) {
+            if self.hides_to_tray() {
+                // Only the window goes away; servers keep running and Show brings it back
+                ctx.send_viewport_cmd(egui::ViewportCommand::CancelClose);
+                ctx.send_viewport_cmd(egui::ViewportCommand::Visible(false));
+            } else if self.guard_close(ctx) || self.guard_running_servers(ctx) {
+                // If the prompt is cancelled, the close button hides again
+                self.quit_requested = false;
+            } else {
+                // Nothing restarts the servers once the panel is gone
+                let ids: Vec<String> = self.running.iter().map(|r| r.server_id.clone()).collect();
+                for id in ids {
+                    self.cancel_restart_countdown(&id);
+                }
+            }
+        }
+        
+        // Repaint immediately while output is flowing (there may be more queued than
+        // one frame's worth), otherwise just keep polling at a low rate
+        if terminal_changed {
+            ctx.request_repaint();
+        } else if !self.running.is_empty() || !self.attached.is_empty() || self.server_log_tail.is_some() {
+            ctx.request_repaint_after(OUTPUT_POLL_INTERVAL);
+        }
+        // Handle delete confirmation modal
+        if let Some(confirmation) = &self.delete_confirmation {
+            let mut should_close = false;
+            let mut should_confirm = false;
+
+            egui::Window::new("Confirm Deletion")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    match confirmation {
+                        DeleteConfirmation::Server(_) => {
+                            ui.label("Remove this server from the manager?");
+                            ui.label("This will NOT delete any files.");
+                        }
+                        DeleteConfirmation::Mod(idx) => {
+                            if let Some(cache) = &self.mods_cache {
+                                if let Some(mod_entry) = cache.mods.get(*idx) {
+                                    ui.label("Delete this mod?");
+                                    ui.label(&mod_entry.relative_path);
+                                    ui.label("It is moved to the server's trash, where Undo or 🗑 Trash can restore it.");
+                                }
+                            }
+                        }
+                        DeleteConfirmation::Mods(indices) => {
+                            if let Some(cache) = &self.mods_cache {
+                                ui.label(format!("Delete these {} mods?", indices.len()));
+                                egui::ScrollArea::vertical()
+                                    .max_height(200.0)
+                                    .show(ui, |ui| {
+                                        for mod_entry in indices.iter().filter_map(|idx| cache.mods.get(*idx)) {
+                                            ui.label(format!("  • {}", mod_entry.relative_path));
+                                        }
+                                    });
+                                ui.label("They are moved to the server's trash, where Undo or 🗑 Trash can restore them.");
+                            }
+                        }
+                    }
+                    
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Cancel").clicked() {
+                            should_close = true;
+                        }
+                        if ui.button("Confirm").clicked() {
+                            should_confirm = true;
+                        }
+                    });
+                });
+
+            if should_close {
+                self.delete_confirmation = None;
+            }
+            if should_confirm {
+                match self.delete_confirmation.take() {
+                    Some(DeleteConfirmation::Server(idx)) => {
+                        self.selected_server_index = Some(idx);
+                        self.remove_selected_server();
+                    }
+                    Some(DeleteConfirmation::Mod(idx)) => {
+                        self.delete_mods(&[idx]);
+                    }
+                    Some(DeleteConfirmation::Mods(indices)) => {
+                        self.delete_mods(&indices);
+                    }
+                    None => {}
+                }
+            }
+        }
+
+        if let Some(copy) = &mut self.mod_copy {
+            let mut action = ui::mod_copy::CopyAction::None;
+
+            egui::Window::new("Copy Mods")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    action = ui::mod_copy::show(ui, copy, &self.server_list.servers);
+                });
+
+            if let ui::mod_copy::CopyAction::Check = action {
+                let existing = copy.existing_in_target(&self.server_list.servers);
+                if existing.is_empty() {
+                    action = ui::mod_copy::CopyAction::Copy { overwrite: false };
+                } else {
+                    copy.existing = Some(existing);
+                }
+            }
+            match action {
+                ui::mod_copy::CopyAction::None | ui::mod_copy::CopyAction::Check => {}
+                ui::mod_copy::CopyAction::Cancel => self.mod_copy = None,
+                ui::mod_copy::CopyAction::Copy { overwrite } => {
+                    if let Some(copy) = self.mod_copy.take() {
+                        self.copy_mods(copy, overwrite);
+                    }
+                }
+            }
+        }
+
+        // Handle server list import modal
+        if let Some(preview) = &self.import_confirmation {
+            let mut chosen_mode = None;
+            let mut should_close = false;
+
+            egui::Window::new("Import Server List")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(format!("The file contains {} server(s):", preview.entries.len()));
+                    ui.label(format!("  • {} new", preview.new_count));
+                    ui.label(format!("  • {} already in the list (matched by path)", preview.matching_count));
+                    ui.add_space(5.0);
+                    ui.label("Merge keeps your current servers and adds only the new ones.");
+                    ui.label(format!(
+                        "Replace removes all {} current server(s) from the manager first.",
+                        self.server_list.servers.len()
+                    ));
+                    ui.label("No server files are modified either way.");
+
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Cancel").clicked() {
+                            should_close = true;
+                        }
+                        if ui.button("Merge").clicked() {
+                            chosen_mode = Some(ImportMode::Merge);
+                        }
+                        if ui.button("Replace").clicked() {
+                            chosen_mode = Some(ImportMode::Replace);
+                        }
+                    });
+                });
+
+            if should_close {
+                self.import_confirmation = None;
+            }
+            if let Some(mode) = chosen_mode {
+                if let Some(preview) = self.import_confirmation.take() {
+                    if let Some(text) = self.apply_import(preview.entries, mode) {
+                        self.set_status(text, false);
+                    }
+                }
+            }
+        }
+
+        // Handle server bundle import modal
+        if let Some(import) = &mut self.bundle_import {
+            let mut action = ui::bundle_import::BundleImportAction::None;
+
+            egui::Window::new("Import Server Bundle")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    action = ui::bundle_import::show(ui, import, &self.server_list.servers);
+                });
+
+            match action {
+                ui::bundle_import::BundleImportAction::None => {}
+                ui::bundle_import::BundleImportAction::Cancel => self.bundle_import = None,
+                ui::bundle_import::BundleImportAction::Import(mode) => {
+                    if let Some(import) = self.bundle_import.take() {
+                        self.apply_bundle_import(import, mode);
+                    }
+                }
+            }
+        }
+
+        // Handle damaged server list modal (shown once at startup)
+        if let Some(problem) = &self.server_list_problem {
+            let mut should_close = false;
+
+            egui::Window::new("Server List Recovered")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.set_max_width(450.0);
+                    ui.label(problem);
+
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("OK").clicked() {
+                            should_close = true;
+                        }
+                        if ui.button("📂 Open Config Directory").clicked() {
+                            if let Ok(dir) = server::config_dir() {
+                                let _ = process::open_path(&dir);
+                            }
+                        }
+                    });
+                });
+
+            if should_close {
+                self.server_list_problem = None;
+            }
+        }
+
+        // Handle interrupted mod move modal (shown once at startup)
+        if let Some(entries) = &self.split_mods_prompt {
+            let mut should_close = false;
+            let mut keep_enabled = None;
+
+            egui::Window::new("Interrupted Mod Moves")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label("These mods exist in both the enabled and the disabled folder,");
+                    ui.label("probably because an enable/disable was interrupted:");
+                    egui::ScrollArea::vertical()
+                        .max_height(200.0)
+                        .show(ui, |ui| {
+                            for entry in entries {
+                                ui.label(format!("  • {}: {}", entry.server_name, entry.split.name));
+                            }
+                        });
+                    ui.label("Keeping one copy deletes the other.");
+
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Decide Later").clicked() {
+                            should_close = true;
+                        }
+                        if ui.button("Keep Enabled Copies").clicked() {
+                            keep_enabled = Some(true);
+                        }
+                        if ui.button("Keep Disabled Copies").clicked() {
+                            keep_enabled = Some(false);
+                        }
+                    });
+                });
+
+            if should_close {
+                self.split_mods_prompt = None;
+                self.set_status("Mods in both folders are marked in the Mods tab".to_string(), false);
+            }
+            if let Some(keep_enabled) = keep_enabled {
+                if let Some(entries) = self.split_mods_prompt.take() {
+                    self.resolve_split_mods(entries, keep_enabled);
+                }
+            }
+        }
+
+        if let Some(prompt) = &self.unsaved_changes {
+            let mut should_close = false;
+            let mut choice = None;
+            let name = self.server_list.servers.get(prompt.server_index)
+                .map(|s| s.display_name().to_string())
+                .unwrap_or_default();
+
+            egui::Window::new("Unsaved Changes")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label(format!("{} has config changes that haven't been applied.", name));
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Save").clicked() {
+                            choice = Some(true);
+                        }
+                        if ui.button("Discard").clicked() {
+                            choice = Some(false);
+                        }
+                        if ui.button("Cancel").clicked() {
+                            should_close = true;
+                        }
+                    });
+                });
+
+            if should_close {
+                self.unsaved_changes = None;
+            }
+            if let Some(save) = choice {
+                if let Some(prompt) = self.unsaved_changes.take() {
+                    if save {
+                        // May open the port conflict prompt instead, which keeps the selection
+                        self.apply_config(prompt.server_index, true);
+                    } else if let Some(server) = self.server_list.servers.get_mut(prompt.server_index) {
+                        server.revert_config();
+                    }
+                    let resolved = self.server_list.servers.get(prompt.server_index).is_none_or(|s| !s.is_config_dirty());
+                    if resolved {
+                        match prompt.then {
+                            AfterUnsaved::SelectServer(idx) => self.select_server(idx),
+                            AfterUnsaved::CloseWindow => {
+                                self.close_confirmed = true;
+                                ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        if self.quit_prompt {
+            let mut should_close = false;
+            let mut stop_servers = None;
+            let names: Vec<String> = self.running.iter().map(|r| self.server_name(&r.server_id)).collect();
+
+            egui::Window::new("Servers Running")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    if names.len() == 1 {
+                        ui.label(format!("{} is still running.", names[0]));
+                    } else {
+                        ui.label(format!("{} servers are still running:", names.len()));
+                        for name in &names {
+                            ui.label(format!("• {}", name));
+                        }
+                    }
+                    ui.label(format!(
+                        "Stop and Quit waits for them to shut down with their console still visible. Quit Now gives them {} seconds, then kills them.",
+                        process::EXIT_STOP_TIMEOUT.as_secs()
+                    ));
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Stop and Quit").clicked() {
+                            stop_servers = Some(true);
+                        }
+                        if ui.button("Quit Now").clicked() {
+                            stop_servers = Some(false);
+                        }
+                        if ui.button("Cancel").clicked() {
+                            should_close = true;
+                        }
+                    });
+                });
+
+            if should_close || stop_servers.is_some() {
+                self.quit_prompt = false;
+            }
+            if should_close {
+                // Closing again later asks about everything again
+                self.close_confirmed = false;
+            }
+            match stop_servers {
+                Some(true) => self.stop_all_and_quit(),
+                Some(false) => {
+                    self.quit_confirmed = true;
+                    ctx.send_viewport_cmd(egui::ViewportCommand::Close);
+                }
+                None => {}
+            }
+            // The servers may have stopped on their own meanwhile
+            if self.running.is_empty() {
+                self.quit_prompt = false;
+            }
+        }
+
+        let share_running = self.share_view.as_ref().is_some_and(|view| self.server_state(&view.server_id) != ServerState::Stopped);
+        if let Some(view) = &mut self.share_view {
+            let mut open = true;
+            let config = self.server_list.servers.iter()
+                .find(|s| s.id == view.server_id)
+                .and_then(|s| s.loaded_config.as_ref());
+            let auth_error = self.consoles.get(&view.server_id).and_then(|console| listing::auth_error(&console.lines));
+
+            egui::Window::new("Share Server")
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.set_max_width(420.0);
+                    match config {
+                        Some(config) => ui::share::show(ui, view, config, &self.settings, share_running, auth_error),
+                        None => {
+                            ui.label("The server's config couldn't be read.");
+                        }
+                    }
+                });
+
+            if !open {
+                self.share_view = None;
+            }
+        }
+
+        if let Some(view) = &self.mod_trash_view {
+            let mut open = true;
+            let mut action = ui::mod_trash::TrashAction::None;
+            let name = self.server_name(&view.server_id);
+
+            egui::Window::new(format!("Mod Trash - {}", name))
+                .id(egui::Id::new("mod_trash"))
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.set_min_width(480.0);
+                    action = ui::mod_trash::show(ui, view);
+                });
+
+            self.handle_trash_action(action);
+            if !open {
+                self.mod_trash_view = None;
+            }
+        }
+
+        if let Some(wizard) = &mut self.setup_wizard {
+            let mut open = true;
+            let mut action = ui::setup_wizard::WizardAction::None;
+            egui::Window::new("Set Up a Server")
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.set_max_width(460.0);
+                    action = ui::setup_wizard::show(ui, wizard, &self.settings);
+                });
+
+            self.handle_setup_wizard_action(action);
+            if !open {
+                self.close_setup_wizard();
+            }
+        }
+
+        let update_attached = self.server_update.as_ref().is_some_and(|u| self.server_state(&u.server_id) == ServerState::Attached);
+        if let Some(update) = &mut self.server_update {
+            let mut open = true;
+            let mut action = ui::server_update::ServerUpdateAction::None;
+            let installed = self.server_list.servers.iter()
+                .find(|s| s.id == update.server_id)
+                .and_then(|s| s.server_version.as_deref());
+
+            egui::Window::new("Update BeamMP-Server")
+                .open(&mut open)
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    action = ui::server_update::show(ui, update, installed, update_attached);
+                });
+
+            match action {
+                ui::server_update::ServerUpdateAction::None => {}
+                ui::server_update::ServerUpdateAction::Start => {
+                    update.stage = updates::UpdateStage::Stopping;
+                    let server_id = update.server_id.clone();
+                    self.stop_server(&server_id);
                 }
             }
-        } else {
-            self.set_status("No server is running".to_string(), true);
+            if !open {
+                self.server_update = None;
+            }
         }
-    }
 
-    fn refresh_player_list(&mut self) {
-        self.player_list.clear();
-        self.send_server_command("list");
-        // Player list will be populated from terminal output parsing
-        // For now, just trigger the command
-    }
-}
-
-impl eframe::App for BeamMpManagerApp {
-    fn update(&mut self, ctx: &egui::Context, _frame: &mut eframe::Frame) {
-        // Update terminal output and check if there were changes
-        let terminal_changed = self.update_terminal();
-        
-        // Only request repaint if terminal actually changed (optimization)
-        if terminal_changed {
-            ctx.request_repaint();
-        }
-        // Handle delete confirmation modal
-        if let Some(confirmation) = &self.delete_confirmation {
+        // Handle port conflict modal
+        if let Some(prompt) = &self.port_conflict {
             let mut should_close = false;
-            let mut should_confirm = false;
+            let mut save_with_port = None;
 
-            egui::Window::new("Confirm Deletion")
+            egui::Window::new("Port Conflict")
                 .collapsible(false)
                 .resizable(false)
                 .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
                 .show(ctx, |ui| {
-                    match confirmation {
-                        DeleteConfirmation::Server(_) => {
-                            ui.label("Remove this server from the manager?");
-                            ui.label("This will NOT delete any files.");
-                        }
-                        DeleteConfirmation::Mod(idx) => {
-                            if let Some(cache) = &self.mods_cache {
-                                if let Some(mod_entry) = cache.mods.get(*idx) {
-                                    ui.label("Delete this mod from disk?");
-                                    ui.label(&mod_entry.relative_path);
-                                    ui.colored_label(
-                                        egui::Color32::RED,
-                                        "This cannot be undone!",
-                                    );
-                                }
-                            }
-                        }
-                    }
-                    
+                    ui.label(format!(
+                        "Port {} is already configured for: {}",
+                        prompt.port,
+                        prompt.used_by.join(", ")
+                    ));
+                    ui.label("Only one server can listen on a port at a time.");
+
                     ui.separator();
                     ui.horizontal(|ui| {
                         if ui.button("Cancel").clicked() {
                             should_close = true;
                         }
-                        if ui.button("Confirm").clicked() {
-                            should_confirm = true;
+                        if ui.button("Save Anyway").clicked() {
+                            save_with_port = Some(prompt.port);
+                        }
+                        if let Some(free_port) = prompt.free_port {
+                            if ui.button(format!("Use Port {} and Save", free_port)).clicked() {
+                                save_with_port = Some(free_port);
+                            }
                         }
                     });
                 });
 
             if should_close {
-                self.delete_confirmation = None;
+                self.port_conflict = None;
             }
-            if should_confirm {
-                match self.delete_confirmation.take() {
-                    Some(DeleteConfirmation::Server(idx)) => {
-                        self.selected_server_index = Some(idx);
-                        self.remove_selected_server();
+            if let Some(port) = save_with_port {
+                if let Some(prompt) = self.port_conflict.take() {
+                    if let Some(config) = self.server_list.servers.get_mut(prompt.server_index)
+                        .and_then(|s| s.edited_config.as_mut())
+                    {
+                        config.general.port = port;
                     }
-                    Some(DeleteConfirmation::Mod(idx)) => {
-                        if let Some(cache) = &self.mods_cache {
-                            let mod_type = cache.mod_type;
-                            if let Some(mod_entry) = cache.mods.get(idx) {
-                                if let Err(e) = mods::delete_mod(&mod_entry.full_path) {
-                                    self.set_status(format!("Failed to delete mod: {}", e), true);
-                                } else {
-                                    self.set_status("Mod deleted".to_string(), false);
-                                    self.reload_mods(mod_type);
-                                }
-                            }
-                        }
+                    self.apply_config(prompt.server_index, false);
+                }
+            }
+        }
+
+        if let Some(downloads) = &mut self.mod_downloads {
+            let mut action = ui::mod_downloads::DownloadsAction::None;
+            let search = &mut self.mod_repository_search;
+            let searchable = !self.settings.mod_search_url.trim().is_empty();
+            search.poll();
+            let server_name = self.server_list.servers.iter()
+                .find(|s| s.id == downloads.server_id)
+                .map(|s| s.display_name().to_string())
+                .unwrap_or_default();
+
+            egui::Window::new("Download Client Mods")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    action = ui::mod_downloads::show(ui, downloads, search, searchable, &server_name);
+                });
+
+            match action {
+                ui::mod_downloads::DownloadsAction::None => {}
+                ui::mod_downloads::DownloadsAction::Search => {
+                    let timeout = Duration::from_secs(self.settings.download_timeout_secs.max(1) as u64);
+                    self.mod_repository_search.start(self.settings.mod_search_url.trim(), timeout, ctx);
+                }
+                ui::mod_downloads::DownloadsAction::Download(urls) => self.start_mod_downloads(urls, ctx),
+                ui::mod_downloads::DownloadsAction::CancelBatch => {
+                    if let Some(batch) = &downloads.batch {
+                        batch.cancel();
                     }
-                    None => {}
                 }
+                ui::mod_downloads::DownloadsAction::Back => downloads.batch = None,
+                ui::mod_downloads::DownloadsAction::Close => self.mod_downloads = None,
             }
         }
 
         // Handle mod details modal
-        if let Some(details_view) = &self.mod_details_view {
+        if let Some(details_view) = &mut self.mod_details_view {
             let mut should_close = false;
 
             egui::Window::new("Mod Details")
@@ -385,7 +3634,7 @@ impl eframe::App for BeamMpManagerApp {
                         ui.separator();
                         
                         ui.label(format!("Total Files: {}", details.total_files));
-                        ui.label(format!("Total Size: {:.2} MB", details.total_size as f64 / 1_048_576.0));
+                        ui.label(format!("Total Size: {}", ui::format_bytes(details.total_size)));
                         
                         ui.separator();
                         
@@ -411,6 +3660,9 @@ impl eframe::App for BeamMpManagerApp {
                                     }
                                 });
                         }
+
+                        ui.separator();
+                        ui::zip_tree::show(ui, &mut details_view.contents);
                     } else {
                         ui.label("Loading details...");
                     }
@@ -426,6 +3678,136 @@ impl eframe::App for BeamMpManagerApp {
             }
         }
 
+        if let Some(rename) = &mut self.rename_server {
+            let mut should_close = false;
+            let mut should_save = false;
+
+            egui::Window::new("Rename Server")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label("Only changes the name shown in the panel, not ServerConfig.toml.");
+                    let response = ui.text_edit_singleline(&mut rename.name);
+                    if ui.memory(|m| m.focused().is_none()) {
+                        response.request_focus();
+                    }
+                    if response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter)) {
+                        should_save = true;
+                    }
+                    ui.label("Leave empty to use the config name again.");
+
+                    ui.separator();
+                    ui.horizontal(|ui| {
+                        if ui.button("Save").clicked() {
+                            should_save = true;
+                        }
+                        if ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+                            should_close = true;
+                        }
+                    });
+                });
+
+            if should_save {
+                if let Some(rename) = self.rename_server.take() {
+                    self.set_server_nickname(rename.server_index, Some(rename.name));
+                }
+            } else if should_close {
+                self.rename_server = None;
+            }
+        }
+
+        if let Some(editor) = &mut self.macro_editor {
+            let mut action = ui::control_tab::MacroEditorAction::None;
+            egui::Window::new("Edit Macros")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    action = ui::control_tab::show_macro_editor(ui, editor);
+                });
+
+            match action {
+                ui::control_tab::MacroEditorAction::Save => {
+                    if let Some(editor) = self.macro_editor.take() {
+                        let server_id = editor.server_id.clone();
+                        if let Some(server) = self.server_list.servers.iter_mut().find(|s| s.id == server_id) {
+                            server.macros = editor.finished();
+                        }
+                        if let Err(e) = self.server_list.save() {
+                            self.set_status(format!("Failed to save server list: {}", e), true);
+                        }
+                    }
+                }
+                ui::control_tab::MacroEditorAction::Cancel => self.macro_editor = None,
+                ui::control_tab::MacroEditorAction::None => {}
+            }
+        }
+
+        if let Some(view) = &self.config_backups_view {
+            let mut should_close = false;
+            let mut restore = None;
+
+            egui::Window::new("Config Backups")
+                .collapsible(false)
+                .resizable(false)
+                .anchor(egui::Align2::CENTER_CENTER, [0.0, 0.0])
+                .show(ctx, |ui| {
+                    ui.label("Restoring replaces ServerConfig.toml and discards unsaved edits.");
+                    ui.label("The current config is backed up first.");
+                    ui.separator();
+
+                    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                        for (i, backup) in view.backups.iter().enumerate() {
+                            ui.horizontal(|ui| {
+                                ui.label(ui::format_timestamp(backup.created));
+                                if ui.button("Restore").clicked() {
+                                    restore = Some(i);
+                                }
+                            });
+                        }
+                    });
+
+                    ui.separator();
+                    if ui.button("Close").clicked() {
+                        should_close = true;
+                    }
+                });
+
+            if let Some(i) = restore {
+                if let Some(view) = self.config_backups_view.take() {
+                    self.restore_config_backup(view.server_index, &view.backups[i]);
+                }
+            } else if should_close {
+                self.config_backups_view = None;
+            }
+        }
+
+        // Persistent warning: without it, failed saves only flash by in the status bar
+        if let Some(error) = self.config_dir_error.clone() {
+            let mut relocate = false;
+            egui::TopBottomPanel::top("config_dir_warning").show(ctx, |ui| {
+                ui.horizontal(|ui| {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        "⚠ The panel data folder is not writable, server list and settings changes will not be saved.",
+                    )
+                    .on_hover_text(&error);
+                    if ui.button("Use Another Folder...").clicked() {
+                        relocate = true;
+                    }
+                });
+            });
+            if relocate {
+                self.relocate_config_dir();
+            }
+        }
+
+        // Offered for a little while after each mod change; the Mods tab keeps an Undo button
+        let undo_offer = self.mod_history.last()
+            .filter(|undo| undo.at.elapsed() < UNDO_OFFER_DURATION)
+            .map(|undo| (undo.describe(), UNDO_OFFER_DURATION - undo.at.elapsed()));
+        let mut undo_clicked = false;
         egui::TopBottomPanel::bottom("status_bar").show(ctx, |ui| {
             ui.horizontal(|ui| {
                 if let Some(msg) = &self.status_message {
@@ -436,8 +3818,39 @@ impl eframe::App for BeamMpManagerApp {
                     };
                     ui.colored_label(color, &msg.text);
                 }
+                if let Some((description, remaining)) = &undo_offer {
+                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                        undo_clicked = ui.button("↶ Undo").on_hover_text(format!("Undo: {}", description)).clicked();
+                    });
+                    ctx.request_repaint_after(*remaining);
+                }
             });
         });
+        if undo_clicked {
+            self.undo_mod_change();
+        }
+
+        let server_states: Vec<ServerState> = self.server_list.servers.iter()
+            .map(|s| self.server_state(&s.id))
+            .collect();
+        // None until a running server has answered `list`
+        let player_counts: Vec<Option<usize>> = self.server_list.servers.iter()
+            .map(|s| self.running_process(&s.id).filter(|r| r.players_known).map(|r| r.player_list.len()))
+            .collect();
+        // Per server: the port it shares with others and who those others are
+        let mut shared_ports: Vec<Option<(u16, Vec<String>)>> = vec![None; self.server_list.servers.len()];
+        for (port, users) in self.server_list.port_conflicts() {
+            for &idx in &users {
+                let others = users.iter()
+                    .filter(|&&other| other != idx)
+                    .map(|&other| self.server_list.servers[other].display_name().to_string())
+                    .collect();
+                shared_ports[idx] = Some((port, others));
+            }
+        }
+        let updates_available: Vec<Option<String>> = self.server_list.servers.iter()
+            .map(|s| self.available_update(s).map(|release| release.tag_name.clone()))
+            .collect();
 
         egui::SidePanel::left("servers_panel")
             .min_width(250.0)
@@ -445,21 +3858,143 @@ impl eframe::App for BeamMpManagerApp {
                 ui.heading("Servers");
                 ui.separator();
 
+                let mut reset_name = None;
+                let mut locate_folder = None;
+                let mut move_request = None;
+                let mut select_request = None;
                 egui::ScrollArea::vertical().show(ui, |ui| {
                     for (idx, server) in self.server_list.servers.iter().enumerate() {
                         let is_selected = self.selected_server_index == Some(idx);
-                        let response = ui.selectable_label(is_selected, &server.name);
-                        
+                        let name = server.display_name();
+                        let badge = format!(
+                            "{}{}",
+                            if shared_ports[idx].is_some() || server.folder_missing { " ⚠" } else { "" },
+                            if updates_available[idx].is_some() { " ⬆" } else { "" },
+                        );
+                        let name_text = match server_states[idx] {
+                            ServerState::Stopped if server.folder_missing => {
+                                egui::RichText::new(format!("{}{}", name, badge)).color(egui::Color32::GRAY).italics()
+                            }
+                            ServerState::Stopped => egui::RichText::new(format!("{}{}", name, badge)),
+                            ServerState::Running => egui::RichText::new(format!("● {}{}", name, badge)).color(egui::Color32::GREEN),
+                            ServerState::Attached => egui::RichText::new(format!("● {}{}", name, badge)).color(egui::Color32::LIGHT_BLUE),
+                            ServerState::Starting | ServerState::Stopping { .. } => {
+                                egui::RichText::new(format!("● {}{}", name, badge)).color(egui::Color32::YELLOW)
+                            }
+                        };
+                        // Players / max players, grayed while the count isn't live
+                        let max_players = server.loaded_config.as_ref().map(|c| c.general.max_players);
+                        let players_text = max_players.map(|max| match player_counts[idx] {
+                            Some(count) => egui::RichText::new(format!("  ({}/{})", count, max)),
+                            None => egui::RichText::new(format!("  (-/{})", max)).weak(),
+                        });
+                        let mut label = egui::text::LayoutJob::default();
+                        for text in std::iter::once(name_text).chain(players_text) {
+                            text.append_to(&mut label, ui.style(), egui::FontSelection::Default, egui::Align::Center);
+                        }
+                        let row = ui.dnd_drag_source(
+                            egui::Id::new(("server_row", &server.id)),
+                            idx,
+                            |ui| ui.selectable_label(is_selected, label),
+                        );
+
+                        // Dropping on the upper half of a row puts the server above it, the lower half below
+                        let row_rect = row.response.rect;
+                        let drop_above = ui.ctx().pointer_interact_pos().is_some_and(|p| p.y < row_rect.center().y);
+                        if row.response.dnd_hover_payload::<usize>().is_some() {
+                            let y = if drop_above { row_rect.top() } else { row_rect.bottom() };
+                            let stroke = egui::Stroke::new(2.0, ui.visuals().selection.bg_fill);
+                            ui.painter().hline(row_rect.x_range(), y, stroke);
+                        }
+                        if let Some(from) = row.response.dnd_release_payload::<usize>() {
+                            move_request = Some((*from, if drop_above { idx } else { idx + 1 }));
+                        }
+
+                        let response = row.inner;
                         if response.clicked() {
-                            self.selected_server_index = Some(idx);
-                            self.mods_cache = None;
+                            select_request = Some(idx);
+                        }
+
+                        if response.double_clicked() {
+                            self.rename_server = Some(RenameServer { server_index: idx, name: name.to_string() });
                         }
+                        response.context_menu(|ui| {
+                            if ui.button("Rename...").clicked() {
+                                self.rename_server = Some(RenameServer { server_index: idx, name: name.to_string() });
+                                ui.close_menu();
+                            }
+                            if server.custom_name.is_some() && ui.button("Use Config Name").clicked() {
+                                reset_name = Some(idx);
+                                ui.close_menu();
+                            }
+                            if server.folder_missing {
+                                ui.separator();
+                                if ui.button("Locate Folder...").clicked() {
+                                    locate_folder = Some(idx);
+                                    ui.close_menu();
+                                }
+                                if ui.button("Remove from List").clicked() {
+                                    self.delete_confirmation = Some(DeleteConfirmation::Server(idx));
+                                    ui.close_menu();
+                                }
+                            }
+                        });
 
                         if response.hovered() {
-                            response.on_hover_text(&server.path.display().to_string());
+                            let current_run = self.running.iter()
+                                .find(|r| r.server_id == server.id)
+                                .map(|r| r.start_time.elapsed())
+                                .unwrap_or_default();
+                            let last_started = server.last_started
+                                .map(ui::format_timestamp)
+                                .unwrap_or_else(|| "Never".to_string());
+                            let total = Duration::from_secs(server.total_runtime_secs) + current_run;
+                            let mut hover = format!(
+                                "{}\nLast started: {}\nTotal runtime: {}",
+                                server.path.display(),
+                                last_started,
+                                ui::format_duration(total),
+                            );
+                            if let Some(version) = &server.server_version {
+                                hover.push_str(&format!("\nBeamMP-Server {}", version));
+                            }
+                            if let Some(DiskUsageState::Done(usage)) = self.disk_usage.get(&server.id) {
+                                hover.push_str(&format!(
+                                    "\nFolder size: {} (client mods {}, server mods {})",
+                                    ui::format_bytes(usage.total),
+                                    ui::format_bytes(usage.client_mods),
+                                    ui::format_bytes(usage.server_mods),
+                                ));
+                            }
+                            if server.folder_missing {
+                                hover.push_str("\n⚠ Folder not found. Right-click to locate it or remove the server.");
+                            }
+                            if let Some((port, others)) = &shared_ports[idx] {
+                                hover.push_str(&format!(
+                                    "\n⚠ Port {} is also used by {}; only one of them can run at a time",
+                                    port,
+                                    others.join(", ")
+                                ));
+                            }
+                            if let Some(tag) = &updates_available[idx] {
+                                hover.push_str(&format!("\n⬆ BeamMP-Server {} is available", tag));
+                            }
+                            response.on_hover_text(hover);
                         }
                     }
                 });
+                if let Some(idx) = reset_name {
+                    self.set_server_nickname(idx, None);
+                }
+                if let Some(idx) = locate_folder {
+                    self.locate_server_folder(idx);
+                }
+                if let Some((from, to)) = move_request {
+                    self.move_server(from, to);
+                }
+                if let Some(idx) = select_request {
+                    self.select_server(idx);
+                }
 
                 ui.separator();
                 ui.horizontal(|ui| {
@@ -473,15 +4008,36 @@ impl eframe::App for BeamMpManagerApp {
                         }
                     }
                 });
+
+                ui.horizontal(|ui| {
+                    if ui.button("Import List...").clicked() {
+                        self.import_server_list();
+                    }
+
+                    ui.toggle_value(&mut self.show_settings, "⚙ Settings");
+                });
             });
 
         egui::CentralPanel::default().show(ctx, |ui| {
-            if let Some(idx) = self.selected_server_index {
+            if self.show_settings {
+                match ui::settings_tab::show(ui, &mut self.settings, &mut self.status_message, &self.api) {
+                    ui::settings_tab::SettingsAction::ExportBundle { include_configs } => self.export_bundle(include_configs),
+                    ui::settings_tab::SettingsAction::ImportBundle => self.import_bundle(),
+                    ui::settings_tab::SettingsAction::CheckServerUpdates => self.check_server_updates(ctx),
+                    ui::settings_tab::SettingsAction::EmptyModTrash => self.empty_mod_trash(),
+                    ui::settings_tab::SettingsAction::RestartApi => self.api.restart(),
+                    ui::settings_tab::SettingsAction::OpenSetupWizard => {
+                        self.setup_wizard = Some(ui::setup_wizard::SetupWizard::new());
+                    }
+                    ui::settings_tab::SettingsAction::None => {}
+                }
+            } else if let Some(idx) = self.selected_server_index {
                 // Check if we need to reload mods before borrowing
                 let should_reload_mods = if self.current_tab == Tab::Mods {
                     if let Some(server) = self.server_list.servers.get(idx) {
                         self.mods_cache.is_none() || 
-                        self.mods_cache.as_ref().map(|c| &c.server_id) != Some(&server.id)
+                        self.mods_cache.as_ref().map(|c| &c.server_id) != Some(&server.id) ||
+                        self.mods_cache.as_ref().is_some_and(|c| c.nested != self.settings.nested_server_mods)
                     } else {
                         false
                     }
@@ -497,112 +4053,244 @@ impl eframe::App for BeamMpManagerApp {
                 // Get server info without holding mutable borrow
                 let server_info = self.server_list.servers.get(idx).map(|s| (s.id.clone(), s.path.clone()));
                 
-                if server_info.is_none() {
-                    self.selected_server_index = None;
-                } else {
-                    let (server_id, server_path) = server_info.unwrap();
-                    let is_running = self.running_process.as_ref()
-                        .map(|r| r.server_id == server_id)
-                        .unwrap_or(false);
+                if let Some((server_id, server_path)) = server_info {
+                    let state = self.server_state(&server_id);
+                    let is_running = state != ServerState::Stopped;
+                    let is_ready = state == ServerState::Running;
 
                     // Track actions to perform after UI
                     let mut should_start = false;
                     let mut should_stop = false;
-                    let mut should_clear_terminal = false;
+                    let mut should_connect = false;
+                    let mut should_attach = false;
+                    let mut should_detach = false;
+                    let mut should_share = false;
+                    let mut should_update = false;
+                    let mut restart_request = None;
+                    let restart_required = self.restart_required(&self.server_list.servers[idx]);
+                    let update_release = self.available_update(&self.server_list.servers[idx]).cloned();
+                    let mut console_action = ui::console::ConsoleAction::None;
                     let mut control_action = ui::control_tab::ControlAction::None;
                     let mut mods_action = ui::mods_tab::ModsAction::None;
+                    let mut config_action = ui::config_tab::ConfigAction::None;
+                    let mut schedule_action = ui::schedule_tab::ScheduleAction::None;
 
                     // Top section with tabs and server controls
                     ui.horizontal(|ui| {
                         ui.selectable_value(&mut self.current_tab, Tab::Config, "Config");
                         ui.selectable_value(&mut self.current_tab, Tab::Mods, "Mods");
                         ui.selectable_value(&mut self.current_tab, Tab::Control, "Control");
+                        ui.selectable_value(&mut self.current_tab, Tab::Schedule, "Schedule");
+                        ui.selectable_value(&mut self.current_tab, Tab::Events, "Events");
                         
                         ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
                             // Start/Stop buttons
-                            if is_running {
-                                ui.colored_label(egui::Color32::GREEN, "● Running");
-                                if ui.button("Stop Server").clicked() {
-                                    should_stop = true;
+                            match state {
+                                ServerState::Stopped => {
+                                    if ui.button("Start Server").clicked() {
+                                        should_start = true;
+                                    }
+                                    if ui.button("Attach")
+                                        .on_hover_text("Watch a BeamMP-Server that was started from this folder outside the panel")
+                                        .clicked()
+                                    {
+                                        should_attach = true;
+                                    }
                                 }
-                            } else {
-                                if ui.button("Start Server").clicked() {
-                                    should_start = true;
+                                ServerState::Attached => {
+                                    ui.colored_label(egui::Color32::LIGHT_BLUE, "● Attached (read-only)").on_hover_text(
+                                        "Started outside the panel. Its state and Server.log are shown, but it can't be stopped or sent commands from here.",
+                                    );
+                                    if ui.button("Detach").on_hover_text("Stop watching; the server keeps running").clicked() {
+                                        should_detach = true;
+                                    }
+                                    if ui.button("🎮 Connect with BeamNG").clicked() {
+                                        should_connect = true;
+                                    }
+                                }
+                                ServerState::Stopping { .. } => {
+                                    ui.colored_label(egui::Color32::YELLOW, "Stopping...");
+                                    ui.spinner();
+                                    // Disabled so repeated clicks can't queue more `exit`s
+                                    ui.add_enabled(false, egui::Button::new("Stop Server"));
+                                }
+                                ServerState::Starting | ServerState::Running => {
+                                    if is_ready {
+                                        ui.colored_label(egui::Color32::GREEN, "● Running");
+                                    } else {
+                                        ui.colored_label(egui::Color32::YELLOW, "Starting...");
+                                        ui.spinner();
+                                    }
+                                    // Stop stays available while starting
+                                    if ui.button("Stop Server").clicked() {
+                                        should_stop = true;
+                                    }
+                                    if ui.add_enabled(is_ready, egui::Button::new("🎮 Connect with BeamNG")).clicked() {
+                                        should_connect = true;
+                                    }
+                                }
+                            }
+                            if ui.button("📤 Share").on_hover_text("Address and QR code for players to join with").clicked() {
+                                should_share = true;
+                            }
+                            if let Some(release) = &update_release {
+                                let hover = format!(
+                                    "BeamMP-Server {} is available, this server has {}",
+                                    release.tag_name,
+                                    self.server_list.servers[idx].server_version.as_deref().unwrap_or("an older version"),
+                                );
+                                if ui.button("⬆ Update").on_hover_text(hover).clicked() {
+                                    should_update = true;
                                 }
                             }
                         });
                     });
                     ui.separator();
 
-                    // Main content area - split vertically if server is running
-                    if is_running {
+                    if restart_required {
+                        ui.horizontal(|ui| {
+                            ui.colored_label(egui::Color32::YELLOW, "⚠ Restart required to apply changes")
+                                .on_hover_text("The config was saved after the server started; it only reads it at startup");
+                            if ui.button("Restart Now").clicked() {
+                                restart_request = Some(false);
+                            }
+                            if ui.button("Restart with Warning").on_hover_text("Count down in chat first").clicked() {
+                                restart_request = Some(true);
+                            }
+                        });
+                        ui.separator();
+                    }
+
+                    // Main content area - split vertically if the server is running or its
+                    // last run left output (unless the console has been popped out into its own window)
+                    let lines = console_lines(self.shows_server_log(&server_id), &self.server_log_tail, &self.consoles, &server_id, self.settings.console_timestamps);
+                    if (is_running || lines.is_some()) && !self.console_detached {
+                        let lines = lines.unwrap_or_default();
+                        let default_rate = ui::console::OutputRate::default();
+                        let output_rate = self.consoles.get(&server_id).map_or(&default_rate, |c| &c.output_rate);
                         // Split view: tabs on top, terminal on bottom
                         egui::TopBottomPanel::bottom("server_terminal")
                             .resizable(true)
                             .min_height(150.0)
                             .default_height(250.0)
                             .show_inside(ui, |ui| {
-                                ui.horizontal(|ui| {
-                                    ui.heading("Server Console");
-                                    
-                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                        if ui.button("Clear").clicked() {
-                                            should_clear_terminal = true;
-                                        }
-                                        
-                                        ui.checkbox(&mut self.auto_scroll_terminal, "Auto-scroll");
-                                    });
-                                });
-                                
-                                ui.separator();
-                                
-                                let text_style = egui::TextStyle::Monospace;
-                                let row_height = ui.text_style_height(&text_style);
-                                
-                                egui::ScrollArea::vertical()
-                                    .auto_shrink([false, false])
-                                    .stick_to_bottom(self.auto_scroll_terminal)
-                                    .show_rows(
-                                        ui,
-                                        row_height,
-                                        self.terminal_output.len(),
-                                        |ui, row_range| {
-                                            for row in row_range {
-                                                if let Some(line) = self.terminal_output.get(row) {
-                                                    ui.label(egui::RichText::new(line).monospace());
-                                                }
-                                            }
-                                        },
-                                    );
+                                console_action = ui::console::show(
+                                    ui,
+                                    lines,
+                                    &mut self.console_view,
+                                    false,
+                                    output_rate,
+                                    &mut self.console_input,
+                                    &mut self.console_filter,
+                                );
                             });
                     }
 
+                    // Other servers sharing the edited port, shown next to the Port field
+                    let port_used_by = self.server_list.servers.get(idx)
+                        .and_then(|s| s.edited_config.as_ref())
+                        .map(|c| self.server_list.servers_using_port(c.general.port, idx))
+                        .unwrap_or_default();
+                    // Servers on a different BeamMP-Server version than this one
+                    let this_version = self.server_list.servers.get(idx).and_then(|s| s.server_version.clone());
+                    let other_versions: Vec<(String, String)> = self.server_list.servers.iter()
+                        .filter(|s| this_version.is_some() && s.server_version.is_some() && s.server_version != this_version)
+                        .map(|s| (s.display_name().to_string(), s.server_version.clone().unwrap_or_default()))
+                        .collect();
+
+                    if self.current_tab == Tab::Config {
+                        self.refresh_level_catalog(idx);
+                    }
+                    let levels = self.level_catalog.as_ref().map(|c| c.levels.as_slice()).unwrap_or_default();
+
                     // Tab content in remaining space
                     if let Some(server) = self.server_list.servers.get_mut(idx) {
                         egui::CentralPanel::default().show_inside(ui, |ui| {
                             match self.current_tab {
                                 Tab::Config => {
-                                    ui::config_tab::show(ui, server, &mut self.status_message);
+                                    let raw_editor = self.raw_config.as_mut().filter(|e| e.server_id == server.id);
+                                    if let Some(editor) = raw_editor {
+                                        config_action = ui::config_tab::show_raw(ui, editor, server.is_config_dirty());
+                                    } else {
+                                        let disk_usage = self.disk_usage.get(&server.id);
+                                        config_action = ui::config_tab::show(
+                                            ui,
+                                            server,
+                                            &ui::config_tab::OtherServers {
+                                                port_used_by: &port_used_by,
+                                                other_versions: &other_versions,
+                                            },
+                                            levels,
+                                            disk_usage.and_then(|state| match state {
+                                                DiskUsageState::Done(usage) => Some(usage),
+                                                DiskUsageState::Calculating(_) => None,
+                                            }),
+                                            matches!(disk_usage, Some(DiskUsageState::Calculating(_))),
+                                            &mut self.status_message,
+                                        );
+                                    }
                                 }
                                 Tab::Mods => {
                                     mods_action = ui::mods_tab::show(
                                         ui,
-                                        server,
                                         &mut self.mods_cache,
                                         self.current_mod_type,
                                         self.current_mod_filter,
-                                        &mut self.status_message,
+                                        &mut self.mod_search,
                                         &mut self.delete_confirmation,
+                                        self.mod_history.last().map(|undo| undo.describe()).as_deref(),
                                     );
                                 }
                                 Tab::Control => {
+                                    let running = self.running.iter_mut().find(|r| r.server_id == server.id);
+                                    let (process_info, player_list) = match running {
+                                        Some(r) => (
+                                            Some(ui::control_tab::ProcessInfo {
+                                                uptime: r.start_time.elapsed(),
+                                                pid: r.process.pid(),
+                                                usage_history: r.process.usage_history(),
+                                                restart_countdown: (r.manual_restart_at.is_some() || r.restart_warned.is_some())
+                                                    .then(|| r.next_restart(server, &self.settings.restart_countdown_secs))
+                                                    .flatten()
+                                                    .map(|(at, _)| (at - chrono::Local::now()).to_std().unwrap_or_default()),
+                                                in_lua_console: r.process.in_lua_console(),
+                                                player_history: &r.player_history,
+                                                chat: &r.chat,
+                                            }),
+                                            r.player_list.as_mut_slice(),
+                                        ),
+                                        None => (None, &mut [][..]),
+                                    };
                                     control_action = ui::control_tab::show(
                                         ui,
-                                        is_running,
-                                        &mut self.player_list,
-                                        &mut self.kick_player_name,
-                                        &mut self.kick_reason,
-                                        &mut self.broadcast_message,
+                                        process_info,
+                                        is_ready,
+                                        player_list,
+                                        &mut self.control_inputs,
+                                        &server.macros,
+                                        &mut server.broadcast_prefix,
+                                    );
+                                }
+                                Tab::Schedule => {
+                                    let running = self.running.iter().find(|r| r.server_id == server.id);
+                                    let next_restart = running.and_then(|r| server.restart.next_restart(r.restart_anchor));
+                                    schedule_action = ui::schedule_tab::show(
+                                        ui,
+                                        &mut server.restart,
+                                        running.is_some(),
+                                        next_restart,
+                                    );
+                                }
+                                Tab::Events => {
+                                    let current_run = self.running.iter()
+                                        .find(|r| r.server_id == server.id)
+                                        .map(|r| r.start_time.elapsed());
+                                    ui::events_tab::show(
+                                        ui,
+                                        server,
+                                        current_run,
+                                        self.event_log.events_for(&server.id),
+                                        &mut self.event_filter,
                                     );
                                 }
                             }
@@ -610,15 +4298,55 @@ impl eframe::App for BeamMpManagerApp {
                     }
 
                     // Execute deferred actions
+                    match config_action {
+                        ui::config_tab::ConfigAction::Apply => self.apply_config(idx, true),
+                        ui::config_tab::ConfigAction::PickFreePort => self.pick_free_port(idx),
+                        ui::config_tab::ConfigAction::ShowBackups => {
+                            let backups = self.server_list.servers[idx].config_backups();
+                            if backups.is_empty() {
+                                self.set_status("No config backups yet. One is made every time you apply changes.".to_string(), false);
+                            } else {
+                                self.config_backups_view = Some(ConfigBackupsView { server_index: idx, backups });
+                            }
+                        }
+                        ui::config_tab::ConfigAction::LocateFolder => self.locate_server_folder(idx),
+                        ui::config_tab::ConfigAction::CalculateSize => self.calculate_disk_usage(idx, ctx),
+                        ui::config_tab::ConfigAction::EditRaw | ui::config_tab::ConfigAction::ReloadRaw => self.open_raw_config(idx),
+                        ui::config_tab::ConfigAction::CloseRaw => self.raw_config = None,
+                        ui::config_tab::ConfigAction::SaveRaw => self.save_raw_config(idx),
+                        ui::config_tab::ConfigAction::RemoveServer => {
+                            self.delete_confirmation = Some(DeleteConfirmation::Server(idx));
+                        }
+                        ui::config_tab::ConfigAction::None => {}
+                    }
+
                     if should_start {
-                        self.start_server(server_id, server_path);
+                        self.start_server(server_id.clone(), server_path.clone());
                     }
                     if should_stop {
-                        self.stop_server();
+                        self.stop_server(&server_id);
                     }
-                    if should_clear_terminal {
-                        self.terminal_output.clear();
+                    if should_attach {
+                        self.attach_server(&server_id, &server_path);
                     }
+                    if should_detach {
+                        self.detach_server(&server_id);
+                    }
+                    if should_connect {
+                        self.connect_with_client(idx);
+                    }
+                    match restart_request {
+                        Some(true) => self.restart_with_warning(&server_id),
+                        Some(false) => self.restart_now(&server_id),
+                        None => {}
+                    }
+                    if should_share {
+                        self.share_view = Some(ui::share::ShareView::new(server_id.clone()));
+                    }
+                    if let Some(release) = update_release.filter(|_| should_update) {
+                        self.server_update = Some(updates::ServerUpdate::new(server_id.clone(), release));
+                    }
+                    self.handle_console_action(console_action);
                     
                     // Handle mods tab actions
                     match mods_action {
@@ -633,12 +4361,13 @@ impl eframe::App for BeamMpManagerApp {
                         ui::mods_tab::ModsAction::ViewDetails(mod_idx) => {
                             if let Some(cache) = &self.mods_cache {
                                 if let Some(mod_entry) = cache.mods.get(mod_idx) {
-                                    // Get mod details
-                                    match mods::get_mod_details(&mod_entry.full_path) {
+                                    // Get mod details (cached from the last scan unless the ZIP changed)
+                                    match self.mod_file_cache.details(&mod_entry.full_path) {
                                         Ok(details) => {
                                             self.mod_details_view = Some(ModDetailsView {
                                                 mod_name: mod_entry.relative_path.clone(),
                                                 details: Some(details),
+                                                contents: ui::zip_tree::ZipTreeView::new(mod_entry.full_path.clone()),
                                             });
                                         }
                                         Err(e) => {
@@ -651,19 +4380,113 @@ impl eframe::App for BeamMpManagerApp {
                         ui::mods_tab::ModsAction::ChangeFilter(new_filter) => {
                             self.current_mod_filter = new_filter;
                         }
+                        ui::mods_tab::ModsAction::SetEnabled(mod_idx, enable) => {
+                            self.set_mod_enabled(mod_idx, enable);
+                        }
+                        ui::mods_tab::ModsAction::AddClientMods(files) => {
+                            self.add_client_mods(files);
+                        }
+                        ui::mods_tab::ModsAction::PackageFolder(folder) => {
+                            self.package_client_mod(folder);
+                        }
+                        ui::mods_tab::ModsAction::DownloadMods => {
+                            self.mod_downloads = Some(ui::mod_downloads::ModDownloads {
+                                server_id: server_id.clone(),
+                                urls: String::new(),
+                                batch: None,
+                            });
+                        }
+                        ui::mods_tab::ModsAction::SetEnabledMany(indices, enable) => {
+                            self.set_mods_enabled(&indices, enable);
+                        }
+                        ui::mods_tab::ModsAction::ResolveSplit(mod_idx, keep_enabled) => {
+                            self.resolve_split_mod(mod_idx, keep_enabled);
+                        }
+                        ui::mods_tab::ModsAction::Undo => {
+                            self.undo_mod_change();
+                        }
+                        ui::mods_tab::ModsAction::CreateResourceFolder => {
+                            self.create_resource_folder();
+                        }
+                        ui::mods_tab::ModsAction::UseDefaultResourceFolder(save) => {
+                            self.use_default_resource_folder(save);
+                        }
+                        ui::mods_tab::ModsAction::ShowTrash => {
+                            self.mod_trash_view = Some(ui::mod_trash::TrashView::new(server_id.clone(), &server_path));
+                        }
+                        ui::mods_tab::ModsAction::CopyToServer(indices) => {
+                            if let Some(cache) = &self.mods_cache {
+                                self.mod_copy = Some(ModCopy {
+                                    source_server_id: cache.server_id.clone(),
+                                    mod_type: cache.mod_type,
+                                    mods: indices.iter()
+                                        .filter_map(|idx| cache.mods.get(*idx))
+                                        .map(|m| (m.relative_path.clone(), m.full_path.clone()))
+                                        .collect(),
+                                    target_server_id: None,
+                                    existing: None,
+                                });
+                            }
+                        }
                         ui::mods_tab::ModsAction::None => {}
                     }
                     
                     // Handle control tab actions
                     match control_action {
                         ui::control_tab::ControlAction::SendCommand(cmd) => {
-                            self.send_server_command(&cmd);
+                            let _ = self.send_server_command(&server_id, &cmd);
+                        }
+                        ui::control_tab::ControlAction::RunLua { state, code } => {
+                            self.run_lua(&server_id, &state, &code);
+                        }
+                        ui::control_tab::ControlAction::ExitLua => {
+                            self.send_console_input(&server_id, "exit()");
+                        }
+                        ui::control_tab::ControlAction::EditMacros => {
+                            let macros = self.server_list.servers.iter()
+                                .find(|s| s.id == server_id)
+                                .map(|s| s.macros.as_slice())
+                                .unwrap_or_default();
+                            self.macro_editor = Some(ui::control_tab::MacroEditor::new(server_id.clone(), macros));
                         }
                         ui::control_tab::ControlAction::RefreshPlayers => {
-                            self.refresh_player_list();
+                            self.refresh_player_list(&server_id);
+                        }
+                        ui::control_tab::ControlAction::KickPlayer(name) => {
+                            // Only a kick that reached the server takes the player off the list
+                            let kicked = self.send_server_command(&server_id, &format!("kick {}", quote_player_name(&name)));
+                            if kicked.is_ok() {
+                                if let Some(running) = self.running.iter_mut().find(|r| r.server_id == server_id) {
+                                    running.player_list.retain(|p| p != &name);
+                                }
+                            }
+                        }
+                        ui::control_tab::ControlAction::BanPlayer(name) => {
+                            let _ = self.send_server_command(&server_id, &format!("ban {}", quote_player_name(&name)));
+                        }
+                        ui::control_tab::ControlAction::WhisperPlayer { name, message } => {
+                            self.whisper(&server_id, &name, &message);
                         }
+                        ui::control_tab::ControlAction::ExportPlayerHistory => {
+                            self.export_player_history(&server_id);
+                        }
+                        ui::control_tab::ControlAction::BroadcastPrefixChanged => {
+                            if let Err(e) = self.server_list.save() {
+                                self.set_status(format!("Failed to save server list: {}", e), true);
+                            }
+                        }
+                        ui::control_tab::ControlAction::RestartWithWarning => self.restart_with_warning(&server_id),
+                        ui::control_tab::ControlAction::CancelRestart => self.cancel_pending_restart(&server_id),
                         ui::control_tab::ControlAction::None => {}
                     }
+
+                    match schedule_action {
+                        ui::schedule_tab::ScheduleAction::Changed => self.restart_schedule_changed(&server_id),
+                        ui::schedule_tab::ScheduleAction::SkipNext => self.skip_scheduled_restart(&server_id),
+                        ui::schedule_tab::ScheduleAction::None => {}
+                    }
+                } else {
+                    self.selected_server_index = None;
                 }
             } else {
                 ui.vertical_centered(|ui| {
@@ -673,6 +4496,17 @@ impl eframe::App for BeamMpManagerApp {
                 });
             }
         });
+
+        let has_console = self.selected_server_id()
+            .is_some_and(|id| console_lines(self.shows_server_log(&id), &self.server_log_tail, &self.consoles, &id, self.settings.console_timestamps).is_some());
+        if self.console_detached && has_console {
+            self.show_detached_console(ctx);
+        }
+        self.show_drop_overlay(ctx);
     }
-}
 
+    fn on_exit(&mut self, _gl: Option<&eframe::glow::Context>) {
+        self.stop_servers_on_exit();
+        let _ = self.event_log.flush();
+    }
+}
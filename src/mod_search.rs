@@ -0,0 +1,137 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+use std::time::{Duration, Instant};
+
+// Repeating a search within this time shows the earlier results instead of asking again
+const CACHE_DURATION: Duration = Duration::from_secs(5 * 60);
+
+/// One mod offered by the search service
+#[derive(Debug, Clone, Deserialize)]
+pub struct SearchResult {
+    #[serde(alias = "title")]
+    pub name: String,
+    /// Direct link to the ZIP
+    #[serde(alias = "url", alias = "download")]
+    pub download_url: String,
+    #[serde(default)]
+    pub author: Option<String>,
+    #[serde(default, alias = "tag_line", alias = "summary")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub version: Option<String>,
+    #[serde(default, alias = "file_size")]
+    pub size: Option<u64>,
+}
+
+// The service may answer with a bare list or wrap it in an object
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum SearchResponse {
+    List(Vec<SearchResult>),
+    Wrapped {
+        #[serde(alias = "mods", alias = "resources", alias = "data")]
+        results: Vec<SearchResult>,
+    },
+}
+
+pub enum SearchState {
+    Idle,
+    Searching(Receiver<Result<Vec<SearchResult>, String>>),
+    Done(Vec<SearchResult>),
+    Failed(String),
+}
+
+/// Searches a mod repository configured in Settings on a background thread, keeping
+/// recent results so paging back and forth doesn't hit the service every time
+pub struct ModSearch {
+    pub query: String,
+    pub state: SearchState,
+    /// Query the current state belongs to
+    searched: String,
+    cache: HashMap<String, (Instant, Vec<SearchResult>)>,
+}
+
+impl Default for ModSearch {
+    fn default() -> Self {
+        Self {
+            query: String::new(),
+            state: SearchState::Idle,
+            searched: String::new(),
+            cache: HashMap::new(),
+        }
+    }
+}
+
+impl ModSearch {
+    /// Search for the current query. `url_template` has `{query}` replaced with it.
+    pub fn start(&mut self, url_template: &str, timeout: Duration, ctx: &egui::Context) {
+        let query = self.query.trim().to_string();
+        if query.is_empty() {
+            return;
+        }
+        self.cache.retain(|_, (at, _)| at.elapsed() < CACHE_DURATION);
+        self.searched = query.clone();
+        if let Some((_, results)) = self.cache.get(&query.to_lowercase()) {
+            self.state = SearchState::Done(results.clone());
+            return;
+        }
+
+        let url = url_template.replace("{query}", &encode_query(&query));
+        let (tx, rx) = channel();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = tx.send(search(&url, timeout).map_err(|e| e.to_string()));
+            ctx.request_repaint();
+        });
+        self.state = SearchState::Searching(rx);
+    }
+
+    pub fn poll(&mut self) {
+        let SearchState::Searching(rx) = &self.state else {
+            return;
+        };
+        match rx.try_recv() {
+            Ok(Ok(results)) => {
+                self.cache.insert(self.searched.to_lowercase(), (Instant::now(), results.clone()));
+                self.state = SearchState::Done(results);
+            }
+            Ok(Err(e)) => self.state = SearchState::Failed(e),
+            Err(std::sync::mpsc::TryRecvError::Empty) => {}
+            Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                self.state = SearchState::Failed("The search stopped unexpectedly".to_string());
+            }
+        }
+    }
+}
+
+fn search(url: &str, timeout: Duration) -> Result<Vec<SearchResult>> {
+    let response = crate::download::agent(timeout).get(url).call().map_err(|e| match e {
+        ureq::Error::Status(code, response) => anyhow!("The search service answered HTTP {} {}", code, response.status_text()),
+        ureq::Error::Transport(e) => anyhow!("Could not reach the search service ({}). Check your internet connection.", e),
+    })?;
+    let body = response.into_string()?;
+    let results = match serde_json::from_str(&body) {
+        Ok(SearchResponse::List(results)) | Ok(SearchResponse::Wrapped { results }) => results,
+        Err(e) => return Err(anyhow!("The search service sent an unexpected answer: {}", e)),
+    };
+    // Only links the downloader can fetch
+    Ok(results
+        .into_iter()
+        .filter(|r| r.download_url.starts_with("https://") || r.download_url.starts_with("http://"))
+        .collect())
+}
+
+/// Percent-encode a search term for use in a query string
+fn encode_query(query: &str) -> String {
+    query
+        .bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+            b' ' => "+".to_string(),
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
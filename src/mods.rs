@@ -1,6 +1,10 @@
 use anyhow::Result;
+use std::collections::{BTreeMap, HashMap};
 use std::fs;
+use std::hash::Hasher;
+use std::io::Read;
 use std::path::{Path, PathBuf};
+use std::time::SystemTime;
 
 #[derive(Debug, Clone)]
 pub struct ModEntry {
@@ -9,6 +13,11 @@ pub struct ModEntry {
     pub enabled: bool,
     pub is_level: bool,
     pub is_vehicle: bool,
+    /// Size on disk in bytes (the ZIP for client mods, the whole folder for server mods)
+    pub size: u64,
+    /// Set when the mod also exists in the `_disabled` tree, usually left behind by an
+    /// interrupted move. `full_path` is then the enabled copy.
+    pub disabled_copy: Option<PathBuf>,
 }
 
 #[derive(Debug, Clone)]
@@ -21,7 +30,223 @@ pub struct ModDetailInfo {
     pub total_size: u64,
 }
 
-pub fn scan_server_mods(server_path: &Path, resource_folder: &str) -> Result<Vec<ModEntry>> {
+// Size and mtime of a file when its cached data was computed
+type FileStamp = (SystemTime, u64);
+
+fn file_stamp(path: &Path) -> Result<FileStamp> {
+    let metadata = fs::metadata(path)?;
+    Ok((metadata.modified()?, metadata.len()))
+}
+
+/// Per-file data that is expensive to compute (content hashes, archive listings),
+/// reused while a file's size and mtime are unchanged
+#[derive(Default)]
+pub struct ModFileCache {
+    hashes: HashMap<PathBuf, (FileStamp, u64)>,
+    details: HashMap<PathBuf, (FileStamp, ModDetailInfo)>,
+}
+
+impl ModFileCache {
+    pub fn hash(&mut self, path: &Path) -> Result<u64> {
+        let stamp = file_stamp(path)?;
+        if let Some((cached_stamp, hash)) = self.hashes.get(path) {
+            if *cached_stamp == stamp {
+                return Ok(*hash);
+            }
+        }
+
+        let mut file = fs::File::open(path)?;
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        let mut buffer = vec![0u8; 64 * 1024];
+        loop {
+            let read = file.read(&mut buffer)?;
+            if read == 0 {
+                break;
+            }
+            hasher.write(&buffer[..read]);
+        }
+        hasher.write_u64(stamp.1);
+        let hash = hasher.finish();
+
+        self.hashes.insert(path.to_path_buf(), (stamp, hash));
+        Ok(hash)
+    }
+
+    /// Contents of a client mod ZIP, see `get_mod_details`
+    pub fn details(&mut self, path: &Path) -> Result<ModDetailInfo> {
+        let stamp = file_stamp(path)?;
+        if let Some((cached_stamp, details)) = self.details.get(path) {
+            if *cached_stamp == stamp {
+                return Ok(details.clone());
+            }
+        }
+
+        let details = get_mod_details(path)?;
+        self.details.insert(path.to_path_buf(), (stamp, details.clone()));
+        Ok(details)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ConflictKind {
+    Level,
+    Vehicle,
+}
+
+impl ConflictKind {
+    pub fn label(&self) -> &'static str {
+        match self {
+            ConflictKind::Level => "Level",
+            ConflictKind::Vehicle => "Vehicle",
+        }
+    }
+}
+
+/// A level or vehicle shipped by more than one enabled client mod
+#[derive(Debug, Clone)]
+pub struct Conflict {
+    pub kind: ConflictKind,
+    pub name: String,
+    /// Indices into the scanned mod list
+    pub mods: Vec<usize>,
+}
+
+/// Find levels and vehicles provided by two or more enabled client ZIPs. The game
+/// only loads one of them, so which one wins depends on load order.
+pub fn find_mod_conflicts(mods: &[ModEntry], cache: &mut ModFileCache) -> Vec<Conflict> {
+    // Keyed case-insensitively, the game's virtual filesystem doesn't care about case
+    let mut providers: HashMap<(ConflictKind, String), (String, Vec<usize>)> = HashMap::new();
+
+    for (idx, mod_entry) in mods.iter().enumerate() {
+        if !mod_entry.enabled || !(mod_entry.is_level || mod_entry.is_vehicle) {
+            continue;
+        }
+        let Ok(details) = cache.details(&mod_entry.full_path) else {
+            continue;
+        };
+
+        let names = details.level_names.iter().map(|n| (ConflictKind::Level, n))
+            .chain(details.vehicle_names.iter().map(|n| (ConflictKind::Vehicle, n)));
+        for (kind, name) in names {
+            providers
+                .entry((kind, name.to_lowercase()))
+                .or_insert_with(|| (name.clone(), Vec::new()))
+                .1
+                .push(idx);
+        }
+    }
+
+    let mut conflicts: Vec<Conflict> = providers
+        .into_iter()
+        .filter(|(_, (_, indices))| indices.len() > 1)
+        .map(|((kind, _), (name, mods))| Conflict { kind, name, mods })
+        .collect();
+    conflicts.sort_by(|a, b| (a.kind.label(), &a.name).cmp(&(b.kind.label(), &b.name)));
+    conflicts
+}
+
+/// Group mods whose files have identical content. Each group holds indices into
+/// `mods`, with the copy worth keeping first (enabled before disabled, then by name).
+pub fn find_duplicate_mods(mods: &[ModEntry], cache: &mut ModFileCache) -> Vec<Vec<usize>> {
+    let mut by_hash: HashMap<u64, Vec<usize>> = HashMap::new();
+    for (idx, mod_entry) in mods.iter().enumerate() {
+        if let Ok(hash) = cache.hash(&mod_entry.full_path) {
+            by_hash.entry(hash).or_default().push(idx);
+        }
+    }
+
+    let mut groups: Vec<Vec<usize>> = by_hash
+        .into_values()
+        .filter(|group| group.len() > 1)
+        .map(|mut group| {
+            group.sort_by(|a, b| {
+                mods[*b].enabled
+                    .cmp(&mods[*a].enabled)
+                    .then_with(|| mods[*a].relative_path.cmp(&mods[*b].relative_path))
+            });
+            group
+        })
+        .collect();
+    groups.sort();
+    groups
+}
+
+/// Fold mods found in both the enabled and the `_disabled` tree into a single
+/// enabled entry (the server loads that copy) that remembers the stray disabled copy
+fn merge_split_mods(mods: &mut Vec<ModEntry>) {
+    let enabled: HashMap<String, usize> = mods
+        .iter()
+        .enumerate()
+        .filter(|(_, m)| m.enabled)
+        .map(|(idx, m)| (m.relative_path.clone(), idx))
+        .collect();
+
+    let mut split = Vec::new();
+    for (idx, mod_entry) in mods.iter().enumerate() {
+        if !mod_entry.enabled {
+            if let Some(enabled_idx) = enabled.get(&mod_entry.relative_path) {
+                split.push((*enabled_idx, idx));
+            }
+        }
+    }
+
+    for (enabled_idx, disabled_idx) in &split {
+        mods[*enabled_idx].disabled_copy = Some(mods[*disabled_idx].full_path.clone());
+    }
+
+    // Remove the disabled duplicates back to front so indices stay valid
+    let mut disabled: Vec<usize> = split.into_iter().map(|(_, idx)| idx).collect();
+    disabled.sort_unstable();
+    for idx in disabled.into_iter().rev() {
+        mods.remove(idx);
+    }
+}
+
+/// The resource folder BeamMP uses when the config doesn't set one
+pub const DEFAULT_RESOURCE_FOLDER: &str = "Resources";
+
+/// Something off with a server's configured resource folder
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceFolderIssue {
+    /// The folder doesn't exist, so there are no mods to find
+    Missing,
+    /// A custom folder is configured but a `Resources` folder exists as well; the server
+    /// only loads the configured one
+    AlsoDefault,
+}
+
+pub fn check_resource_folder(server_path: &Path, resource_folder: &str) -> Option<ResourceFolderIssue> {
+    let configured = server_path.join(resource_folder);
+    if !configured.is_dir() {
+        return Some(ResourceFolderIssue::Missing);
+    }
+
+    let default = server_path.join(DEFAULT_RESOURCE_FOLDER);
+    if !default.is_dir() {
+        return None;
+    }
+    // Canonical paths, so "./Resources" or a differently cased name on Windows isn't reported
+    match (fs::canonicalize(&configured), fs::canonicalize(&default)) {
+        (Ok(configured), Ok(default)) if configured != default => Some(ResourceFolderIssue::AlsoDefault),
+        _ => None,
+    }
+}
+
+/// Create a missing resource folder with its Client and Server folders
+pub fn create_resource_folder(server_path: &Path, resource_folder: &str) -> Result<()> {
+    let root = server_path.join(resource_folder);
+    fs::create_dir_all(root.join("Client"))?;
+    fs::create_dir_all(root.join("Server"))?;
+    Ok(())
+}
+
+/// How many folders deep server mods are searched with `nested`
+pub const SERVER_MOD_MAX_DEPTH: usize = 4;
+
+/// Server mods are the folders in `Resources/Server`. With `nested`, folders without Lua
+/// scripts of their own are groups and the mods are looked for inside them; `relative_path`
+/// is then the full path below `Server`, like `group/mod`.
+pub fn scan_server_mods(server_path: &Path, resource_folder: &str, nested: bool) -> Result<Vec<ModEntry>> {
     // Preallocate capacity for better performance
     let mut mods = Vec::with_capacity(128);
 
@@ -30,14 +255,16 @@ pub fn scan_server_mods(server_path: &Path, resource_folder: &str) -> Result<Vec
 
     // Scan enabled server mods (folders only)
     if enabled_root.exists() {
-        scan_server_folders(&enabled_root, true, &mut mods)?;
+        scan_server_folders(&enabled_root, true, nested, &mut mods)?;
     }
 
     // Scan disabled server mods (folders only)
     if disabled_root.exists() {
-        scan_server_folders(&disabled_root, false, &mut mods)?;
+        scan_server_folders(&disabled_root, false, nested, &mut mods)?;
     }
 
+    merge_split_mods(&mut mods);
+
     // Sort by path for consistent display
     mods.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
 
@@ -66,6 +293,8 @@ pub fn scan_client_mods(server_path: &Path, resource_folder: &str) -> Result<Vec
         !mod_entry.relative_path.eq_ignore_ascii_case("mods.json")
     });
 
+    merge_split_mods(&mut mods);
+
     // Sort by path for consistent display
     mods.sort_by(|a, b| a.relative_path.cmp(&b.relative_path));
 
@@ -75,37 +304,88 @@ pub fn scan_client_mods(server_path: &Path, resource_folder: &str) -> Result<Vec
 fn scan_server_folders(
     root: &Path,
     enabled: bool,
+    nested: bool,
     mods: &mut Vec<ModEntry>,
 ) -> Result<()> {
     if !root.is_dir() {
         return Ok(());
     }
 
+    let max_depth = if nested { SERVER_MOD_MAX_DEPTH } else { 1 };
+    scan_server_dir(root, root, 1, max_depth, enabled, mods)
+}
+
+fn scan_server_dir(
+    root: &Path,
+    dir: &Path,
+    depth: usize,
+    max_depth: usize,
+    enabled: bool,
+    mods: &mut Vec<ModEntry>,
+) -> Result<()> {
     // Server mods are folders in the Server directory
-    for entry in fs::read_dir(root)? {
+    for entry in fs::read_dir(dir)? {
         let entry = entry?;
         let path = entry.path();
+        if !path.is_dir() {
+            continue;
+        }
 
-        if path.is_dir() {
-            let folder_name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("")
-                .to_string();
-
-            mods.push(ModEntry {
-                relative_path: folder_name,
-                full_path: path,
-                enabled,
-                is_level: false, // Server mods are folders, not levels
-                is_vehicle: false,
-            });
+        if depth < max_depth {
+            let (has_scripts, has_folders, is_empty) = folder_contents(&path);
+            if !has_scripts && has_folders {
+                // A group of mods
+                scan_server_dir(root, &path, depth + 1, max_depth, enabled, mods)?;
+                continue;
+            }
+            if depth > 1 && is_empty {
+                // Left behind in a group after its mods were moved out
+                continue;
+            }
         }
+
+        let relative_path = path
+            .strip_prefix(root)
+            .unwrap_or(&path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+        let size = tree_size(&path).map(|(_, bytes)| bytes).unwrap_or(0);
+
+        mods.push(ModEntry {
+            relative_path,
+            full_path: path,
+            enabled,
+            is_level: false, // Server mods are folders, not levels
+            is_vehicle: false,
+            size,
+            disabled_copy: None,
+        });
     }
 
     Ok(())
 }
 
+/// Whether a folder directly holds Lua scripts, holds folders, and is empty
+fn folder_contents(path: &Path) -> (bool, bool, bool) {
+    let mut has_scripts = false;
+    let mut has_folders = false;
+    let mut is_empty = true;
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.map_while(Result::ok) {
+            is_empty = false;
+            let entry_path = entry.path();
+            if entry_path.is_dir() {
+                has_folders = true;
+            } else if entry_path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("lua")) {
+                has_scripts = true;
+            }
+        }
+    }
+    (has_scripts, has_folders, is_empty)
+}
+
 fn scan_client_files(
     root: &Path,
     enabled: bool,
@@ -132,6 +412,7 @@ fn scan_client_files(
 
                     // Check if this ZIP contains a "level" or "vehicles" folder
                     let (is_level, is_vehicle) = check_zip_content_type(&path);
+                    let size = entry.metadata().map(|m| m.len()).unwrap_or(0);
 
                     mods.push(ModEntry {
                         relative_path: file_name,
@@ -139,6 +420,8 @@ fn scan_client_files(
                         enabled,
                         is_level,
                         is_vehicle,
+                        size,
+                        disabled_copy: None,
                     });
                 }
             }
@@ -166,20 +449,10 @@ fn check_zip_content_type(zip_path: &Path) -> (bool, bool) {
     // Check if any file in the ZIP is inside a "level/levels" or "vehicles/vehicle" folder
     for i in 0..archive.len() {
         if let Ok(file) = archive.by_index(i) {
-            let name = file.name().to_lowercase();
-            
-            // Check if the path contains "level/" or "levels/" folder
-            if name.starts_with("level/") || name.starts_with("levels/") 
-                || name.contains("/level/") || name.contains("/levels/") {
-                is_level = true;
-            }
-            
-            // Check if the path contains "vehicle/" or "vehicles/" folder
-            if name.starts_with("vehicle/") || name.starts_with("vehicles/") 
-                || name.contains("/vehicle/") || name.contains("/vehicles/") {
-                is_vehicle = true;
-            }
-            
+            let (level, vehicle) = classify_zip_entry(file.name());
+            is_level |= level;
+            is_vehicle |= vehicle;
+
             // Early exit if both found
             if is_level && is_vehicle {
                 break;
@@ -190,6 +463,112 @@ fn check_zip_content_type(zip_path: &Path) -> (bool, bool) {
     (is_level, is_vehicle)
 }
 
+/// Whether a path inside a mod ZIP lies in a level and/or vehicle folder
+fn classify_zip_entry(name: &str) -> (bool, bool) {
+    let name = name.to_lowercase();
+    let in_folder = |folder: &str| name.starts_with(&format!("{}/", folder)) || name.contains(&format!("/{}/", folder));
+    (
+        in_folder("level") || in_folder("levels"),
+        in_folder("vehicle") || in_folder("vehicles"),
+    )
+}
+
+/// Check that a client mod ZIP can be read in full and looks like a BeamNG mod, with a
+/// levels/ or vehicles/ folder. `allow_unknown_structure` skips the second check.
+pub fn validate_client_mod(path: &Path, allow_unknown_structure: bool) -> Result<()> {
+    let file = fs::File::open(path)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| anyhow::anyhow!("Not a valid ZIP archive ({})", e))?;
+    if archive.is_empty() {
+        return Err(anyhow::anyhow!("The ZIP archive is empty"));
+    }
+
+    let mut recognized = false;
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| anyhow::anyhow!("The ZIP archive is corrupt ({})", e))?;
+        let name = entry.name().to_string();
+        // Reading an entry to the end verifies its checksum
+        std::io::copy(&mut entry, &mut std::io::sink())
+            .map_err(|e| anyhow::anyhow!("The ZIP archive is corrupt: {} can't be read ({})", name, e))?;
+        let (level, vehicle) = classify_zip_entry(&name);
+        recognized |= level || vehicle;
+    }
+
+    if !recognized && !allow_unknown_structure {
+        return Err(anyhow::anyhow!(
+            "No levels/ or vehicles/ folder found, so this doesn't look like a BeamNG mod. \
+             Allow mods with an unknown structure in Settings to install it anyway"
+        ));
+    }
+    Ok(())
+}
+
+/// Level folders that ship with BeamNG.drive, so they never need a mod
+pub const STOCK_LEVELS: [&str; 15] = [
+    "automation_test_track",
+    "cliff",
+    "derby",
+    "driver_training",
+    "east_coast_usa",
+    "gridmap_v2",
+    "hirochi_raceway",
+    "industrial",
+    "italy",
+    "johnson_valley",
+    "jungle_rock_island",
+    "small_island",
+    "smallgrid",
+    "utah",
+    "west_coast_usa",
+];
+
+/// A level the server's Map setting can point to
+#[derive(Debug, Clone)]
+pub struct AvailableLevel {
+    pub name: String,
+    /// Client mod that provides it, None for stock levels
+    pub mod_name: Option<String>,
+}
+
+impl AvailableLevel {
+    /// Value for the Map setting, e.g. `/levels/gridmap_v2/info.json`
+    pub fn map_path(&self) -> String {
+        format!("/levels/{}/info.json", self.name)
+    }
+}
+
+/// Level folder named by a Map setting such as `/levels/gridmap_v2/info.json`
+pub fn map_level_name(map: &str) -> Option<&str> {
+    let mut parts = map.trim().trim_start_matches('/').split('/');
+    let root = parts.next()?;
+    let name = parts.next().filter(|name| !name.is_empty())?;
+    root.eq_ignore_ascii_case("levels").then_some(name)
+}
+
+/// Stock levels plus every level in the server's enabled client mods, sorted by name
+pub fn available_levels(server_path: &Path, resource_folder: &str, cache: &mut ModFileCache) -> Vec<AvailableLevel> {
+    let mut levels: Vec<AvailableLevel> = STOCK_LEVELS
+        .iter()
+        .map(|name| AvailableLevel { name: name.to_string(), mod_name: None })
+        .collect();
+
+    // A mod folder that can't be read just contributes nothing
+    let mods = scan_client_mods(server_path, resource_folder).unwrap_or_default();
+    for mod_entry in mods.iter().filter(|m| m.enabled && m.is_level) {
+        let Ok(details) = cache.details(&mod_entry.full_path) else {
+            continue;
+        };
+        for name in details.level_names {
+            levels.push(AvailableLevel { name, mod_name: Some(mod_entry.relative_path.clone()) });
+        }
+    }
+
+    levels.sort_by_key(|level| level.name.to_lowercase());
+    levels
+}
+
 pub fn get_mod_details(zip_path: &Path) -> Result<ModDetailInfo> {
     let file = fs::File::open(zip_path)?;
     let mut archive = zip::ZipArchive::new(file)?;
@@ -287,12 +666,66 @@ pub fn get_mod_details(zip_path: &Path) -> Result<ModDetailInfo> {
     })
 }
 
+/// A folder inside a mod ZIP, as listed by `read_zip_tree`
+#[derive(Debug, Default)]
+pub struct ZipDir {
+    /// Folders in this one, sorted by name
+    pub dirs: BTreeMap<String, ZipDir>,
+    /// Files in this folder as (name, uncompressed size), sorted by name
+    pub files: Vec<(String, u64)>,
+    /// Uncompressed size of everything below this folder
+    pub total_size: u64,
+    /// Number of files below this folder
+    pub total_files: usize,
+}
+
+/// Folder tree of a ZIP, read from its central directory without decompressing anything
+pub fn read_zip_tree(zip_path: &Path) -> Result<ZipDir> {
+    let file = fs::File::open(zip_path)?;
+    let mut archive = zip::ZipArchive::new(file)?;
+    let mut root = ZipDir::default();
+
+    for i in 0..archive.len() {
+        let entry = archive.by_index_raw(i)?;
+        let is_dir = entry.is_dir();
+        let size = entry.size();
+        let mut parts: Vec<&str> = entry.name().split('/').filter(|p| !p.is_empty()).collect();
+        // Folders usually have their own entry; their contents add them anyway
+        let file_name = if is_dir { None } else { parts.pop() };
+
+        let mut dir = &mut root;
+        for part in parts {
+            if file_name.is_some() {
+                dir.total_size += size;
+                dir.total_files += 1;
+            }
+            dir = dir.dirs.entry(part.to_string()).or_default();
+        }
+        if let Some(name) = file_name {
+            dir.total_size += size;
+            dir.total_files += 1;
+            dir.files.push((name.to_string(), size));
+        }
+    }
+
+    sort_zip_files(&mut root);
+    Ok(root)
+}
+
+fn sort_zip_files(dir: &mut ZipDir) {
+    dir.files.sort_by(|a, b| a.0.cmp(&b.0));
+    for child in dir.dirs.values_mut() {
+        sort_zip_files(child);
+    }
+}
+
 pub fn disable_server_mod(
     server_path: &Path,
     resource_folder: &str,
     relative_path: &str,
 ) -> Result<()> {
-    let source = server_path.join(resource_folder).join("Server").join(relative_path);
+    let source_root = server_path.join(resource_folder).join("Server");
+    let source = source_root.join(relative_path);
     let target = server_path
         .join(format!("{}_disabled", resource_folder))
         .join("Server")
@@ -303,7 +736,8 @@ pub fn disable_server_mod(
         fs::create_dir_all(parent)?;
     }
 
-    fs::rename(source, target)?;
+    move_mod(&source, &target)?;
+    remove_empty_groups(&source, &source_root);
     Ok(())
 }
 
@@ -312,10 +746,10 @@ pub fn enable_server_mod(
     resource_folder: &str,
     relative_path: &str,
 ) -> Result<()> {
-    let source = server_path
+    let source_root = server_path
         .join(format!("{}_disabled", resource_folder))
-        .join("Server")
-        .join(relative_path);
+        .join("Server");
+    let source = source_root.join(relative_path);
     let target = server_path.join(resource_folder).join("Server").join(relative_path);
 
     // Create parent directories if needed
@@ -323,10 +757,22 @@ pub fn enable_server_mod(
         fs::create_dir_all(parent)?;
     }
 
-    fs::rename(source, target)?;
+    move_mod(&source, &target)?;
+    remove_empty_groups(&source, &source_root);
     Ok(())
 }
 
+/// Remove the group folders a nested server mod leaves empty when it is moved out
+fn remove_empty_groups(moved: &Path, root: &Path) {
+    let mut dir = moved.parent();
+    while let Some(group) = dir {
+        if group == root || !group.starts_with(root) || fs::remove_dir(group).is_err() {
+            break;
+        }
+        dir = group.parent();
+    }
+}
+
 pub fn disable_client_mod(
     server_path: &Path,
     resource_folder: &str,
@@ -343,7 +789,7 @@ pub fn disable_client_mod(
         fs::create_dir_all(parent)?;
     }
 
-    fs::rename(source, target)?;
+    move_mod(&source, &target)?;
     Ok(())
 }
 
@@ -363,10 +809,400 @@ pub fn enable_client_mod(
         fs::create_dir_all(parent)?;
     }
 
-    fs::rename(source, target)?;
+    move_mod(&source, &target)?;
+    Ok(())
+}
+
+/// Whether a rename failed because source and target are on different filesystems
+fn is_cross_device_error(error: &std::io::Error) -> bool {
+    is_cross_device_code(error.raw_os_error(), cfg!(windows))
+}
+
+/// EXDEV on Unix, ERROR_NOT_SAME_DEVICE on Windows
+fn is_cross_device_code(code: Option<i32>, windows: bool) -> bool {
+    code == Some(if windows { 17 } else { 18 })
+}
+
+/// A mod couldn't be moved because something already exists where it would go, typically
+/// a copy of the same mod in the other tree. Returned instead of replacing it.
+#[derive(Debug)]
+pub struct TargetExists(pub PathBuf);
+
+impl std::fmt::Display for TargetExists {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} already exists", self.0.display())
+    }
+}
+
+impl std::error::Error for TargetExists {}
+
+/// Move a mod between the enabled and `_disabled` trees. Falls back to copy + delete
+/// when the trees are on different filesystems; the source is only removed once the
+/// copy has been verified. Never replaces an existing target, see `TargetExists`.
+fn move_mod(source: &Path, target: &Path) -> Result<()> {
+    move_mod_with(source, target, |source, target| fs::rename(source, target))
+}
+
+/// `move_mod` with the rename step passed in, so a failed rename can be tested
+fn move_mod_with(source: &Path, target: &Path, rename: impl Fn(&Path, &Path) -> std::io::Result<()>) -> Result<()> {
+    // rename() replaces files on Unix and fails on Windows, neither is wanted
+    if fs::symlink_metadata(target).is_ok() {
+        return Err(TargetExists(target.to_path_buf()).into());
+    }
+    match rename(source, target) {
+        Ok(_) => Ok(()),
+        Err(e) if is_cross_device_error(&e) => copy_then_delete(source, target),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn copy_then_delete(source: &Path, target: &Path) -> Result<()> {
+    copy_then_delete_with(source, target, copy_recursive)
+}
+
+/// `copy_then_delete` with the copy step passed in, so a bad copy can be tested
+fn copy_then_delete_with(source: &Path, target: &Path, copy: impl Fn(&Path, &Path) -> Result<()>) -> Result<()> {
+    let expected = tree_size(source)?;
+
+    // Don't leave a half-written copy behind if anything goes wrong
+    let copied = copy(source, target).and_then(|_| tree_size(target));
+    match copied {
+        Ok(actual) if actual == expected => {}
+        Ok(_) => {
+            let _ = delete_mod(target);
+            return Err(anyhow::anyhow!("Copy of {} was incomplete, source left in place", source.display()));
+        }
+        Err(e) => {
+            let _ = delete_mod(target);
+            return Err(e);
+        }
+    }
+
+    delete_mod(source)
+}
+
+fn copy_recursive(source: &Path, target: &Path) -> Result<()> {
+    if source.is_dir() {
+        fs::create_dir_all(target)?;
+        for entry in fs::read_dir(source)? {
+            let entry = entry?;
+            copy_recursive(&entry.path(), &target.join(entry.file_name()))?;
+        }
+    } else {
+        fs::copy(source, target)?;
+    }
+    Ok(())
+}
+
+/// File count and total byte size of a file or folder, used to verify copies
+fn tree_size(path: &Path) -> Result<(u64, u64)> {
+    if !path.is_dir() {
+        return Ok((1, fs::metadata(path)?.len()));
+    }
+
+    let mut files = 0;
+    let mut bytes = 0;
+    for entry in fs::read_dir(path)? {
+        let (entry_files, entry_bytes) = tree_size(&entry?.path())?;
+        files += entry_files;
+        bytes += entry_bytes;
+    }
+    Ok((files, bytes))
+}
+
+/// A mod that exists in both the enabled and the `_disabled` tree
+#[derive(Debug, Clone)]
+pub struct SplitMod {
+    pub name: String,
+    pub enabled_path: PathBuf,
+    pub disabled_path: PathBuf,
+}
+
+/// Cheap check for mods left in both trees by an interrupted move, without
+/// opening any archives. Covers both client and server mods. A folder of server mods
+/// that is in both trees is looked into rather than reported: disabling one nested mod
+/// leaves its group in both, and only mods that are in both count.
+pub fn find_split_mods(server_path: &Path, resource_folder: &str) -> Vec<SplitMod> {
+    let mut split = Vec::new();
+
+    for (kind, max_depth) in [("Client", 1), ("Server", SERVER_MOD_MAX_DEPTH)] {
+        let enabled_root = server_path.join(resource_folder).join(kind);
+        let disabled_root = server_path.join(format!("{}_disabled", resource_folder)).join(kind);
+        find_split_in(&enabled_root, &disabled_root, "", 1, max_depth, &mut split);
+    }
+
+    split
+}
+
+fn find_split_in(
+    enabled_dir: &Path,
+    disabled_dir: &Path,
+    prefix: &str,
+    depth: usize,
+    max_depth: usize,
+    split: &mut Vec<SplitMod>,
+) {
+    let Ok(entries) = fs::read_dir(disabled_dir) else {
+        return;
+    };
+    for entry in entries.map_while(Result::ok) {
+        let file_name = entry.file_name();
+        let name = format!("{}{}", prefix, file_name.to_string_lossy());
+        let enabled_path = enabled_dir.join(&file_name);
+        if !enabled_path.exists() {
+            continue;
+        }
+        let disabled_path = entry.path();
+        if depth < max_depth && is_group_pair(&enabled_path, &disabled_path) {
+            find_split_in(&enabled_path, &disabled_path, &format!("{}/", name), depth + 1, max_depth, split);
+            continue;
+        }
+        split.push(SplitMod {
+            name,
+            enabled_path,
+            disabled_path,
+        });
+    }
+}
+
+/// Whether two folders of the same name are groups of server mods: neither has Lua
+/// scripts of its own and at least one holds folders (the other may be left empty)
+fn is_group_pair(a: &Path, b: &Path) -> bool {
+    if !a.is_dir() || !b.is_dir() {
+        return false;
+    }
+    let (a_scripts, a_folders, _) = folder_contents(a);
+    let (b_scripts, b_folders, _) = folder_contents(b);
+    !a_scripts && !b_scripts && (a_folders || b_folders)
+}
+
+/// Where a copy of the mod `relative_path` goes on another server, `kind` being "Client" or
+/// "Server": over its existing copy, enabled or disabled, if it has one (then `true` is
+/// returned too), otherwise into the enabled tree
+pub fn copy_target(server_path: &Path, resource_folder: &str, kind: &str, relative_path: &str) -> (PathBuf, bool) {
+    let enabled = server_path.join(resource_folder).join(kind).join(relative_path);
+    let disabled = server_path
+        .join(format!("{}_disabled", resource_folder))
+        .join(kind)
+        .join(relative_path);
+    if enabled.exists() {
+        (enabled, true)
+    } else if disabled.exists() {
+        (disabled, true)
+    } else {
+        (enabled, false)
+    }
+}
+
+/// Copy a mod ZIP or folder to `target`, replacing anything already there. The copy is
+/// written next to the target and verified first, so a failure leaves the old one intact.
+/// Returns the number of files copied.
+pub fn copy_mod(source: &Path, target: &Path) -> Result<u64> {
+    let name = target
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid target path"))?
+        .to_string_lossy();
+    let partial = target.with_file_name(format!(".{}.copying", name));
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    if partial.exists() {
+        delete_mod(&partial)?;
+    }
+
+    let expected = tree_size(source)?;
+    let copied = copy_recursive(source, &partial).and_then(|_| tree_size(&partial));
+    match copied {
+        Ok(actual) if actual == expected => {}
+        Ok(_) => {
+            let _ = delete_mod(&partial);
+            return Err(anyhow::anyhow!("Copy of {} was incomplete", source.display()));
+        }
+        Err(e) => {
+            let _ = delete_mod(&partial);
+            return Err(e);
+        }
+    }
+
+    if target.exists() {
+        delete_mod(target)?;
+    }
+    fs::rename(&partial, target)?;
+    Ok(expected.0)
+}
+
+/// Folder in each server's directory that deleted mods are moved to
+pub const TRASH_FOLDER: &str = ".trash";
+
+/// Move a mod into the server's trash instead of deleting it. Each deletion gets its own
+/// timestamped folder in which the mod keeps its path relative to the server folder, so
+/// it can be put back. Returns where the mod now is.
+pub fn trash_mod(server_path: &Path, path: &Path) -> Result<PathBuf> {
+    let relative = path
+        .strip_prefix(server_path)
+        .map_err(|_| anyhow::anyhow!("{} is not inside the server folder", path.display()))?;
+    let trash = server_path.join(TRASH_FOLDER);
+    let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S%.3f").to_string();
+    let mut entry = trash.join(&stamp);
+    let mut n = 1;
+    while entry.exists() {
+        n += 1;
+        entry = trash.join(format!("{}-{}", stamp, n));
+    }
+
+    let target = entry.join(relative);
+    if let Some(parent) = target.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    move_mod(path, &target)?;
+    Ok(target)
+}
+
+/// A mod in a server's trash
+#[derive(Debug, Clone)]
+pub struct TrashedMod {
+    /// Where it is in the trash
+    pub path: PathBuf,
+    /// Where it was deleted from, relative to the server folder
+    pub original: PathBuf,
+    pub deleted_at: Option<chrono::NaiveDateTime>,
+    pub size: u64,
+}
+
+impl TrashedMod {
+    pub fn name(&self) -> String {
+        self.original.file_name().map(|n| n.to_string_lossy().into_owned()).unwrap_or_default()
+    }
+}
+
+/// Everything in the server's trash, most recently deleted first
+pub fn list_trash(server_path: &Path) -> Result<Vec<TrashedMod>> {
+    let trash = server_path.join(TRASH_FOLDER);
+    if !trash.is_dir() {
+        return Ok(Vec::new());
+    }
+
+    let mut trashed = Vec::new();
+    for entry in fs::read_dir(&trash)?.map_while(Result::ok) {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let deleted_at = name
+            .get(..15)
+            .and_then(|stamp| chrono::NaiveDateTime::parse_from_str(stamp, "%Y%m%d-%H%M%S").ok());
+        collect_trashed(&entry.path(), &mut |path| {
+            trashed.push(TrashedMod {
+                original: path.strip_prefix(entry.path()).unwrap_or(&path).to_path_buf(),
+                size: tree_size(&path).map(|(_, bytes)| bytes).unwrap_or(0),
+                path,
+                deleted_at,
+            });
+        });
+    }
+    trashed.sort_by(|a, b| b.path.cmp(&a.path));
+    Ok(trashed)
+}
+
+/// Mods are the entries of the Client and Server folders within a deletion's folder
+fn collect_trashed(dir: &Path, found: &mut impl FnMut(PathBuf)) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let is_mod_folder = matches!(dir.file_name().and_then(|n| n.to_str()), Some("Client" | "Server"));
+    for entry in entries.map_while(Result::ok) {
+        if is_mod_folder {
+            found(entry.path());
+        } else if entry.path().is_dir() {
+            collect_trashed(&entry.path(), found);
+        }
+    }
+}
+
+/// The deletion's folder a trashed path is in, and where it was deleted from relative to the server
+fn trash_entry(server_path: &Path, trashed: &Path) -> Result<(PathBuf, PathBuf)> {
+    let trash = server_path.join(TRASH_FOLDER);
+    let mut parts = trashed
+        .strip_prefix(&trash)
+        .map_err(|_| anyhow::anyhow!("{} is not in the server's trash", trashed.display()))?
+        .components();
+    let entry = trash.join(parts.next().ok_or_else(|| anyhow::anyhow!("Invalid trash path"))?);
+    Ok((entry, parts.as_path().to_path_buf()))
+}
+
+/// Permanently delete one mod from the server's trash
+pub fn purge_trashed(server_path: &Path, trashed: &Path) -> Result<()> {
+    let (entry, _) = trash_entry(server_path, trashed)?;
+    delete_mod(trashed)?;
+    remove_empty_dirs(&entry);
     Ok(())
 }
 
+/// Put a trashed mod back where it was deleted from. Returns its restored path.
+pub fn restore_mod(server_path: &Path, trashed: &Path) -> Result<PathBuf> {
+    let (entry, relative) = trash_entry(server_path, trashed)?;
+    let original = server_path.join(&relative);
+
+    if !trashed.exists() {
+        return Err(anyhow::anyhow!("{} is no longer in the trash", relative.display()));
+    }
+    if original.exists() {
+        return Err(anyhow::anyhow!("{} already exists", original.display()));
+    }
+    if let Some(parent) = original.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    move_mod(trashed, &original)?;
+    // The deletion's folder only held this mod and the folders leading to it
+    remove_empty_dirs(&entry);
+    Ok(original)
+}
+
+fn remove_empty_dirs(path: &Path) {
+    if let Ok(entries) = fs::read_dir(path) {
+        for entry in entries.map_while(Result::ok) {
+            remove_empty_dirs(&entry.path());
+        }
+    }
+    // Fails, as wanted, unless the folder is now empty
+    let _ = fs::remove_dir(path);
+}
+
+/// Delete the oldest deletions in the server's trash until it holds at most `max_bytes`,
+/// never removing one that holds a path in `keep`. Returns the bytes freed.
+pub fn prune_trash(server_path: &Path, max_bytes: u64, keep: &[PathBuf]) -> Result<u64> {
+    let trash = server_path.join(TRASH_FOLDER);
+    if !trash.is_dir() {
+        return Ok(0);
+    }
+    let mut entries: Vec<(PathBuf, u64)> = fs::read_dir(&trash)?
+        .map_while(Result::ok)
+        .map(|entry| {
+            let size = tree_size(&entry.path()).map(|(_, bytes)| bytes).unwrap_or(0);
+            (entry.path(), size)
+        })
+        .collect();
+    // Folder names are timestamps, so this is oldest first
+    entries.sort();
+
+    let mut total: u64 = entries.iter().map(|(_, size)| size).sum();
+    let mut freed = 0;
+    for (path, size) in entries {
+        if total <= max_bytes {
+            break;
+        }
+        if keep.iter().any(|kept| kept.starts_with(&path)) {
+            continue;
+        }
+        delete_mod(&path)?;
+        total -= size;
+        freed += size;
+    }
+    Ok(freed)
+}
+
+/// Permanently delete everything in the server's trash. Returns the bytes freed.
+pub fn empty_trash(server_path: &Path) -> Result<u64> {
+    prune_trash(server_path, 0, &[])
+}
+
 pub fn delete_mod(path: &Path) -> Result<()> {
     // Handle both files (client mods) and directories (server mods)
     if path.is_dir() {
@@ -377,7 +1213,12 @@ pub fn delete_mod(path: &Path) -> Result<()> {
     Ok(())
 }
 
-pub fn add_client_mod(server_path: &Path, resource_folder: &str, source_path: &Path) -> Result<()> {
+pub fn add_client_mod(
+    server_path: &Path,
+    resource_folder: &str,
+    source_path: &Path,
+    allow_unknown_structure: bool,
+) -> Result<()> {
     let file_name = source_path
         .file_name()
         .ok_or_else(|| anyhow::anyhow!("Invalid file name"))?;
@@ -390,6 +1231,9 @@ pub fn add_client_mod(server_path: &Path, resource_folder: &str, source_path: &P
     } else {
         return Err(anyhow::anyhow!("Only ZIP files are allowed for client mods"));
     }
+
+    // A broken archive copied into Resources/Client would break the server for everyone
+    validate_client_mod(source_path, allow_unknown_structure)?;
     
     let client_dir = server_path.join(resource_folder).join("Client");
     let target = client_dir.join(file_name);
@@ -403,3 +1247,325 @@ pub fn add_client_mod(server_path: &Path, resource_folder: &str, source_path: &P
     Ok(())
 }
 
+/// Zip a mod built as a folder into Resources/Client as `<folder name>.zip`. Paths in the
+/// archive are relative to `folder`, so it should contain levels/, vehicles/ and so on
+/// directly. Returns the written ZIP.
+pub fn package_client_mod(
+    server_path: &Path,
+    resource_folder: &str,
+    folder: &Path,
+    allow_unknown_structure: bool,
+) -> Result<PathBuf> {
+    let name = folder
+        .file_name()
+        .ok_or_else(|| anyhow::anyhow!("Invalid folder name"))?
+        .to_string_lossy()
+        .into_owned();
+
+    let mut files = Vec::new();
+    collect_files(folder, folder, &mut files)?;
+    if files.is_empty() {
+        return Err(anyhow::anyhow!("The folder is empty"));
+    }
+    let recognized = files.iter().any(|(relative, _)| {
+        let (level, vehicle) = classify_zip_entry(relative);
+        level || vehicle
+    });
+    if !recognized && !allow_unknown_structure {
+        return Err(anyhow::anyhow!(
+            "No levels/ or vehicles/ folder directly inside {}. \
+             Allow mods with an unknown structure in Settings to package it anyway",
+            name
+        ));
+    }
+
+    let client_dir = server_path.join(resource_folder).join("Client");
+    fs::create_dir_all(&client_dir)?;
+    let target = client_dir.join(format!("{}.zip", name));
+    if target.exists() {
+        return Err(anyhow::anyhow!("{}.zip already exists in the Client folder", name));
+    }
+
+    // Written under a temporary name so a failed run never leaves half a mod behind
+    let partial = client_dir.join(format!("{}.zip.part", name));
+    let written = write_zip(&partial, &files);
+    if let Err(e) = written.and_then(|_| Ok(fs::rename(&partial, &target)?)) {
+        let _ = fs::remove_file(&partial);
+        return Err(e);
+    }
+    Ok(target)
+}
+
+/// Files below `dir` as (path relative to `root` with `/` separators, full path)
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<(String, PathBuf)>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            collect_files(root, &path, files)?;
+        } else {
+            let relative = path
+                .strip_prefix(root)?
+                .components()
+                .map(|c| c.as_os_str().to_string_lossy())
+                .collect::<Vec<_>>()
+                .join("/");
+            files.push((relative, path));
+        }
+    }
+    Ok(())
+}
+
+fn write_zip(target: &Path, files: &[(String, PathBuf)]) -> Result<()> {
+    let mut zip = zip::ZipWriter::new(fs::File::create(target)?);
+    let options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .large_file(true);
+    for (relative, path) in files {
+        zip.start_file(relative.as_str(), options)?;
+        std::io::copy(&mut fs::File::open(path)?, &mut zip)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+    use std::io::Write;
+
+    fn write_zip(path: &Path, files: &[&str]) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        let mut zip = zip::ZipWriter::new(fs::File::create(path).unwrap());
+        for name in files {
+            zip.start_file(*name, zip::write::SimpleFileOptions::default()).unwrap();
+            zip.write_all(name.as_bytes()).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    #[test]
+    fn finds_conflicts_between_enabled_mods() {
+        let server = temp_dir("conflicts");
+        let client = server.join("Resources").join("Client");
+        write_zip(&client.join("a.zip"), &["vehicles/pickup/pickup.jbeam", "levels/island/info.json"]);
+        write_zip(&client.join("b.zip"), &["vehicles/Pickup/skin.dds"]);
+        let mut cache = ModFileCache::default();
+
+        let mods = scan_client_mods(&server, "Resources").unwrap();
+        let conflicts = find_mod_conflicts(&mods, &mut cache);
+        assert_eq!(conflicts.len(), 1);
+        assert_eq!(conflicts[0].kind, ConflictKind::Vehicle);
+        assert_eq!(conflicts[0].name.to_lowercase(), "pickup");
+        let names: Vec<&str> = conflicts[0].mods.iter().map(|&i| mods[i].relative_path.as_str()).collect();
+        assert_eq!(names, ["a.zip", "b.zip"]);
+
+        let disabled = server.join("Resources_disabled").join("Client");
+        fs::create_dir_all(&disabled).unwrap();
+        fs::rename(client.join("b.zip"), disabled.join("b.zip")).unwrap();
+        let mods = scan_client_mods(&server, "Resources").unwrap();
+        assert!(find_mod_conflicts(&mods, &mut cache).is_empty());
+
+        fs::remove_dir_all(server).unwrap();
+    }
+
+    fn write_script(path: &Path) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, "-- test").unwrap();
+    }
+
+    #[test]
+    fn scans_nested_server_mods() {
+        let server = temp_dir("nested");
+        let enabled = server.join("Resources").join("Server");
+        let disabled = server.join("Resources_disabled").join("Server");
+        // A mod with a subfolder of its own, and a group holding two mods
+        write_script(&enabled.join("plugin").join("main.lua"));
+        write_script(&enabled.join("plugin").join("sub").join("helper.lua"));
+        write_script(&enabled.join("group").join("inner").join("main.lua"));
+        write_script(&disabled.join("group").join("other").join("sub").join("main.lua"));
+
+        let found = |nested| -> Vec<(String, bool)> {
+            scan_server_mods(&server, "Resources", nested)
+                .unwrap()
+                .into_iter()
+                .map(|m| (m.relative_path, m.enabled))
+                .collect()
+        };
+        assert_eq!(
+            found(true),
+            [
+                ("group/inner".to_string(), true),
+                ("group/other/sub".to_string(), false),
+                ("plugin".to_string(), true),
+            ]
+        );
+        // Without nesting every top-level folder is a mod; the group is in both trees
+        let flat = scan_server_mods(&server, "Resources", false).unwrap();
+        let names: Vec<(&str, bool)> = flat.iter().map(|m| (m.relative_path.as_str(), m.enabled)).collect();
+        assert_eq!(names, [("group", true), ("plugin", true)]);
+        assert!(flat[0].disabled_copy.is_some());
+
+        fs::remove_dir_all(server).unwrap();
+    }
+
+    #[test]
+    fn finds_split_mods_inside_groups() {
+        let server = temp_dir("split");
+        let enabled = server.join("Resources").join("Server");
+        let disabled = server.join("Resources_disabled").join("Server");
+        write_script(&enabled.join("group").join("inner").join("main.lua"));
+        write_script(&disabled.join("group").join("other").join("main.lua"));
+        // A group in both trees with different mods in it is not split
+        assert!(find_split_mods(&server, "Resources").is_empty());
+
+        write_script(&disabled.join("group").join("inner").join("main.lua"));
+        write_script(&enabled.join("plugin").join("main.lua"));
+        write_script(&disabled.join("plugin").join("main.lua"));
+        write_zip(&server.join("Resources").join("Client").join("car.zip"), &["vehicles/car/a.jbeam"]);
+        write_zip(&server.join("Resources_disabled").join("Client").join("car.zip"), &["vehicles/car/a.jbeam"]);
+
+        let mut names: Vec<String> = find_split_mods(&server, "Resources").into_iter().map(|s| s.name).collect();
+        names.sort();
+        assert_eq!(names, ["car.zip", "group/inner", "plugin"]);
+
+        fs::remove_dir_all(server).unwrap();
+    }
+
+    #[test]
+    fn moves_client_mod_between_trees() {
+        let server = temp_dir("move");
+        let enabled = server.join("Resources").join("Client").join("car.zip");
+        let disabled = server.join("Resources_disabled").join("Client").join("car.zip");
+        write_zip(&enabled, &["vehicles/car/a.jbeam"]);
+        let contents = fs::read(&enabled).unwrap();
+
+        disable_client_mod(&server, "Resources", "car.zip").unwrap();
+        assert!(!enabled.exists());
+        assert_eq!(fs::read(&disabled).unwrap(), contents);
+
+        enable_client_mod(&server, "Resources", "car.zip").unwrap();
+        assert!(!disabled.exists());
+        assert_eq!(fs::read(&enabled).unwrap(), contents);
+
+        fs::remove_dir_all(server).unwrap();
+    }
+
+    #[test]
+    fn refuses_to_replace_existing_target() {
+        let server = temp_dir("collision");
+        let enabled = server.join("Resources").join("Client").join("car.zip");
+        let disabled = server.join("Resources_disabled").join("Client").join("car.zip");
+        write_zip(&enabled, &["vehicles/car/new.jbeam"]);
+        write_zip(&disabled, &["vehicles/car/old.jbeam"]);
+        let (enabled_contents, disabled_contents) = (fs::read(&enabled).unwrap(), fs::read(&disabled).unwrap());
+
+        let error = disable_client_mod(&server, "Resources", "car.zip").unwrap_err();
+        let exists = error.downcast_ref::<TargetExists>().expect("a TargetExists error");
+        assert_eq!(exists.0, disabled);
+        assert_eq!(fs::read(&enabled).unwrap(), enabled_contents);
+        assert_eq!(fs::read(&disabled).unwrap(), disabled_contents);
+
+        fs::remove_dir_all(server).unwrap();
+    }
+
+    #[test]
+    fn recognizes_cross_device_errors() {
+        assert!(is_cross_device_code(Some(18), false));
+        assert!(is_cross_device_code(Some(17), true));
+        // EEXIST on Unix, ERROR_NOT_READY on Windows
+        assert!(!is_cross_device_code(Some(17), false));
+        assert!(!is_cross_device_code(Some(18), true));
+        assert!(!is_cross_device_code(None, false));
+        assert!(!is_cross_device_code(None, true));
+    }
+
+    #[test]
+    fn copy_then_delete_moves_a_folder() {
+        let dir = temp_dir("copy");
+        let source = dir.join("plugin");
+        write_script(&source.join("main.lua"));
+        write_script(&source.join("sub").join("helper.lua"));
+        let target = dir.join("moved");
+
+        copy_then_delete(&source, &target).unwrap();
+        assert!(!source.exists());
+        assert_eq!(tree_size(&target).unwrap(), (2, 14));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn copy_then_delete_keeps_source_when_copy_is_incomplete() {
+        let dir = temp_dir("badcopy");
+        let source = dir.join("plugin");
+        write_script(&source.join("main.lua"));
+        write_script(&source.join("sub").join("helper.lua"));
+        let target = dir.join("moved");
+
+        // Copies everything but comes up short on one file
+        let short_copy = |source: &Path, target: &Path| {
+            copy_recursive(source, target)?;
+            fs::write(target.join("main.lua"), "--")?;
+            Ok(())
+        };
+        let error = copy_then_delete_with(&source, &target, short_copy).unwrap_err();
+        assert!(error.to_string().contains("incomplete"));
+        assert_eq!(tree_size(&source).unwrap(), (2, 14));
+        assert!(!target.exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn move_mod_copies_when_rename_crosses_filesystems() {
+        let dir = temp_dir("exdev");
+        let source = dir.join("plugin");
+        write_script(&source.join("main.lua"));
+        write_script(&source.join("sub").join("helper.lua"));
+        let target = dir.join("moved");
+
+        let cross_device = |_: &Path, _: &Path| Err(std::io::Error::from_raw_os_error(if cfg!(windows) { 17 } else { 18 }));
+        move_mod_with(&source, &target, cross_device).unwrap();
+        assert!(!source.exists());
+        assert_eq!(tree_size(&target).unwrap(), (2, 14));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn move_mod_returns_other_rename_errors() {
+        let dir = temp_dir("denied");
+        let source = dir.join("plugin");
+        write_script(&source.join("main.lua"));
+        let target = dir.join("moved");
+
+        let denied = |_: &Path, _: &Path| Err(std::io::Error::from(std::io::ErrorKind::PermissionDenied));
+        let error = move_mod_with(&source, &target, denied).unwrap_err();
+        let io_error = error.downcast_ref::<std::io::Error>().expect("the rename's io::Error");
+        assert_eq!(io_error.kind(), std::io::ErrorKind::PermissionDenied);
+        assert_eq!(tree_size(&source).unwrap(), (1, 7));
+        assert!(!target.exists());
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn prune_trash_keeps_the_listed_deletions() {
+        let server = temp_dir("prune");
+        let client = server.join("Resources").join("Client");
+        write_zip(&client.join("old.zip"), &["vehicles/old/a.jbeam"]);
+        write_zip(&client.join("new.zip"), &["vehicles/new/a.jbeam"]);
+        let old = trash_mod(&server, &client.join("old.zip")).unwrap();
+        let new = trash_mod(&server, &client.join("new.zip")).unwrap();
+
+        // Over the limit even with only the kept deletion left
+        let freed = prune_trash(&server, 0, std::slice::from_ref(&new)).unwrap();
+        assert!(freed > 0);
+        assert!(!old.exists());
+        assert!(new.exists());
+
+        fs::remove_dir_all(server).unwrap();
+    }
+}
@@ -0,0 +1,13 @@
+use std::thread;
+
+/// Show a desktop notification. Best effort: on systems without a notification service
+/// nothing is shown, and the call never blocks the UI.
+pub fn send(summary: String, body: String) {
+    thread::spawn(move || {
+        let _ = notify_rust::Notification::new()
+            .appname("BeamMP Panel")
+            .summary(&summary)
+            .body(&body)
+            .show();
+    });
+}
@@ -0,0 +1,346 @@
+use std::collections::VecDeque;
+
+/// Turns the server's response to `list` back into player names.
+///
+/// BeamMP-Server prints either `No players online.` or a table:
+///
+/// ```text
+/// Name                     ID    Cars
+/// SomePlayer               0     1
+/// Another Player           3     0
+/// ```
+///
+/// Some builds print `Name (ID: 3)` rows instead, which are accepted inside the block too.
+/// The block has no end marker, so it ends at the first line that isn't a player row.
+#[derive(Default)]
+pub struct PlayerListParser {
+    in_block: bool,
+    players: Vec<String>,
+}
+
+impl PlayerListParser {
+    /// Call right after sending `list`, so rows are accepted even if this server
+    /// build prints them without a header
+    pub fn expect_list(&mut self) {
+        self.in_block = true;
+        self.players.clear();
+    }
+
+    /// Feed one line of console output. Returns the updated player list whenever
+    /// this line changed it, and None for unrelated lines.
+    pub fn feed(&mut self, line: &str) -> Option<Vec<String>> {
+        let text = strip_log_prefix(line).trim();
+
+        if text.eq_ignore_ascii_case("No players online.") || text.eq_ignore_ascii_case("No players online") {
+            self.in_block = false;
+            self.players.clear();
+            return Some(Vec::new());
+        }
+
+        if is_header(text) {
+            // A new response: anything from an earlier `list` is stale now
+            self.in_block = true;
+            self.players.clear();
+            return Some(Vec::new());
+        }
+
+        if !self.in_block {
+            return None;
+        }
+
+        match parse_row(text) {
+            Some(name) => {
+                self.players.push(name);
+                Some(self.players.clone())
+            }
+            None => {
+                self.in_block = false;
+                None
+            }
+        }
+    }
+}
+
+/// Tags BeamMP-Server and the panel put in front of a line, besides the timestamp
+const LOG_TAGS: [&str; 8] = ["INFO", "WARN", "WARNING", "ERROR", "DEBUG", "LUA", "CHAT", "PANEL"];
+
+/// Drop leading `[...]` tags such as timestamps, `[INFO]` or the panel's own `[ERROR]`.
+/// Other brackets are kept, since they may start a player name like `[TAG]Name`.
+fn strip_log_prefix(line: &str) -> &str {
+    let mut rest = line.trim_start();
+    while let Some(tag) = rest.strip_prefix('[') {
+        let Some(end) = tag.find(']') else { break };
+        let inner = tag[..end].trim();
+        let is_log_tag = inner.starts_with(|c: char| c.is_ascii_digit())
+            || LOG_TAGS.iter().any(|known| inner.eq_ignore_ascii_case(known));
+        if !is_log_tag {
+            break;
+        }
+        rest = tag[end + 1..].trim_start();
+    }
+    rest
+}
+
+fn is_header(text: &str) -> bool {
+    let mut columns = text.split_whitespace();
+    columns.next() == Some("Name") && columns.next() == Some("ID")
+}
+
+fn parse_row(text: &str) -> Option<String> {
+    // "Name (ID: 3)"
+    if let Some(start) = text.rfind(" (ID:") {
+        let id = text[start + 5..].trim().strip_suffix(')')?;
+        id.trim().parse::<u32>().ok()?;
+        let name = text[..start].trim();
+        return (!name.is_empty()).then(|| name.to_string());
+    }
+
+    // "Name   ID   Cars" - names may contain spaces, so take the two numeric columns from the end
+    let columns: Vec<&str> = text.split_whitespace().collect();
+    if columns.len() < 3 {
+        return None;
+    }
+    let (name, numbers) = columns.split_at(columns.len() - 2);
+    if numbers.iter().all(|n| n.parse::<u32>().is_ok()) {
+        Some(name.join(" "))
+    } else {
+        None
+    }
+}
+
+/// A player joining or leaving, from the lines BeamMP-Server logs for it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PlayerEvent {
+    Joined(String),
+    Left(String),
+}
+
+/// Recognize `Name : Connected` and `Name Connection Terminated` (or `Lost Connection`),
+/// after the timestamp and level tags
+pub fn parse_player_event(line: &str) -> Option<PlayerEvent> {
+    let rest = strip_log_prefix(line);
+    // A chat message could end the same way
+    if line[..line.len() - rest.len()].contains("[CHAT]") {
+        return None;
+    }
+    let text = rest.trim();
+    if let Some(name) = text.strip_suffix(" : Connected") {
+        return (!name.trim().is_empty()).then(|| PlayerEvent::Joined(name.trim().to_string()));
+    }
+    let name = text
+        .strip_suffix(" Connection Terminated")
+        .or_else(|| text.strip_suffix(" Lost Connection"))?;
+    (!name.trim().is_empty()).then(|| PlayerEvent::Left(name.trim().to_string()))
+}
+
+pub struct PlayerHistoryEntry {
+    pub at: chrono::DateTime<chrono::Local>,
+    pub name: String,
+    pub joined: bool,
+}
+
+/// Joins and leaves seen during one run of a server, oldest first
+#[derive(Default)]
+pub struct PlayerHistory {
+    pub entries: Vec<PlayerHistoryEntry>,
+}
+
+impl PlayerHistory {
+    pub fn record(&mut self, event: PlayerEvent) {
+        let (name, joined) = match event {
+            PlayerEvent::Joined(name) => (name, true),
+            PlayerEvent::Left(name) => (name, false),
+        };
+        self.entries.push(PlayerHistoryEntry { at: chrono::Local::now(), name, joined });
+    }
+
+    /// Different players who joined this run
+    pub fn unique_players(&self) -> usize {
+        self.entries
+            .iter()
+            .filter(|e| e.joined)
+            .map(|e| e.name.as_str())
+            .collect::<std::collections::HashSet<_>>()
+            .len()
+    }
+
+    /// `time,player,event` rows with a header line
+    pub fn to_csv(&self) -> String {
+        let mut csv = String::from("time,player,event\n");
+        for entry in &self.entries {
+            let name = if entry.name.contains([',', '"', '\n']) {
+                format!("\"{}\"", entry.name.replace('"', "\"\""))
+            } else {
+                entry.name.clone()
+            };
+            csv.push_str(&format!(
+                "{},{},{}\n",
+                entry.at.format("%Y-%m-%d %H:%M:%S"),
+                name,
+                if entry.joined { "joined" } else { "left" }
+            ));
+        }
+        csv
+    }
+}
+
+/// A chat message from the console. BeamMP-Server logs chat when LogChat is on, as
+/// `[CHAT] (3) <Name> text`, or `[CHAT] <Server> ...` for messages sent with `say`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChatLine {
+    /// Empty when the line names no sender
+    pub sender: String,
+    pub text: String,
+}
+
+pub fn parse_chat_line(line: &str) -> Option<ChatLine> {
+    // The [CHAT] tag comes after the timestamp, before the message
+    let mut rest = line.trim_start();
+    let mut is_chat = false;
+    while rest.starts_with('[') {
+        let end = rest.find(']')?;
+        is_chat |= &rest[1..end] == "CHAT";
+        rest = rest[end + 1..].trim_start();
+    }
+    if !is_chat {
+        return None;
+    }
+
+    // Player ID
+    if let Some(after) = rest.strip_prefix('(') {
+        if let Some(end) = after.find(')') {
+            if after[..end].trim().parse::<u32>().is_ok() {
+                rest = after[end + 1..].trim_start();
+            }
+        }
+    }
+
+    if let Some(after) = rest.strip_prefix('<') {
+        if let Some(end) = after.find('>') {
+            return Some(ChatLine {
+                sender: after[..end].to_string(),
+                text: after[end + 1..].trim().to_string(),
+            });
+        }
+    }
+    Some(ChatLine { sender: String::new(), text: rest.trim().to_string() })
+}
+
+/// Chat messages kept per running server
+const MAX_CHAT_MESSAGES: usize = 500;
+
+/// Chat of one run of a server with the time each message arrived, oldest first
+#[derive(Default)]
+pub struct ChatLog {
+    pub messages: VecDeque<(chrono::DateTime<chrono::Local>, ChatLine)>,
+}
+
+impl ChatLog {
+    pub fn push(&mut self, line: ChatLine) {
+        if self.messages.len() == MAX_CHAT_MESSAGES {
+            self.messages.pop_front();
+        }
+        self.messages.push_back((chrono::Local::now(), line));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn feed_all(parser: &mut PlayerListParser, lines: &[&str]) -> Option<Vec<String>> {
+        lines.iter().fold(None, |last, line| parser.feed(line).or(last))
+    }
+
+    #[test]
+    fn parses_player_table() {
+        let mut parser = PlayerListParser::default();
+        parser.expect_list();
+        let players = feed_all(
+            &mut parser,
+            &[
+                "[12/03/24 10:00:00] [INFO] Name                     ID    Cars",
+                "[12/03/24 10:00:00] [INFO] SomePlayer               0     1",
+                "[12/03/24 10:00:00] [INFO] Another                  3     0",
+                "[12/03/24 10:00:01] [INFO] Vehicle spawned",
+            ],
+        );
+        assert_eq!(players, Some(vec!["SomePlayer".to_string(), "Another".to_string()]));
+    }
+
+    #[test]
+    fn parses_empty_list() {
+        let mut parser = PlayerListParser::default();
+        parser.expect_list();
+        parser.feed("Name ID Cars");
+        parser.feed("SomePlayer 0 1");
+        assert_eq!(parser.feed("[12/03/24 10:00:00] [INFO] No players online."), Some(Vec::new()));
+        // The block is over, so rows after it are ignored
+        assert_eq!(parser.feed("Other 1 0"), None);
+    }
+
+    #[test]
+    fn keeps_spaces_and_brackets_in_names() {
+        let mut parser = PlayerListParser::default();
+        parser.expect_list();
+        let players = feed_all(
+            &mut parser,
+            &[
+                "Name ID Cars",
+                "[12/03/24 10:00:00] [INFO] Another Player   3   0",
+                "[12/03/24 10:00:00] [INFO] [TAG]Racer   4   2",
+                "[Crew] Driver (ID: 5)",
+            ],
+        );
+        assert_eq!(
+            players,
+            Some(vec!["Another Player".to_string(), "[TAG]Racer".to_string(), "[Crew] Driver".to_string()])
+        );
+    }
+
+    #[test]
+    fn continues_list_across_feed_calls() {
+        let mut parser = PlayerListParser::default();
+        parser.expect_list();
+        assert_eq!(parser.feed("Name ID Cars"), Some(Vec::new()));
+        assert_eq!(parser.feed("First 0 1"), Some(vec!["First".to_string()]));
+        // Anything else may arrive between two frames' reads
+        assert_eq!(parser.feed("Second 1 0"), Some(vec!["First".to_string(), "Second".to_string()]));
+        assert_eq!(parser.feed("Server tick"), None);
+        assert_eq!(parser.feed("Third 2 0"), None);
+
+        // A new header replaces the old list
+        assert_eq!(parser.feed("Name ID Cars"), Some(Vec::new()));
+        assert_eq!(parser.feed("Third 2 0"), Some(vec!["Third".to_string()]));
+    }
+
+    fn chat(sender: &str, text: &str) -> Option<ChatLine> {
+        Some(ChatLine { sender: sender.to_string(), text: text.to_string() })
+    }
+
+    #[test]
+    fn parses_chat_lines() {
+        assert_eq!(parse_chat_line("[16/03/24 12:00:00] [CHAT] (3) <Some Player> hello there"), chat("Some Player", "hello there"));
+        assert_eq!(parse_chat_line("[CHAT] (0) <Racer> meet at 12:30: the docks"), chat("Racer", "meet at 12:30: the docks"));
+        assert_eq!(parse_chat_line("[16/03/24 12:00:00] [CHAT] <Server> restarting soon"), chat("Server", "restarting soon"));
+        assert_eq!(parse_chat_line("[CHAT] no sender"), chat("", "no sender"));
+    }
+
+    #[test]
+    fn ignores_other_lines() {
+        assert_eq!(parse_chat_line("[16/03/24 12:00:00] [INFO] Racer : Connected"), None);
+        assert_eq!(parse_chat_line("Racer: [CHAT] hello"), None);
+        assert_eq!(parse_chat_line(""), None);
+    }
+
+    #[test]
+    fn chat_log_drops_oldest_messages() {
+        let mut log = ChatLog::default();
+        for i in 0..MAX_CHAT_MESSAGES + 3 {
+            log.push(ChatLine { sender: "Racer".to_string(), text: i.to_string() });
+        }
+        assert_eq!(log.messages.len(), MAX_CHAT_MESSAGES);
+        assert_eq!(log.messages.front().map(|(_, line)| line.text.as_str()), Some("3"));
+        assert_eq!(log.messages.back().map(|(_, line)| line.text.clone()), Some((MAX_CHAT_MESSAGES + 2).to_string()));
+    }
+}
@@ -1,35 +1,384 @@
 use anyhow::{anyhow, Result};
-use std::io::{BufRead, BufReader, Write};
-use std::path::Path;
-use std::process::{Child, ChildStdin, Command, Stdio};
-use std::sync::mpsc::{sync_channel, Receiver};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, ExitStatus, Stdio};
+use std::sync::mpsc::sync_channel;
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::{Arc, Mutex};
 use std::thread;
+use std::time::{Duration, Instant};
 
 #[cfg(windows)]
 use std::os::windows::process::CommandExt;
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum BinaryFormat {
+    Elf,
+    Pe,
+    MachO,
+}
+
+impl BinaryFormat {
+    fn host() -> Self {
+        if cfg!(windows) {
+            BinaryFormat::Pe
+        } else if cfg!(target_os = "macos") {
+            BinaryFormat::MachO
+        } else {
+            BinaryFormat::Elf
+        }
+    }
+
+    fn platform_name(&self) -> &'static str {
+        match self {
+            BinaryFormat::Elf => "Linux",
+            BinaryFormat::Pe => "Windows",
+            BinaryFormat::MachO => "macOS",
+        }
+    }
+}
+
+/// Identify the executable format and CPU architecture from the file header.
+/// Returns None if the file can't be read or isn't a recognized executable.
+fn detect_binary(path: &Path) -> Option<(BinaryFormat, Option<&'static str>)> {
+    let mut header = Vec::with_capacity(4096);
+    std::fs::File::open(path)
+        .ok()?
+        .take(4096)
+        .read_to_end(&mut header)
+        .ok()?;
+
+    let u16_le = |offset: usize| {
+        header
+            .get(offset..offset + 2)
+            .map(|b| u16::from_le_bytes([b[0], b[1]]))
+    };
+    let u32_le = |offset: usize| {
+        header
+            .get(offset..offset + 4)
+            .map(|b| u32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+    };
+
+    if header.starts_with(b"\x7fELF") {
+        let arch = match u16_le(18)? {
+            0x03 => Some("x86"),
+            0x28 => Some("arm"),
+            0x3E => Some("x86_64"),
+            0xB7 => Some("aarch64"),
+            _ => None,
+        };
+        return Some((BinaryFormat::Elf, arch));
+    }
+
+    if header.starts_with(b"MZ") {
+        let pe_offset = u32_le(0x3C)? as usize;
+        if header.get(pe_offset..pe_offset + 4) != Some(b"PE\0\0".as_slice()) {
+            return Some((BinaryFormat::Pe, None));
+        }
+        let arch = match u16_le(pe_offset + 4)? {
+            0x014C => Some("x86"),
+            0x8664 => Some("x86_64"),
+            0xAA64 => Some("aarch64"),
+            _ => None,
+        };
+        return Some((BinaryFormat::Pe, arch));
+    }
+
+    match u32_le(0)? {
+        // Thin 64-bit Mach-O
+        0xFEED_FACF => {
+            let arch = match u32_le(4)? {
+                0x0100_0007 => Some("x86_64"),
+                0x0100_000C => Some("aarch64"),
+                _ => None,
+            };
+            Some((BinaryFormat::MachO, arch))
+        }
+        // Universal binary (stored big-endian), runs on either architecture
+        0xBEBA_FECA => Some((BinaryFormat::MachO, None)),
+        _ => None,
+    }
+}
+
+/// Explain why `exe_path` can't run on this machine, if its header says so
+pub fn platform_mismatch(exe_path: &Path) -> Option<String> {
+    let (format, arch) = detect_binary(exe_path)?;
+    let host_format = BinaryFormat::host();
+    let host_arch = std::env::consts::ARCH;
+
+    if format != host_format {
+        return Some(format!(
+            "The server binary is a {} executable but this machine runs {}. Download the BeamMP-Server build for {}.",
+            format.platform_name(),
+            host_format.platform_name(),
+            host_format.platform_name(),
+        ));
+    }
+
+    // 32-bit x86 binaries still run on x86_64 hosts
+    let compatible = match arch {
+        None => true,
+        Some(arch) => arch == host_arch || (arch == "x86" && host_arch == "x86_64"),
+    };
+    if !compatible {
+        return Some(format!(
+            "The server binary is built for {} but this machine is {}. Download the {} build of BeamMP-Server.",
+            arch.unwrap_or("another architecture"),
+            host_arch,
+            host_arch,
+        ));
+    }
+
+    None
+}
+
+/// Whether a spawn error means the OS refused the executable's format
+fn is_exec_format_error(error: &std::io::Error) -> bool {
+    // ENOEXEC on Unix, ERROR_BAD_EXE_FORMAT on Windows
+    let code = if cfg!(windows) { 193 } else { 8 };
+    error.raw_os_error() == Some(code)
+}
+
+/// Whether writing to a server failed because its stdin is closed, i.e. the server has
+/// exited or is exiting
+pub fn is_pipe_closed(error: &anyhow::Error) -> bool {
+    error
+        .downcast_ref::<std::io::Error>()
+        .is_some_and(|e| e.kind() == std::io::ErrorKind::BrokenPipe)
+}
+
+// Lines BeamMP-Server prints once it is listening for players
+const READY_MARKERS: [&str; 2] = [
+    "ALL SYSTEMS STARTED SUCCESSFULLY",
+    "Vehicle data network online",
+];
+
+/// Whether a line of server output means the server has finished starting up
+pub fn is_ready_line(line: &str) -> bool {
+    READY_MARKERS.iter().any(|marker| line.contains(marker))
+}
+
+/// Version from the banner BeamMP-Server prints when it starts, e.g. "BeamMP Server v3.4.1"
+pub fn banner_version(line: &str) -> Option<String> {
+    let lower = line.to_lowercase();
+    let start = ["beammp server v", "beammp-server v"].iter().find_map(|banner| lower.find(banner))?;
+    let (major, minor, patch) = crate::updates::parse_version(&lower[start..])?;
+    Some(format!("{}.{}.{}", major, minor, patch))
+}
+
+/// Where the server binary should be: BeamMP-Server.exe (Windows) or BeamMP-Server (Linux/Mac)
+pub fn server_executable(server_path: &Path) -> PathBuf {
+    let exe_name = if cfg!(windows) {
+        "BeamMP-Server.exe"
+    } else {
+        "BeamMP-Server"
+    };
+    server_path.join(exe_name)
+}
+
+// Leaves the server's Lua console
+const LUA_EXIT: &str = "exit()";
+
+/// Lua that sends `message` to the chat of the player named `player` only. BeamMP has no
+/// console command for this, so it goes through the Lua console.
+pub fn private_message_lua(player: &str, message: &str) -> String {
+    format!(
+        "for id, name in pairs(MP.GetPlayers()) do if name == {} then MP.SendChatMessage(id, {}) end end",
+        lua_string(player),
+        lua_string(message)
+    )
+}
+
+/// `text` as a double-quoted Lua string literal on a single line
+fn lua_string(text: &str) -> String {
+    let mut out = String::with_capacity(text.len() + 2);
+    out.push('"');
+    for c in text.chars() {
+        match c {
+            '\\' => out.push_str("\\\\"),
+            '"' => out.push_str("\\\""),
+            '\n' => out.push_str("\\n"),
+            '\r' => {}
+            '\t' => out.push_str("\\t"),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+// How long `--version` may take before the binary is assumed not to support it
+const VERSION_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Version reported by the server binary's `--version`, e.g. "3.4.1". It runs in an empty
+/// temporary folder, so a build that ignores the flag and starts up anyway touches nothing
+/// of the server's and is killed after a few seconds.
+pub fn server_version(server_path: &Path) -> Result<String> {
+    let exe_path = server_executable(server_path);
+    if !exe_path.exists() {
+        return Err(anyhow!("BeamMP server executable not found: {}", exe_path.display()));
+    }
+    if let Some(reason) = platform_mismatch(&exe_path) {
+        return Err(anyhow!(reason));
+    }
+
+    let dir = std::env::temp_dir().join(format!("beammp-panel-version-{}", uuid::Uuid::new_v4().simple()));
+    std::fs::create_dir_all(&dir)?;
+    let mut command = Command::new(&exe_path);
+    command
+        .arg("--version")
+        .current_dir(&dir)
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::null());
+    #[cfg(windows)]
+    {
+        const CREATE_NO_WINDOW: u32 = 0x08000000;
+        command.creation_flags(CREATE_NO_WINDOW);
+    }
+    let result = run_for_version(command);
+    let _ = std::fs::remove_dir_all(&dir);
+    result
+}
+
+fn run_for_version(mut command: Command) -> Result<String> {
+    let mut child = command.spawn()?;
+    let stdout = child.stdout.take().ok_or_else(|| anyhow!("Failed to capture stdout"))?;
+    let (tx, rx) = sync_channel(100);
+    thread::spawn(move || {
+        for line in BufReader::new(stdout).lines().map_while(Result::ok) {
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+
+    let deadline = Instant::now() + VERSION_TIMEOUT;
+    let mut version = None;
+    while version.is_none() {
+        let remaining = deadline.saturating_duration_since(Instant::now());
+        match rx.recv_timeout(remaining) {
+            Ok(line) => version = crate::updates::parse_version(&line),
+            Err(_) => break,
+        }
+    }
+    if child.try_wait().ok().flatten().is_none() {
+        let _ = child.kill();
+    }
+    let _ = child.wait();
+
+    let (major, minor, patch) = version.ok_or_else(|| anyhow!("The server binary didn't report its version"))?;
+    Ok(format!("{}.{}.{}", major, minor, patch))
+}
+
+// CPU usage is measured between two samples, so sampling much faster only adds noise
+const USAGE_SAMPLE_INTERVAL: Duration = Duration::from_secs(1);
+/// Samples kept for the usage graph, one per USAGE_SAMPLE_INTERVAL
+pub const USAGE_HISTORY_LEN: usize = 60;
+
+#[derive(Debug, Clone, Copy)]
+pub struct ResourceUsage {
+    /// Share of the whole machine (all cores), 0-100
+    pub cpu_percent: f32,
+    /// Resident memory in bytes
+    pub memory_bytes: u64,
+}
+
+/// Lines of output held until the UI reads them. The readers never wait for the UI, so
+/// a log flood or a window hidden in the tray can't fill the pipes and stall the server.
+/// Past this the oldest lines are dropped, and the next read says how many.
+pub const OUTPUT_BUFFER_LINES: usize = 20_000;
+
+// A process the server started may keep the pipes open after it exits
+const OUTPUT_DRAIN_TIMEOUT: Duration = Duration::from_millis(500);
+
+/// Output shared by the stdout and stderr readers. Both append under one lock as they
+/// read, so lines of the two streams stay interleaved in the order they arrived.
+#[derive(Default)]
+struct OutputBuffer {
+    lines: VecDeque<String>,
+    /// Lines dropped since the last read because the buffer was full
+    dropped: usize,
+}
+
+impl OutputBuffer {
+    fn push(&mut self, line: String) {
+        if self.lines.len() >= OUTPUT_BUFFER_LINES {
+            self.lines.pop_front();
+            self.dropped += 1;
+        }
+        self.lines.push_back(line);
+    }
+}
+
+/// Read one output stream into the shared buffer, each line prefixed with `prefix`.
+///
+/// stdout and stderr are separate pipes, so their exact emission order is lost once the
+/// server writes to both; what the console shows is the order the panel read them in.
+/// Each stream always keeps its own order, and all complete lines from one read go in
+/// together, so a line of the other stream can't land between lines written before it.
+fn spawn_reader(mut stream: impl Read + Send + 'static, output: Arc<Mutex<OutputBuffer>>, prefix: &'static str) -> thread::JoinHandle<()> {
+    thread::spawn(move || {
+        let mut buffer = vec![0u8; 64 * 1024];
+        let mut pending = Vec::new();
+        loop {
+            let read = match stream.read(&mut buffer) {
+                Ok(0) => break,
+                Ok(read) => read,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(_) => break,
+            };
+            pending.extend_from_slice(&buffer[..read]);
+            let Some(end) = pending.iter().rposition(|&b| b == b'\n') else {
+                continue;
+            };
+            let complete: Vec<u8> = pending.drain(..=end).collect();
+            let Ok(mut output) = output.lock() else {
+                return;
+            };
+            for line in complete[..end].split(|&b| b == b'\n') {
+                output.push(output_line(prefix, line));
+            }
+        }
+        // The last line may have no newline
+        if !pending.is_empty() {
+            if let Ok(mut output) = output.lock() {
+                output.push(output_line(prefix, &pending));
+            }
+        }
+    })
+}
+
+/// A line of output as text; bytes that aren't UTF-8 are replaced instead of ending the output
+fn output_line(prefix: &str, line: &[u8]) -> String {
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    format!("{}{}", prefix, String::from_utf8_lossy(line))
+}
+
 pub struct ServerProcess {
     child: Child,
-    output_receiver: Receiver<String>,
+    output: Arc<Mutex<OutputBuffer>>,
     stdin: Arc<Mutex<ChildStdin>>,
-    _output_thread: thread::JoinHandle<()>,
+    readers: [thread::JoinHandle<()>; 2],
+    system: sysinfo::System,
+    last_usage_sample: Option<Instant>,
+    usage_history: VecDeque<ResourceUsage>,
+    /// The console was switched to Lua with `lua`, so input is run as Lua until `exit()`
+    lua_console: AtomicBool,
 }
 
 impl ServerProcess {
     pub fn start(server_path: &Path) -> Result<Self> {
-        // Look for BeamMP-Server.exe (Windows) or BeamMP-Server (Linux/Mac)
-        let exe_name = if cfg!(windows) {
-            "BeamMP-Server.exe"
-        } else {
-            "BeamMP-Server"
-        };
-
-        let exe_path = server_path.join(exe_name);
+        let exe_path = server_executable(server_path);
         if !exe_path.exists() {
             return Err(anyhow!("BeamMP server executable not found: {}", exe_path.display()));
         }
 
+        if let Some(reason) = platform_mismatch(&exe_path) {
+            return Err(anyhow!(reason));
+        }
+
         let mut command = Command::new(&exe_path);
         command
             .current_dir(server_path)
@@ -44,7 +393,13 @@ impl ServerProcess {
             command.creation_flags(CREATE_NO_WINDOW);
         }
 
-        let mut child = command.spawn()?;
+        let mut child = command.spawn().map_err(|e| {
+            if is_exec_format_error(&e) {
+                anyhow!("The server binary is for a different platform or architecture and can't run on this machine")
+            } else {
+                anyhow!(e)
+            }
+        })?;
 
         let stdout = child.stdout.take().ok_or_else(|| anyhow!("Failed to capture stdout"))?;
         let stderr = child.stderr.take().ok_or_else(|| anyhow!("Failed to capture stderr"))?;
@@ -52,54 +407,92 @@ impl ServerProcess {
             child.stdin.take().ok_or_else(|| anyhow!("Failed to capture stdin"))?
         ));
 
-        // Use bounded channel to prevent unbounded memory growth
-        let (tx, rx) = sync_channel(1000);
-
-        // Spawn thread to read stdout
-        let tx_clone = tx.clone();
-        thread::spawn(move || {
-            let reader = BufReader::new(stdout);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    let _ = tx_clone.send(line);
-                }
-            }
-        });
-
-        // Spawn thread to read stderr
-        let tx_clone = tx.clone();
-        let output_thread = thread::spawn(move || {
-            let reader = BufReader::new(stderr);
-            for line in reader.lines() {
-                if let Ok(line) = line {
-                    let _ = tx_clone.send(format!("[ERROR] {}", line));
-                }
-            }
-        });
+        // Bounded by OUTPUT_BUFFER_LINES, see there
+        let output = Arc::new(Mutex::new(OutputBuffer::default()));
+        let readers = [
+            spawn_reader(stdout, output.clone(), ""),
+            spawn_reader(stderr, output.clone(), "[ERROR] "),
+        ];
 
         Ok(Self {
             child,
-            output_receiver: rx,
+            output,
             stdin,
-            _output_thread: output_thread,
+            readers,
+            system: sysinfo::System::new(),
+            last_usage_sample: None,
+            usage_history: VecDeque::with_capacity(USAGE_HISTORY_LEN),
+            lua_console: AtomicBool::new(false),
         })
     }
 
-    pub fn send_command(&self, command: &str) -> Result<()> {
+    fn write_lines(&self, lines: &[&str]) -> Result<()> {
         let mut stdin = self.stdin.lock().map_err(|e| anyhow!("Failed to lock stdin: {}", e))?;
-        writeln!(stdin, "{}", command)?;
+        for line in lines {
+            writeln!(stdin, "{}", line)?;
+        }
         stdin.flush()?;
         Ok(())
     }
 
-    pub fn stop(&mut self) -> Result<()> {
-        // Try graceful shutdown first
-        let _ = self.send_command("exit");
-        
-        // Wait a bit for graceful shutdown
-        std::thread::sleep(std::time::Duration::from_millis(500));
-        
-        // Force kill if still running
+    /// Send a server console command. If the console is in Lua mode it is left first, so
+    /// the command isn't run as Lua.
+    pub fn send_command(&self, command: &str) -> Result<()> {
+        if self.lua_console.swap(false, Ordering::Relaxed) {
+            self.write_lines(&[LUA_EXIT, command])
+        } else {
+            self.write_lines(&[command])
+        }
+    }
+
+    /// Send a line typed by the user as is, following it in and out of Lua mode
+    pub fn send_input(&self, line: &str) -> Result<()> {
+        self.write_lines(&[line])?;
+        let line = line.trim();
+        if self.lua_console.load(Ordering::Relaxed) {
+            if line == LUA_EXIT {
+                self.lua_console.store(false, Ordering::Relaxed);
+            }
+        } else if line == "lua" || line.starts_with("lua ") {
+            self.lua_console.store(true, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    /// Run Lua code in a state of the server's Lua console (a plugin name, or the default
+    /// state if empty), returning to the normal console afterwards
+    pub fn run_lua(&self, state: &str, code: &str) -> Result<()> {
+        let enter = match state.trim() {
+            "" => "lua".to_string(),
+            state => format!("lua {}", state),
+        };
+        let code = code.trim();
+        // The console runs each line on its own, so longer code is passed to `load` as one string
+        let chunk = if code.lines().count() > 1 {
+            format!("local f, e = load({}) if f then f() else print(e) end", lua_string(code))
+        } else {
+            code.to_string()
+        };
+
+        if self.lua_console.swap(false, Ordering::Relaxed) {
+            self.write_lines(&[LUA_EXIT, &enter, &chunk, LUA_EXIT])
+        } else {
+            self.write_lines(&[&enter, &chunk, LUA_EXIT])
+        }
+    }
+
+    pub fn in_lua_console(&self) -> bool {
+        self.lua_console.load(Ordering::Relaxed)
+    }
+
+    /// Ask the server to shut down. Returns immediately; the caller watches
+    /// `exit_status` and falls back to `kill` if the server takes too long.
+    pub fn request_stop(&self) -> Result<()> {
+        self.send_command("exit")
+    }
+
+    pub fn kill(&mut self) -> Result<()> {
+        // The process may have exited between the last check and now
         if self.is_running() {
             self.child.kill()?;
         }
@@ -107,16 +500,311 @@ impl ServerProcess {
         Ok(())
     }
 
+    pub fn pid(&self) -> u32 {
+        self.child.id()
+    }
+
+    /// Record CPU and memory usage if the last sample is old enough. Call every frame.
+    pub fn sample_usage(&mut self) {
+        if self.last_usage_sample.is_some_and(|t| t.elapsed() < USAGE_SAMPLE_INTERVAL) {
+            return;
+        }
+        self.last_usage_sample = Some(Instant::now());
+
+        let pid = sysinfo::Pid::from_u32(self.child.id());
+        self.system.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::Some(&[pid]),
+            true,
+            sysinfo::ProcessRefreshKind::new().with_cpu().with_memory(),
+        );
+        // Gone between samples: keep the last values until the exit is noticed
+        let Some(process) = self.system.process(pid) else {
+            return;
+        };
+
+        let cores = thread::available_parallelism().map_or(1, |n| n.get());
+        if self.usage_history.len() == USAGE_HISTORY_LEN {
+            self.usage_history.pop_front();
+        }
+        self.usage_history.push_back(ResourceUsage {
+            cpu_percent: process.cpu_usage() / cores as f32,
+            memory_bytes: process.memory(),
+        });
+    }
+
+    /// Samples from oldest to newest. The first CPU value is always 0, as CPU usage
+    /// needs two samples to measure.
+    pub fn usage_history(&self) -> &VecDeque<ResourceUsage> {
+        &self.usage_history
+    }
+
     pub fn is_running(&mut self) -> bool {
         self.child.try_wait().ok().flatten().is_none()
     }
 
-    pub fn read_output(&self) -> Vec<String> {
+    /// Exit status of the child, or None while it is still running
+    pub fn exit_status(&mut self) -> Option<ExitStatus> {
+        self.child.try_wait().ok().flatten()
+    }
+
+    /// Drain up to `max_lines` pending lines. Anything beyond that stays queued
+    /// for the next call so a log flood can't stall a single frame.
+    pub fn read_output(&self, max_lines: usize) -> Vec<String> {
+        let Ok(mut output) = self.output.lock() else {
+            return Vec::new();
+        };
         let mut lines = Vec::new();
-        while let Ok(line) = self.output_receiver.try_recv() {
-            lines.push(line);
+        if output.dropped > 0 && max_lines > 0 {
+            lines.push(format!(
+                "[PANEL] {} lines of output were dropped because the console couldn't keep up",
+                output.dropped
+            ));
+            output.dropped = 0;
         }
+        let count = output.lines.len().min(max_lines - lines.len());
+        lines.extend(output.lines.drain(..count));
         lines
     }
+
+    /// All output left once the process has exited. Waits briefly for the readers to reach
+    /// the end of the pipes, so the last lines before a crash aren't lost.
+    pub fn read_remaining_output(&self) -> Vec<String> {
+        let deadline = Instant::now() + OUTPUT_DRAIN_TIMEOUT;
+        while !self.readers.iter().all(|reader| reader.is_finished()) && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        self.read_output(usize::MAX)
+    }
+}
+
+/// How long servers still running when the panel exits get to shut down before they are killed
+pub const EXIT_STOP_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Shut down every server the panel started, so none outlives it and keeps holding its
+/// port: ask each to exit (unless `stop_requested` says it already was), wait up to
+/// EXIT_STOP_TIMEOUT, then kill what is left. Returns, per process, whether it was killed.
+pub fn stop_all_on_exit<'a>(processes: impl IntoIterator<Item = (&'a mut ServerProcess, bool)>) -> Vec<bool> {
+    let mut processes: Vec<&mut ServerProcess> = processes
+        .into_iter()
+        .map(|(process, stop_requested)| {
+            if !stop_requested {
+                let _ = process.request_stop();
+            }
+            process
+        })
+        .collect();
+
+    let deadline = Instant::now() + EXIT_STOP_TIMEOUT;
+    while Instant::now() < deadline && processes.iter_mut().any(|p| p.is_running()) {
+        thread::sleep(Duration::from_millis(50));
+    }
+
+    processes
+        .into_iter()
+        .map(|process| {
+            let killed = process.is_running();
+            if killed {
+                let _ = process.kill();
+            }
+            killed
+        })
+        .collect()
+}
+
+/// A BeamMP-Server the panel didn't start, e.g. one launched from a terminal. It can only
+/// be watched: its stdin and output belong to whoever started it.
+pub struct ExternalProcess {
+    pid: sysinfo::Pid,
+    system: sysinfo::System,
+    last_check: Instant,
+    alive: bool,
+}
+
+impl ExternalProcess {
+    /// Look for a BeamMP-Server running from `server_path`, by executable path or working
+    /// directory. `exclude` lists the PIDs of servers the panel started itself.
+    pub fn find(server_path: &Path, exclude: &[u32]) -> Option<Self> {
+        let mut system = sysinfo::System::new();
+        system.refresh_processes_specifics(
+            sysinfo::ProcessesToUpdate::All,
+            true,
+            sysinfo::ProcessRefreshKind::new()
+                .with_exe(sysinfo::UpdateKind::OnlyIfNotSet)
+                .with_cwd(sysinfo::UpdateKind::OnlyIfNotSet),
+        );
+
+        let exe_path = server_executable(server_path);
+        let exe_name = exe_path.file_name()?.to_owned();
+        let pid = system
+            .processes()
+            .values()
+            // Linux lists every thread as a process too
+            .filter(|p| p.thread_kind().is_none() && !exclude.contains(&p.pid().as_u32()))
+            .find(|p| {
+                p.exe().is_some_and(|exe| same_path(exe, &exe_path))
+                    || (p.name() == exe_name && p.cwd().is_some_and(|cwd| same_path(cwd, server_path)))
+            })?
+            .pid();
+
+        Some(Self {
+            pid,
+            system,
+            last_check: Instant::now(),
+            alive: true,
+        })
+    }
+
+    pub fn pid(&self) -> u32 {
+        self.pid.as_u32()
+    }
+
+    /// Whether the process still exists. Checked at most once per second; call every frame.
+    pub fn is_running(&mut self) -> bool {
+        if self.alive && self.last_check.elapsed() >= USAGE_SAMPLE_INTERVAL {
+            self.last_check = Instant::now();
+            self.system.refresh_processes_specifics(
+                sysinfo::ProcessesToUpdate::Some(&[self.pid]),
+                true,
+                sysinfo::ProcessRefreshKind::new(),
+            );
+            self.alive = self.system.process(self.pid).is_some();
+        }
+        self.alive
+    }
+}
+
+fn same_path(a: &Path, b: &Path) -> bool {
+    match (a.canonicalize(), b.canonicalize()) {
+        (Ok(a), Ok(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+
+/// Open a file or folder with the system's default handler (file manager, text editor, ...)
+pub fn open_path(path: &Path) -> Result<()> {
+    #[cfg(windows)]
+    let mut command = Command::new("explorer");
+    #[cfg(target_os = "macos")]
+    let mut command = Command::new("open");
+    #[cfg(all(unix, not(target_os = "macos")))]
+    let mut command = Command::new("xdg-open");
+
+    command.arg(path).spawn()?;
+    Ok(())
+}
+
+/// Launch the BeamMP client, substituting `{ip}` and `{port}` in the argument template
+pub fn launch_client(client_path: &Path, args_template: &str, ip: &str, port: u16) -> Result<()> {
+    if !client_path.exists() {
+        return Err(anyhow!("BeamMP launcher not found: {}", client_path.display()));
+    }
+
+    let args: Vec<String> = args_template
+        .split_whitespace()
+        .map(|arg| arg.replace("{ip}", ip).replace("{port}", &port.to_string()))
+        .collect();
+
+    let mut command = Command::new(client_path);
+    command.args(args);
+    if let Some(dir) = client_path.parent() {
+        command.current_dir(dir);
+    }
+    command.spawn()?;
+    Ok(())
 }
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc::{channel, Receiver, Sender};
+
+    /// A pipe the test writes into chunk by chunk, read like a child's stdout
+    struct ChunkReader {
+        chunks: Receiver<Vec<u8>>,
+        current: Vec<u8>,
+    }
+
+    impl Read for ChunkReader {
+        fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+            if self.current.is_empty() {
+                match self.chunks.recv() {
+                    Ok(chunk) => self.current = chunk,
+                    Err(_) => return Ok(0),
+                }
+            }
+            let count = self.current.len().min(buf.len());
+            buf[..count].copy_from_slice(&self.current[..count]);
+            self.current.drain(..count);
+            Ok(count)
+        }
+    }
+
+    fn pipe() -> (Sender<Vec<u8>>, ChunkReader) {
+        let (tx, rx) = channel();
+        (tx, ChunkReader { chunks: rx, current: Vec::new() })
+    }
+
+    fn wait_for_lines(output: &Mutex<OutputBuffer>, count: usize) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while output.lock().unwrap().lines.len() < count {
+            assert!(Instant::now() < deadline, "expected {} lines", count);
+            thread::sleep(Duration::from_millis(1));
+        }
+    }
+
+    #[test]
+    fn interleaves_streams_in_arrival_order() {
+        let output = Arc::new(Mutex::new(OutputBuffer::default()));
+        let (out, out_reader) = pipe();
+        let (err, err_reader) = pipe();
+        let readers = [
+            spawn_reader(out_reader, output.clone(), ""),
+            spawn_reader(err_reader, output.clone(), "[ERROR] "),
+        ];
+
+        // Each chunk is read before the next is written, fixing the arrival order
+        let send = |stream: &Sender<Vec<u8>>, chunk: &[u8], lines_after: usize| {
+            stream.send(chunk.to_vec()).unwrap();
+            wait_for_lines(&output, lines_after);
+        };
+        send(&out, b"first\n", 1);
+        send(&err, b"second\n", 2);
+        send(&out, b"third\r\nfourth\n", 4);
+        // Held back until its newline arrives, so the next stdout line goes first
+        send(&err, b"fifth \xff", 4);
+        send(&out, b"sixth\n", 5);
+        send(&err, b"line\n", 6);
+        out.send(b"no newline".to_vec()).unwrap();
+        drop((out, err));
+        for reader in readers {
+            reader.join().unwrap();
+        }
+
+        let lines: Vec<String> = output.lock().unwrap().lines.iter().cloned().collect();
+        assert_eq!(
+            lines,
+            [
+                "first",
+                "[ERROR] second",
+                "third",
+                "fourth",
+                "sixth",
+                "[ERROR] fifth \u{FFFD}line",
+                "no newline",
+            ]
+        );
+    }
+
+    #[test]
+    fn drops_oldest_lines_past_the_cap() {
+        let mut output = OutputBuffer::default();
+        for i in 0..OUTPUT_BUFFER_LINES + 5 {
+            output.push(i.to_string());
+        }
+        assert_eq!(output.lines.len(), OUTPUT_BUFFER_LINES);
+        assert_eq!(output.dropped, 5);
+        assert_eq!(output.lines.front().map(String::as_str), Some("5"));
+        assert_eq!(output.lines.back(), Some(&(OUTPUT_BUFFER_LINES + 4).to_string()));
+    }
+}
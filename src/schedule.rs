@@ -0,0 +1,90 @@
+use chrono::{DateTime, Days, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+
+/// Seconds before a restart at which players are reminded, unless changed in Settings
+pub const DEFAULT_COUNTDOWN: [u32; 4] = [60, 30, 10, 5];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RestartSchedule {
+    /// Every day at this local time
+    Daily { hour: u32, minute: u32 },
+    /// This many hours after the server was started
+    Interval { hours: u32 },
+}
+
+/// Automatic restarts of one server, persisted with its entry in servers.json. The
+/// schedule is kept while disabled so turning it back on restores it.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RestartSettings {
+    pub enabled: bool,
+    pub schedule: RestartSchedule,
+    /// How long before the restart players are first warned with `say`; 0 restarts without warning
+    pub warning_minutes: u32,
+}
+
+impl Default for RestartSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            schedule: RestartSchedule::Daily { hour: 4, minute: 0 },
+            warning_minutes: 5,
+        }
+    }
+}
+
+impl RestartSettings {
+    /// First scheduled restart after `since` (the start of the run, or the last time the
+    /// schedule was changed or skipped). None if disabled.
+    pub fn next_restart(&self, since: DateTime<Local>) -> Option<DateTime<Local>> {
+        if !self.enabled {
+            return None;
+        }
+        match self.schedule {
+            RestartSchedule::Daily { hour, minute } => {
+                let time = NaiveTime::from_hms_opt(hour, minute, 0)?;
+                // Today or tomorrow, or the day after if a DST change skips that time
+                (0..3).find_map(|days| {
+                    let date = since.date_naive().checked_add_days(Days::new(days))?;
+                    Local
+                        .from_local_datetime(&date.and_time(time))
+                        .earliest()
+                        .filter(|t| *t > since)
+                })
+            }
+            RestartSchedule::Interval { hours } => Some(since + chrono::Duration::hours(hours.max(1) as i64)),
+        }
+    }
+
+    /// Seconds-before-restart at which a message is sent: the first warning, then the
+    /// shorter `countdown` marks. Longest first.
+    pub fn warning_points(&self, countdown: &[u32]) -> Vec<i64> {
+        let first = self.warning_minutes as i64 * 60;
+        if first == 0 {
+            return Vec::new();
+        }
+        let mut points = countdown_points(countdown);
+        points.retain(|&secs| secs < first);
+        points.insert(0, first);
+        points
+    }
+}
+
+/// Countdown marks from Settings, longest first, without zeros or repeats
+pub fn countdown_points(countdown: &[u32]) -> Vec<i64> {
+    let mut points: Vec<i64> = countdown.iter().filter(|&&secs| secs > 0).map(|&secs| secs as i64).collect();
+    points.sort_unstable_by(|a, b| b.cmp(a));
+    points.dedup();
+    points
+}
+
+/// "5 minutes", "30 seconds" for a countdown message
+pub fn format_countdown(secs: i64) -> String {
+    if secs >= 60 {
+        // Round up so 299 seconds left is still announced as 5 minutes
+        let minutes = (secs + 59) / 60;
+        format!("{} minute{}", minutes, if minutes == 1 { "" } else { "s" })
+    } else {
+        format!("{} second{}", secs, if secs == 1 { "" } else { "s" })
+    }
+}
@@ -1,20 +1,96 @@
 use crate::config::ServerConfig;
+use crate::process;
+use crate::schedule::RestartSettings;
 use anyhow::{anyhow, Result};
 use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerEntry {
     pub id: String,
     pub name: String,
+    /// Nickname shown in the panel instead of `name`; never written to ServerConfig.toml
+    #[serde(default)]
+    pub custom_name: Option<String>,
     pub path: PathBuf,
+    /// Unix timestamp of the last start from the panel
+    #[serde(default)]
+    pub last_started: Option<i64>,
+    /// Accumulated runtime of all finished runs, in seconds
+    #[serde(default)]
+    pub total_runtime_secs: u64,
+    #[serde(default)]
+    pub restart: RestartSettings,
+    /// Custom commands shown as buttons in the Control tab
+    #[serde(default)]
+    pub macros: Vec<CommandMacro>,
+    /// Sender label put before broadcasts from the Control tab, like `[Admin]`; empty for none
+    #[serde(default)]
+    pub broadcast_prefix: String,
+    /// BeamMP-Server version the binary last reported, from its startup banner or `--version`
+    #[serde(default)]
+    pub server_version: Option<String>,
     #[serde(skip)]
     pub loaded_config: Option<ServerConfig>,
     #[serde(skip)]
     pub edited_config: Option<ServerConfig>,
     #[serde(skip)]
     pub config_error: Option<String>,
+    /// Where ServerConfig.toml failed to parse, when the file could be read
+    #[serde(skip)]
+    pub parse_error: Option<ConfigParseError>,
+    /// The folder has a config but no server binary (yet); starting will fail until it does
+    #[serde(skip)]
+    pub missing_executable: bool,
+    /// The server folder itself is gone: deleted, moved or on a drive that isn't connected
+    #[serde(skip)]
+    pub folder_missing: bool,
+}
+
+/// A console command with a button label, e.g. a plugin's own command
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CommandMacro {
+    pub label: String,
+    pub command: String,
+}
+
+#[derive(Debug, Clone)]
+pub struct ConfigParseError {
+    /// 1-based line and column of the error, if the parser reported a position
+    pub location: Option<(usize, usize)>,
+    /// The file as read, for showing the error in context
+    pub contents: String,
+}
+
+impl ConfigParseError {
+    fn new(error: &toml::de::Error, contents: String) -> Self {
+        let location = error.span().and_then(|span| {
+            let before = contents.get(..span.start)?;
+            let line = before.matches('\n').count() + 1;
+            let column = before.rsplit('\n').next().unwrap_or_default().chars().count() + 1;
+            Some((line, column))
+        });
+        Self { location, contents }
+    }
+}
+
+/// Folder next to ServerConfig.toml holding copies made before each save
+const CONFIG_BACKUP_DIR: &str = ".backups";
+const MAX_CONFIG_BACKUPS: usize = 10;
+
+pub struct ConfigBackup {
+    pub path: PathBuf,
+    /// Unix timestamp of when the backup was made
+    pub created: i64,
+}
+
+/// A server that was added, possibly with a problem the user should fix before starting it
+pub struct AddedServer {
+    pub name: String,
+    pub missing_executable: bool,
 }
 
 impl ServerEntry {
@@ -34,10 +110,20 @@ impl ServerEntry {
                 .and_then(|n| n.to_str())
                 .unwrap_or("Unknown")
                 .to_string(),
+            custom_name: None,
             path,
+            last_started: None,
+            total_runtime_secs: 0,
+            restart: RestartSettings::default(),
+            macros: Vec::new(),
+            broadcast_prefix: String::new(),
+            server_version: None,
             loaded_config: None,
             edited_config: None,
             config_error: None,
+            parse_error: None,
+            missing_executable: false,
+            folder_missing: false,
         };
 
         entry.load_config();
@@ -53,6 +139,8 @@ impl ServerEntry {
     }
 
     pub fn load_config(&mut self) {
+        self.folder_missing = !self.path.is_dir();
+        self.check_executable();
         let config_path = self.path.join("ServerConfig.toml");
         match fs::read_to_string(&config_path) {
             Ok(contents) => match toml::from_str::<ServerConfig>(&contents) {
@@ -60,25 +148,54 @@ impl ServerEntry {
                     self.loaded_config = Some(config.clone());
                     self.edited_config = Some(config);
                     self.config_error = None;
+                    self.parse_error = None;
                 }
                 Err(e) => {
-                    self.config_error = Some(format!("Parse error: {}", e));
+                    self.config_error = Some(format!("Parse error: {}", e.message()));
+                    self.parse_error = Some(ConfigParseError::new(&e, contents));
                     self.loaded_config = None;
                     self.edited_config = None;
                 }
             },
             Err(e) => {
                 self.config_error = Some(format!("Failed to read config: {}", e));
+                self.parse_error = None;
                 self.loaded_config = None;
                 self.edited_config = None;
             }
         }
     }
 
+    /// Name to show in the panel: the nickname if set, otherwise the config or folder name
+    pub fn display_name(&self) -> &str {
+        self.custom_name
+            .as_deref()
+            .filter(|name| !name.trim().is_empty())
+            .unwrap_or(&self.name)
+    }
+
+    /// Point the entry at the server's new folder, e.g. after it was moved
+    pub fn relocate(&mut self, path: PathBuf) -> Result<()> {
+        if !path.join("ServerConfig.toml").exists() {
+            return Err(anyhow!("ServerConfig.toml not found in the selected folder"));
+        }
+        self.path = path;
+        self.load_config();
+        Ok(())
+    }
+
+    pub fn check_executable(&mut self) {
+        self.missing_executable = !process::server_executable(&self.path).exists();
+    }
+
     pub fn save_config(&mut self) -> Result<()> {
         if let Some(config) = &self.edited_config {
             let config_str = toml::to_string_pretty(config)?;
             let config_path = self.path.join("ServerConfig.toml");
+
+            // Never overwrite the working config without a way back
+            self.backup_config()
+                .map_err(|e| anyhow!("Could not back up the current config, nothing was saved: {}", e))?;
             
             // Use buffered I/O for better performance
             use std::io::BufWriter;
@@ -95,6 +212,82 @@ impl ServerEntry {
         }
     }
 
+    /// ServerConfig.toml exactly as it is on disk
+    pub fn read_raw_config(&self) -> Result<String> {
+        Ok(fs::read_to_string(self.path.join("ServerConfig.toml"))?)
+    }
+
+    /// Write `contents` as ServerConfig.toml verbatim, after a backup, and load it again.
+    /// Text that doesn't parse is written anyway and shows up as a config error.
+    pub fn save_raw_config(&mut self, contents: &str) -> Result<()> {
+        self.backup_config()
+            .map_err(|e| anyhow!("Could not back up the current config, nothing was saved: {}", e))?;
+        fs::write(self.path.join("ServerConfig.toml"), contents)?;
+        self.load_config();
+        Ok(())
+    }
+
+    fn backup_dir(&self) -> PathBuf {
+        self.path.join(CONFIG_BACKUP_DIR)
+    }
+
+    /// Copy the current ServerConfig.toml into the backup folder, dropping the oldest
+    /// backups beyond MAX_CONFIG_BACKUPS. Does nothing if there is no config file yet.
+    pub fn backup_config(&self) -> Result<()> {
+        let config_path = self.path.join("ServerConfig.toml");
+        if !config_path.exists() {
+            return Ok(());
+        }
+
+        let dir = self.backup_dir();
+        fs::create_dir_all(&dir)?;
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S-%3f");
+        fs::copy(&config_path, dir.join(format!("ServerConfig-{}.toml", stamp)))?;
+
+        for old in self.config_backups().iter().skip(MAX_CONFIG_BACKUPS) {
+            let _ = fs::remove_file(&old.path);
+        }
+        Ok(())
+    }
+
+    /// Backups of this server's config, newest first
+    pub fn config_backups(&self) -> Vec<ConfigBackup> {
+        let Ok(entries) = fs::read_dir(self.backup_dir()) else {
+            return Vec::new();
+        };
+        let mut backups: Vec<ConfigBackup> = entries
+            .map_while(Result::ok)
+            .map(|e| e.path())
+            .filter(|p| p.extension().is_some_and(|ext| ext == "toml"))
+            .filter_map(|path| {
+                // Read the time from the name; copies don't reliably get a fresh mtime
+                let stamp = path.file_stem()?.to_str()?.strip_prefix("ServerConfig-")?.get(..15)?;
+                let created = chrono::NaiveDateTime::parse_from_str(stamp, "%Y%m%d-%H%M%S")
+                    .ok()?
+                    .and_local_timezone(chrono::Local)
+                    .earliest()?
+                    .timestamp();
+                Some(ConfigBackup { path, created })
+            })
+            .collect();
+        // File names carry the timestamp, so they sort chronologically
+        backups.sort_by(|a, b| b.path.cmp(&a.path));
+        backups
+    }
+
+    /// Replace ServerConfig.toml with a backup and reload it. The current config is
+    /// backed up first, so a restore can itself be undone.
+    pub fn restore_config_backup(&mut self, backup: &Path) -> Result<()> {
+        let contents = fs::read_to_string(backup)?;
+        toml::from_str::<ServerConfig>(&contents)
+            .map_err(|e| anyhow!("The backup is not a valid config: {}", e))?;
+
+        self.backup_config()?;
+        fs::write(self.path.join("ServerConfig.toml"), contents)?;
+        self.load_config();
+        Ok(())
+    }
+
     pub fn revert_config(&mut self) {
         if let Some(original) = &self.loaded_config {
             self.edited_config = Some(original.clone());
@@ -120,7 +313,10 @@ impl ServerEntry {
             loaded.general.description != edited.general.description ||
             loaded.general.resource_folder != edited.general.resource_folder ||
             loaded.misc.im_scared_of_updates != edited.misc.im_scared_of_updates ||
-            loaded.misc.update_reminder_time != edited.misc.update_reminder_time
+            loaded.misc.update_reminder_time != edited.misc.update_reminder_time ||
+            loaded.general.extra != edited.general.extra ||
+            loaded.misc.extra != edited.misc.extra ||
+            loaded.extra != edited.extra
         } else {
             false
         }
@@ -139,32 +335,363 @@ pub struct ServerList {
     pub servers: Vec<ServerEntry>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ImportMode {
+    /// Keep existing servers and append imported ones whose path isn't already listed
+    Merge,
+    /// Discard the current list and use the imported one
+    Replace,
+}
+
+pub struct ImportPreview {
+    pub entries: Vec<ServerEntry>,
+    pub new_count: usize,
+    pub matching_count: usize,
+}
+
+pub struct ImportSummary {
+    pub added: usize,
+    pub skipped: usize,
+    pub ids_regenerated: usize,
+}
+
+impl ImportSummary {
+    /// Status bar text for an import done with `mode`
+    pub fn describe(&self, mode: ImportMode) -> String {
+        let mut text = match mode {
+            ImportMode::Merge => format!("Imported {} server(s), skipped {} already in the list", self.added, self.skipped),
+            ImportMode::Replace => format!("Replaced server list with {} server(s)", self.added),
+        };
+        if self.ids_regenerated > 0 {
+            text.push_str(&format!(" ({} duplicate ID(s) regenerated)", self.ids_regenerated));
+        }
+        text
+    }
+}
+
+/// Space taken by a server folder, broken out by mod type
+#[derive(Debug, Clone, Default)]
+pub struct DiskUsage {
+    pub total: u64,
+    pub files: u64,
+    /// `<resource folder>/Client`
+    pub client_mods: u64,
+    /// `<resource folder>/Server`
+    pub server_mods: u64,
+    /// Mods moved to `<resource folder>_disabled`
+    pub disabled_mods: u64,
+    /// Unix timestamp of the calculation
+    pub calculated_at: i64,
+}
+
+/// Add up every file below a server folder. Slow on big folders, so run it off the UI
+/// thread. Unreadable entries are skipped and symlinks are not followed.
+pub fn disk_usage(path: &Path, resource_folder: &str) -> DiskUsage {
+    let client = path.join(resource_folder).join("Client");
+    let server = path.join(resource_folder).join("Server");
+    let disabled = path.join(format!("{}_disabled", resource_folder));
+
+    let mut usage = DiskUsage::default();
+    walk_files(path, &mut |file, size| {
+        usage.total += size;
+        usage.files += 1;
+        if file.starts_with(&client) {
+            usage.client_mods += size;
+        } else if file.starts_with(&server) {
+            usage.server_mods += size;
+        } else if file.starts_with(&disabled) {
+            usage.disabled_mods += size;
+        }
+    });
+    usage.calculated_at = chrono::Utc::now().timestamp();
+    usage
+}
+
+fn walk_files(dir: &Path, visit: &mut impl FnMut(&Path, u64)) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let Ok(metadata) = entry.metadata() else {
+            continue;
+        };
+        if metadata.is_dir() {
+            walk_files(&entry.path(), visit);
+        } else if metadata.is_file() {
+            visit(&entry.path(), metadata.len());
+        }
+    }
+}
+
+/// Start a server folder in `path`: write `config` as its ServerConfig.toml and create the
+/// resource folders. An existing ServerConfig.toml is never overwritten.
+pub fn create_server_folder(path: &Path, config: &ServerConfig) -> Result<()> {
+    fs::create_dir_all(path)?;
+    let config_path = path.join("ServerConfig.toml");
+    let file = fs::OpenOptions::new().write(true).create_new(true).open(&config_path).map_err(|e| match e.kind() {
+        std::io::ErrorKind::AlreadyExists => anyhow!("{} already exists", config_path.display()),
+        _ => anyhow!("Could not create {}: {}", config_path.display(), e),
+    })?;
+    use std::io::Write;
+    let mut writer = std::io::BufWriter::new(file);
+    writer.write_all(toml::to_string_pretty(config)?.as_bytes())?;
+    writer.flush()?;
+    crate::mods::create_resource_folder(path, &config.general.resource_folder)
+}
+
+/// Whether `port` can be bound on this machine right now. BeamMP listens on
+/// both TCP and UDP, so both have to be free.
+pub fn port_is_free(port: u16) -> bool {
+    std::net::TcpListener::bind(("0.0.0.0", port)).is_ok()
+        && std::net::UdpSocket::bind(("0.0.0.0", port)).is_ok()
+}
+
+/// Ports shared by more than one entry of `ports` (one per server, None if its config
+/// isn't loaded), each with the indices using it, ordered by port
+pub fn duplicate_ports(ports: &[Option<u16>]) -> Vec<(u16, Vec<usize>)> {
+    let mut by_port: std::collections::BTreeMap<u16, Vec<usize>> = std::collections::BTreeMap::new();
+    for (idx, port) in ports.iter().enumerate() {
+        if let Some(port) = port {
+            by_port.entry(*port).or_default().push(idx);
+        }
+    }
+    by_port.into_iter().filter(|(_, users)| users.len() > 1).collect()
+}
+
+/// Environment variable that points the panel at a different data directory
+pub const CONFIG_DIR_ENV: &str = "BEAMMP_PANEL_CONFIG_DIR";
+
+/// File in the default config directory naming the folder picked in the UI, so the choice
+/// survives a restart
+const CONFIG_DIR_POINTER: &str = "config-dir.txt";
+
+// Location picked in the UI for this session, e.g. when the default one is read-only
+static CONFIG_DIR_OVERRIDE: Mutex<Option<PathBuf>> = Mutex::new(None);
+
+/// Directory holding the panel's own state (server list, event history, ...): the folder
+/// picked in the UI this session, else `BEAMMP_PANEL_CONFIG_DIR`, else the folder a
+/// previous session picked, else the platform's config directory
+pub fn config_dir() -> Result<PathBuf> {
+    let overridden = CONFIG_DIR_OVERRIDE.lock().ok().and_then(|dir| dir.clone());
+    let config_dir = match overridden {
+        Some(dir) => dir,
+        None => match std::env::var_os(CONFIG_DIR_ENV) {
+            Some(dir) => PathBuf::from(dir),
+            None => {
+                let default = default_config_dir()?;
+                remembered_config_dir_in(&default).unwrap_or(default)
+            }
+        },
+    };
+
+    fs::create_dir_all(&config_dir)?;
+    Ok(config_dir)
+}
+
+fn default_config_dir() -> Result<PathBuf> {
+    Ok(directories::ProjectDirs::from("", "", "BeamMP-Panel")
+        .ok_or_else(|| anyhow!("Failed to determine config directory"))?
+        .config_dir()
+        .to_path_buf())
+}
+
+fn remembered_config_dir_in(default: &Path) -> Option<PathBuf> {
+    let contents = fs::read_to_string(default.join(CONFIG_DIR_POINTER)).ok()?;
+    let dir = contents.trim();
+    (!dir.is_empty()).then(|| PathBuf::from(dir))
+}
+
+/// The folder a previous `remember_config_dir` picked, if any
+pub fn remembered_config_dir() -> Option<PathBuf> {
+    remembered_config_dir_in(&default_config_dir().ok()?)
+}
+
+/// Keep using `dir` after a restart, by naming it in a file in the default config
+/// directory. Returns that file. Fails if the default directory can't be written, which
+/// is often why another folder was picked; the environment variable still works then.
+pub fn remember_config_dir(dir: &Path) -> Result<PathBuf> {
+    let default = default_config_dir()?;
+    fs::create_dir_all(&default)?;
+    let pointer = default.join(CONFIG_DIR_POINTER);
+    fs::write(&pointer, dir.display().to_string())?;
+    Ok(pointer)
+}
+
+/// Use `dir` for panel data for the rest of this session
+pub fn set_config_dir_override(dir: PathBuf) {
+    if let Ok(mut current) = CONFIG_DIR_OVERRIDE.lock() {
+        *current = Some(dir);
+    }
+}
+
+pub fn config_dir_is_overridden() -> bool {
+    CONFIG_DIR_OVERRIDE.lock().is_ok_and(|dir| dir.is_some())
+}
+
+/// Make sure panel data can actually be saved by writing a probe file
+pub fn check_config_dir_writable() -> Result<PathBuf> {
+    let dir = config_dir()?;
+    let probe = dir.join(".write_test");
+    fs::write(&probe, b"")?;
+    fs::remove_file(&probe)?;
+    Ok(dir)
+}
+
+/// Outcome of loading a servers.json that failed to parse
+pub struct ListRecovery {
+    pub error: String,
+    /// Copy of the unparseable file, if it could be written
+    pub backup_path: Option<PathBuf>,
+    pub recovered: usize,
+    pub dropped: usize,
+}
+
+/// Remove commas directly before a closing `}` or `]`, the most common hand-editing slip
+fn strip_trailing_commas(contents: &str) -> String {
+    let mut cleaned = String::with_capacity(contents.len());
+    let mut pending_comma = None;
+    let mut in_string = false;
+    let mut escaped = false;
+
+    for c in contents.chars() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            cleaned.push(c);
+            continue;
+        }
+
+        match c {
+            ',' => {
+                if let Some(whitespace) = pending_comma.replace(String::new()) {
+                    // Two commas in a row: keep the first one, it isn't trailing
+                    cleaned.push(',');
+                    cleaned.push_str(&whitespace);
+                }
+                continue;
+            }
+            c if c.is_whitespace() => {
+                if let Some(whitespace) = pending_comma.as_mut() {
+                    whitespace.push(c);
+                    continue;
+                }
+            }
+            '}' | ']' => {
+                if let Some(whitespace) = pending_comma.take() {
+                    cleaned.push_str(&whitespace);
+                }
+            }
+            _ => {
+                if let Some(whitespace) = pending_comma.take() {
+                    cleaned.push(',');
+                    cleaned.push_str(&whitespace);
+                }
+            }
+        }
+
+        if c == '"' {
+            in_string = true;
+        }
+        cleaned.push(c);
+    }
+
+    if let Some(whitespace) = pending_comma {
+        cleaned.push(',');
+        cleaned.push_str(&whitespace);
+    }
+    cleaned
+}
+
+/// Pull every parseable server entry out of a damaged servers.json. Entries are the
+/// objects nested directly inside the root object (in its `servers` array); each one is
+/// parsed on its own so a typo only costs the entry it is in.
+fn recover_entries(contents: &str) -> (Vec<ServerEntry>, usize) {
+    let mut servers = Vec::new();
+    let mut dropped = 0;
+
+    let mut starts = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for (pos, c) in contents.char_indices() {
+        if in_string {
+            match c {
+                _ if escaped => escaped = false,
+                '\\' => escaped = true,
+                '"' => in_string = false,
+                _ => {}
+            }
+            continue;
+        }
+
+        match c {
+            '"' => in_string = true,
+            '{' => starts.push(pos),
+            '}' => {
+                let Some(start) = starts.pop() else {
+                    continue;
+                };
+                // Depth 2: root object -> servers array -> entry
+                if starts.len() == 1 {
+                    match serde_json::from_str::<ServerEntry>(&contents[start..=pos]) {
+                        Ok(entry) => servers.push(entry),
+                        Err(_) => dropped += 1,
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (servers, dropped)
+}
+
 impl ServerList {
     fn get_config_path() -> Result<PathBuf> {
-        let config_dir = directories::ProjectDirs::from("", "", "BeamMP-Panel")
-            .ok_or_else(|| anyhow!("Failed to determine config directory"))?
-            .config_dir()
-            .to_path_buf();
-
-        fs::create_dir_all(&config_dir)?;
-        Ok(config_dir.join("servers.json"))
+        Ok(config_dir()?.join("servers.json"))
     }
 
-    pub fn load() -> Result<Self> {
+    /// Load servers.json. If it doesn't parse, the file is backed up and every entry
+    /// that is still readable is recovered; the second value then describes what happened.
+    pub fn load() -> Result<(Self, Option<ListRecovery>)> {
         let path = Self::get_config_path()?;
         if !path.exists() {
-            return Ok(Self::default());
+            return Ok((Self::default(), None));
         }
 
-        let contents = fs::read_to_string(path)?;
-        let mut list: ServerList = serde_json::from_str(&contents)?;
+        let contents = fs::read_to_string(&path)?;
+        let (mut list, recovery) = match serde_json::from_str::<ServerList>(&contents) {
+            Ok(list) => (list, None),
+            Err(e) => {
+                // Keep the broken file around and salvage what we can instead of starting empty
+                let backup_path = Self::backup_corrupt_file(&path).ok();
+                let (servers, dropped) = recover_entries(&strip_trailing_commas(&contents));
+                let recovery = ListRecovery {
+                    error: e.to_string(),
+                    backup_path,
+                    recovered: servers.len(),
+                    dropped,
+                };
+                (ServerList { servers }, Some(recovery))
+            }
+        };
 
         // Load configs for all servers
         for server in &mut list.servers {
             server.load_config();
         }
 
-        Ok(list)
+        Ok((list, recovery))
+    }
+
+    fn backup_corrupt_file(path: &Path) -> Result<PathBuf> {
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let backup_path = path.with_file_name(format!("servers.json.corrupt-{}", stamp));
+        fs::copy(path, &backup_path)?;
+        Ok(backup_path)
     }
 
     pub fn save(&self) -> Result<()> {
@@ -181,11 +708,111 @@ impl ServerList {
         Ok(())
     }
 
-    pub fn add_server(&mut self, path: PathBuf) -> Result<String> {
+    /// Add the server in `path`. A missing ServerConfig.toml is an error; a missing
+    /// executable is only reported, since the binary is often downloaded afterwards.
+    pub fn add_server(&mut self, path: PathBuf) -> Result<AddedServer> {
         let entry = ServerEntry::new(path)?;
-        let name = entry.name.clone();
+        let added = AddedServer {
+            name: entry.name.clone(),
+            missing_executable: entry.missing_executable,
+        };
         self.servers.push(entry);
-        Ok(name)
+        Ok(added)
+    }
+
+    /// Read a server list file (same format as servers.json) without touching the current list
+    pub fn read_import(path: &Path) -> Result<Vec<ServerEntry>> {
+        let contents = fs::read_to_string(path)?;
+        let list: ServerList = serde_json::from_str(&contents)?;
+        Ok(list.servers)
+    }
+
+    pub fn preview_import(&self, entries: Vec<ServerEntry>) -> ImportPreview {
+        let matching_count = entries
+            .iter()
+            .filter(|entry| self.servers.iter().any(|s| s.path == entry.path))
+            .count();
+
+        ImportPreview {
+            new_count: entries.len() - matching_count,
+            matching_count,
+            entries,
+        }
+    }
+
+    pub fn apply_import(&mut self, entries: Vec<ServerEntry>, mode: ImportMode) -> ImportSummary {
+        let mut summary = ImportSummary {
+            added: 0,
+            skipped: 0,
+            ids_regenerated: 0,
+        };
+
+        if mode == ImportMode::Replace {
+            self.servers.clear();
+        }
+
+        let mut used_ids: HashSet<String> = self.servers.iter().map(|s| s.id.clone()).collect();
+
+        for mut entry in entries {
+            if self.servers.iter().any(|s| s.path == entry.path) {
+                summary.skipped += 1;
+                continue;
+            }
+
+            // Imported lists may come from another machine or be hand-copied,
+            // so never trust their IDs to be unique in this list
+            if used_ids.contains(&entry.id) {
+                entry.id = uuid::Uuid::new_v4().to_string();
+                summary.ids_regenerated += 1;
+            }
+            used_ids.insert(entry.id.clone());
+
+            entry.load_config();
+            self.servers.push(entry);
+            summary.added += 1;
+        }
+
+        summary
+    }
+
+    /// Names of the other servers whose saved config uses `port`
+    pub fn servers_using_port(&self, port: u16, except_index: usize) -> Vec<String> {
+        // The checked server's port replaced by `port`, or added when it isn't listed yet
+        let mut ports = self.saved_ports();
+        let checked = except_index.min(ports.len());
+        match ports.get_mut(checked) {
+            Some(slot) => *slot = Some(port),
+            None => ports.push(Some(port)),
+        }
+        duplicate_ports(&ports)
+            .into_iter()
+            .find(|(shared, _)| *shared == port)
+            .map(|(_, users)| {
+                users
+                    .into_iter()
+                    .filter(|idx| *idx != checked)
+                    .map(|idx| self.servers[idx].display_name().to_string())
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+
+    /// Groups of servers whose saved configs use the same port
+    pub fn port_conflicts(&self) -> Vec<(u16, Vec<usize>)> {
+        duplicate_ports(&self.saved_ports())
+    }
+
+    fn saved_ports(&self) -> Vec<Option<u16>> {
+        self.servers
+            .iter()
+            .map(|s| s.loaded_config.as_ref().map(|c| c.general.port))
+            .collect()
+    }
+
+    /// First port after `start` that no other managed server uses and that can be bound
+    pub fn find_free_port(&self, start: u16, except_index: usize) -> Option<u16> {
+        (start.saturating_add(1)..=u16::MAX)
+            .find(|port| self.servers_using_port(*port, except_index).is_empty() && port_is_free(*port))
     }
 
     pub fn remove_server(&mut self, index: usize) {
@@ -193,7 +820,129 @@ impl ServerList {
             self.servers.remove(index);
         }
     }
+
+    /// Move the server at `from` so it lands at position `to` of the list as it was
+    /// before the move (0 = top, `servers.len()` = bottom)
+    pub fn move_server(&mut self, from: usize, to: usize) {
+        if from >= self.servers.len() || to > self.servers.len() {
+            return;
+        }
+        let server = self.servers.remove(from);
+        // Removing `from` shifts everything after it up by one
+        let to = if to > from { to - 1 } else { to };
+        self.servers.insert(to, server);
+    }
 }
 
 // Add uuid dependency to Cargo.toml
 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::temp_dir;
+
+    /// Empty folder under the system temp dir, unique to this test run
+    #[test]
+    fn reads_remembered_config_dir() {
+        let default = temp_dir("pointer");
+        assert_eq!(remembered_config_dir_in(&default), None);
+
+        fs::write(default.join(CONFIG_DIR_POINTER), "  \n").unwrap();
+        assert_eq!(remembered_config_dir_in(&default), None);
+
+        fs::write(default.join(CONFIG_DIR_POINTER), "/srv/panel data\n").unwrap();
+        assert_eq!(remembered_config_dir_in(&default), Some(PathBuf::from("/srv/panel data")));
+        fs::remove_dir_all(default).unwrap();
+    }
+
+    // Saving goes through serde, so values, unknown keys and unknown sections survive but
+    // comments and the original key order do not. The backup keeps the file as it was.
+    #[test]
+    fn config_round_trip_keeps_values_but_not_comments() {
+        let dir = temp_dir("roundtrip");
+        let original = r#"# Written by BeamMP-Server
+[Misc]
+ImScaredOfUpdates = false
+UpdateReminderTime = "1h"
+
+[General]
+# Keymaster key
+AuthKey = ""
+Name = "Round Trip"
+Port = 30815
+Tags = "Freeroam"
+MaxCars = 2
+MaxPlayers = 10
+Map = "/levels/italy/info.json"
+Description = "Test"
+ResourceFolder = "Resources"
+AllowGuests = false
+LogChat = true
+Debug = false
+IP = "::"
+Private = true
+InformationPacket = true
+FutureOption = 3
+
+[Plugin]
+Enabled = true
+"#;
+        fs::write(dir.join("ServerConfig.toml"), original).unwrap();
+
+        let mut entry = ServerEntry::new(dir.clone()).unwrap();
+        let loaded = entry.loaded_config.clone().unwrap();
+        assert_eq!(loaded.general.port, 30815);
+        entry.save_config().unwrap();
+        entry.load_config();
+        assert_eq!(entry.loaded_config, Some(loaded));
+
+        let saved = fs::read_to_string(dir.join("ServerConfig.toml")).unwrap();
+        assert!(!saved.contains('#'));
+        assert!(saved.find("[General]").unwrap() < saved.find("[Misc]").unwrap());
+        assert!(saved.contains("FutureOption = 3"));
+        assert!(saved.contains("[Plugin]"));
+
+        let backups = entry.config_backups();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(&backups[0].path).unwrap(), original);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    fn server_on_port(root: &Path, name: &str, port: u16) -> ServerEntry {
+        let dir = root.join(name);
+        fs::create_dir_all(&dir).unwrap();
+        let mut config = ServerConfig::default();
+        config.general.name = name.to_string();
+        config.general.port = port;
+        fs::write(dir.join("ServerConfig.toml"), toml::to_string(&config).unwrap()).unwrap();
+        ServerEntry::new(dir).unwrap()
+    }
+
+    #[test]
+    fn finds_servers_sharing_a_port() {
+        let root = temp_dir("ports");
+        let list = ServerList {
+            servers: vec![
+                server_on_port(&root, "A", 30814),
+                server_on_port(&root, "B", 30815),
+                server_on_port(&root, "C", 30815),
+                server_on_port(&root, "D", 30816),
+                server_on_port(&root, "E", 30816),
+                server_on_port(&root, "F", 30816),
+            ],
+        };
+
+        // No other server on the port
+        assert!(list.servers_using_port(30814, 0).is_empty());
+        // Two servers
+        assert_eq!(list.servers_using_port(30815, 1), ["C"]);
+        // Three servers
+        assert_eq!(list.servers_using_port(30816, 4), ["D", "F"]);
+        // An edited, unsaved port, and a server not in the list yet
+        assert_eq!(list.servers_using_port(30814, 3), ["A"]);
+        assert_eq!(list.servers_using_port(30815, list.servers.len()), ["B", "C"]);
+
+        assert_eq!(list.port_conflicts(), [(30815, vec![1, 2]), (30816, vec![3, 4, 5])]);
+        fs::remove_dir_all(root).unwrap();
+    }
+}
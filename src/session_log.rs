@@ -0,0 +1,67 @@
+use crate::server;
+use anyhow::Result;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::{Path, PathBuf};
+
+// Older run logs beyond this are removed when a new run starts
+const MAX_LOG_FILES: usize = 50;
+
+/// Complete console output of one server run. The in-memory console only keeps the
+/// most recent lines; this file keeps everything so exports aren't truncated.
+pub struct SessionLog {
+    path: PathBuf,
+    writer: BufWriter<File>,
+}
+
+impl SessionLog {
+    pub fn create(server_name: &str) -> Result<Self> {
+        let dir = server::config_dir()?.join("logs");
+        fs::create_dir_all(&dir)?;
+        prune_old_logs(&dir);
+
+        let safe_name: String = server_name
+            .chars()
+            .map(|c| if c.is_alphanumeric() || c == '-' || c == '_' { c } else { '_' })
+            .collect();
+        let stamp = chrono::Local::now().format("%Y%m%d-%H%M%S");
+        let path = dir.join(format!("{}-{}.log", safe_name, stamp));
+
+        let writer = BufWriter::new(File::create(&path)?);
+        Ok(Self { path, writer })
+    }
+
+    pub fn append(&mut self, lines: &[String]) {
+        for line in lines {
+            // Logging must never interrupt the console; a failed write just loses the line
+            let _ = writeln!(self.writer, "{}", line);
+        }
+    }
+
+    /// Copy the full log to `target`
+    pub fn export(&mut self, target: &Path) -> Result<()> {
+        self.writer.flush()?;
+        fs::copy(&self.path, target)?;
+        Ok(())
+    }
+}
+
+fn prune_old_logs(dir: &Path) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let mut logs: Vec<(std::time::SystemTime, PathBuf)> = entries
+        .map_while(Result::ok)
+        .filter(|e| e.path().extension().is_some_and(|ext| ext == "log"))
+        .filter_map(|e| Some((e.metadata().ok()?.modified().ok()?, e.path())))
+        .collect();
+
+    if logs.len() < MAX_LOG_FILES {
+        return;
+    }
+    logs.sort();
+    let excess = logs.len() + 1 - MAX_LOG_FILES;
+    for (_, path) in logs.into_iter().take(excess) {
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,160 @@
+use crate::api;
+use crate::server;
+use anyhow::Result;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::ops::RangeInclusive;
+use std::path::PathBuf;
+
+/// Allowed values of `AppSettings::console_scrollback_lines`
+pub const SCROLLBACK_RANGE: RangeInclusive<usize> = 500..=50_000;
+
+/// A regular expression and the color console lines matching it are marked with
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct HighlightRule {
+    pub pattern: String,
+    /// sRGB
+    pub color: [u8; 3],
+    /// Also show a desktop notification when a line matches
+    #[serde(default)]
+    pub notify: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AppSettings {
+    /// BeamMP launcher executable used by "Connect with BeamNG"
+    pub client_path: Option<PathBuf>,
+    /// Extra launcher arguments; `{ip}` and `{port}` are replaced with the local server address
+    pub client_launch_args: String,
+    /// Seconds between automatic `list` refreshes while the Control tab is open (0 = off)
+    pub player_refresh_interval_secs: u32,
+    /// Seconds a server gets to exit after `exit` before it is killed
+    pub stop_grace_secs: u32,
+    /// Seconds before a restart at which players get a countdown message
+    pub restart_countdown_secs: Vec<u32>,
+    /// Install client mod ZIPs that have neither a levels/ nor a vehicles/ folder
+    pub allow_unknown_mod_structure: bool,
+    /// Seconds a mod download may wait for the server to connect or send more data
+    pub download_timeout_secs: u32,
+    /// Oldest deleted mods are removed from a server's trash beyond this size
+    pub mod_trash_limit_mb: u64,
+    /// Look for server mods inside folders that have no Lua scripts of their own
+    pub nested_server_mods: bool,
+    /// Mod repository search address, `{query}` is replaced with the search text; empty hides search
+    pub mod_search_url: String,
+    /// Serve the local control API on 127.0.0.1
+    pub api_enabled: bool,
+    pub api_port: u16,
+    /// Bearer token API clients must send; generated when the API is first enabled
+    pub api_token: String,
+    /// Save config edits on their own shortly after the last change, instead of on Apply
+    pub autosave_config: bool,
+    /// Answers a plain GET with this machine's public IP, for the Share window
+    pub public_ip_service: String,
+    /// Public BeamMP server list the Share window looks the server up in; empty hides the check
+    pub server_list_url: String,
+    /// Compare each server's BeamMP-Server version with the latest release at startup.
+    /// Off by default, for admins who'd rather not hear about updates.
+    pub check_server_updates: bool,
+    /// GitHub API address of the latest BeamMP-Server release
+    pub server_release_url: String,
+    /// Main window size and position from the last session
+    pub window: Option<WindowGeometry>,
+    /// Zoom on top of the display's own scaling, 0.75 to 2.0
+    pub ui_scale: f32,
+    /// Show the local time each console line arrived in front of it
+    pub console_timestamps: bool,
+    /// Console lines matching these get a colored background
+    pub highlight_rules: Vec<HighlightRule>,
+    /// Desktop notification when a server exits unexpectedly
+    pub notify_on_crash: bool,
+    /// Show an icon with a Show/Quit menu in the system tray
+    pub tray_icon: bool,
+    /// Start with the window hidden in the tray (only with `tray_icon`)
+    pub start_minimized: bool,
+    /// The window's close button hides it in the tray instead of quitting (only with `tray_icon`)
+    pub minimize_to_tray: bool,
+    /// Console text size in points; None uses the normal monospace size
+    pub console_font_size: Option<f32>,
+    /// Lines kept in memory per server console, within `SCROLLBACK_RANGE`; the session
+    /// log on disk keeps everything
+    pub console_scrollback_lines: usize,
+    /// Offer the setup wizard at startup when no servers are added yet
+    pub show_setup_wizard: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    /// Inner size in points
+    pub size: [f32; 2],
+    /// Outer position in points, if the platform reports it
+    pub position: Option<[f32; 2]>,
+    pub maximized: bool,
+    /// Size of the monitor the window was on, used to notice a changed monitor setup
+    pub monitor_size: Option<[f32; 2]>,
+}
+
+impl Default for AppSettings {
+    fn default() -> Self {
+        Self {
+            client_path: None,
+            client_launch_args: String::new(),
+            player_refresh_interval_secs: 0,
+            stop_grace_secs: 10,
+            restart_countdown_secs: crate::schedule::DEFAULT_COUNTDOWN.to_vec(),
+            allow_unknown_mod_structure: false,
+            download_timeout_secs: 30,
+            mod_trash_limit_mb: 2048,
+            nested_server_mods: false,
+            mod_search_url: String::new(),
+            api_enabled: false,
+            api_port: api::DEFAULT_PORT,
+            api_token: String::new(),
+            autosave_config: false,
+            public_ip_service: "https://api.ipify.org".to_string(),
+            server_list_url: "https://backend.beammp.com/servers-info".to_string(),
+            check_server_updates: false,
+            server_release_url: "https://api.github.com/repos/BeamMP/BeamMP-Server/releases/latest".to_string(),
+            window: None,
+            ui_scale: 1.0,
+            console_font_size: None,
+            console_scrollback_lines: 1000,
+            console_timestamps: false,
+            highlight_rules: Vec::new(),
+            notify_on_crash: true,
+            tray_icon: false,
+            start_minimized: false,
+            minimize_to_tray: false,
+            show_setup_wizard: true,
+        }
+    }
+}
+
+impl AppSettings {
+    /// `console_scrollback_lines`, kept in range should the file have been edited by hand
+    pub fn scrollback_lines(&self) -> usize {
+        self.console_scrollback_lines.clamp(*SCROLLBACK_RANGE.start(), *SCROLLBACK_RANGE.end())
+    }
+
+    fn get_settings_path() -> Result<PathBuf> {
+        Ok(server::config_dir()?.join("settings.json"))
+    }
+
+    pub fn load() -> Result<Self> {
+        let path = Self::get_settings_path()?;
+        if !path.exists() {
+            return Ok(Self::default());
+        }
+
+        let contents = fs::read_to_string(path)?;
+        Ok(serde_json::from_str(&contents)?)
+    }
+
+    pub fn save(&self) -> Result<()> {
+        let path = Self::get_settings_path()?;
+        let contents = serde_json::to_string_pretty(self)?;
+        fs::write(path, contents)?;
+        Ok(())
+    }
+}
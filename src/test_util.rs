@@ -0,0 +1,10 @@
+use std::fs;
+use std::path::PathBuf;
+
+/// A new empty folder under the system temp directory, unique per call. Tests remove it
+/// when they pass; a failed test leaves it behind for a look.
+pub fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("beammp-panel-test-{}-{}", name, uuid::Uuid::new_v4().simple()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
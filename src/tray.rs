@@ -0,0 +1,181 @@
+use anyhow::Result;
+use std::sync::mpsc::{channel, Receiver, Sender};
+
+/// A click in the tray icon's menu
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TrayEvent {
+    Show,
+    Quit,
+}
+
+/// The panel's system tray icon with its Show/Quit menu. Removed when dropped.
+///
+/// Windows and macOS use the native tray through `tray-icon`, which must be created on the
+/// UI thread. Linux desktops get a StatusNotifierItem over D-Bus, served from its own thread,
+/// so no GTK is needed.
+pub struct Tray {
+    events: Receiver<TrayEvent>,
+    _icon: platform::Icon,
+}
+
+impl Tray {
+    pub fn new(ctx: &egui::Context, icon: &egui::IconData) -> Result<Self> {
+        let (tx, rx) = channel();
+        let notify = Notifier { events: tx, ctx: ctx.clone() };
+        Ok(Self {
+            events: rx,
+            _icon: platform::Icon::new(icon, notify)?,
+        })
+    }
+
+    /// Menu clicks since the last call
+    pub fn events(&self) -> Vec<TrayEvent> {
+        self.events.try_iter().collect()
+    }
+}
+
+/// Hands a tray event to the UI thread and wakes it up
+#[derive(Clone)]
+struct Notifier {
+    events: Sender<TrayEvent>,
+    ctx: egui::Context,
+}
+
+impl Notifier {
+    fn send(&self, event: TrayEvent) {
+        let _ = self.events.send(event);
+        self.ctx.request_repaint();
+    }
+}
+
+#[cfg(target_os = "linux")]
+mod platform {
+    use super::{Notifier, TrayEvent};
+    use anyhow::Result;
+    use ksni::blocking::TrayMethods;
+
+    pub struct Icon(ksni::blocking::Handle<PanelTray>);
+
+    impl Icon {
+        pub fn new(icon: &egui::IconData, notify: Notifier) -> Result<Self> {
+            // The tray wants ARGB, egui has RGBA
+            let data = icon
+                .rgba
+                .chunks_exact(4)
+                .flat_map(|p| [p[3], p[0], p[1], p[2]])
+                .collect();
+            let pixmap = ksni::Icon {
+                width: icon.width as i32,
+                height: icon.height as i32,
+                data,
+            };
+            Ok(Self(PanelTray { pixmap, notify }.spawn()?))
+        }
+    }
+
+    impl Drop for Icon {
+        fn drop(&mut self) {
+            self.0.shutdown();
+        }
+    }
+
+    pub struct PanelTray {
+        pixmap: ksni::Icon,
+        notify: Notifier,
+    }
+
+    impl ksni::Tray for PanelTray {
+        fn id(&self) -> String {
+            "beammp-panel".to_string()
+        }
+
+        fn title(&self) -> String {
+            "BeamMP Panel".to_string()
+        }
+
+        fn icon_pixmap(&self) -> Vec<ksni::Icon> {
+            vec![self.pixmap.clone()]
+        }
+
+        fn activate(&mut self, _x: i32, _y: i32) {
+            self.notify.send(TrayEvent::Show);
+        }
+
+        fn menu(&self) -> Vec<ksni::MenuItem<Self>> {
+            vec![
+                ksni::menu::StandardItem {
+                    label: "Show".to_string(),
+                    activate: Box::new(|tray: &mut Self| tray.notify.send(TrayEvent::Show)),
+                    ..Default::default()
+                }
+                .into(),
+                ksni::MenuItem::Separator,
+                ksni::menu::StandardItem {
+                    label: "Quit".to_string(),
+                    activate: Box::new(|tray: &mut Self| tray.notify.send(TrayEvent::Quit)),
+                    ..Default::default()
+                }
+                .into(),
+            ]
+        }
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+mod platform {
+    use super::{Notifier, TrayEvent};
+    use anyhow::Result;
+    use tray_icon::menu::{Menu, MenuEvent, MenuItem, PredefinedMenuItem};
+    use tray_icon::{MouseButton, MouseButtonState, TrayIcon, TrayIconBuilder, TrayIconEvent};
+
+    const SHOW_ID: &str = "show";
+    const QUIT_ID: &str = "quit";
+
+    pub struct Icon(TrayIcon);
+
+    impl Icon {
+        pub fn new(icon: &egui::IconData, notify: Notifier) -> Result<Self> {
+            let menu = Menu::new();
+            menu.append_items(&[
+                &MenuItem::with_id(SHOW_ID, "Show", true, None),
+                &PredefinedMenuItem::separator(),
+                &MenuItem::with_id(QUIT_ID, "Quit", true, None),
+            ])?;
+            let tray = TrayIconBuilder::new()
+                .with_menu(Box::new(menu))
+                .with_menu_on_left_click(false)
+                .with_tooltip("BeamMP Panel")
+                .with_icon(tray_icon::Icon::from_rgba(icon.rgba.clone(), icon.width, icon.height)?)
+                .build()?;
+
+            // Handlers instead of the receivers, since the UI thread may be idle while the
+            // window is hidden and has to be woken up
+            let menu_notify = notify.clone();
+            MenuEvent::set_event_handler(Some(move |event: MenuEvent| {
+                if event.id == SHOW_ID {
+                    menu_notify.send(TrayEvent::Show);
+                } else if event.id == QUIT_ID {
+                    menu_notify.send(TrayEvent::Quit);
+                }
+            }));
+            TrayIconEvent::set_event_handler(Some(move |event: TrayIconEvent| {
+                let show = match event {
+                    TrayIconEvent::Click { button: MouseButton::Left, button_state: MouseButtonState::Up, .. } => true,
+                    TrayIconEvent::DoubleClick { .. } => true,
+                    _ => false,
+                };
+                if show {
+                    notify.send(TrayEvent::Show);
+                }
+            }));
+            Ok(Self(tray))
+        }
+    }
+
+    impl Drop for Icon {
+        fn drop(&mut self) {
+            MenuEvent::set_event_handler(None::<fn(MenuEvent)>);
+            TrayIconEvent::set_event_handler(None::<fn(TrayIconEvent)>);
+        }
+    }
+}
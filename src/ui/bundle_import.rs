@@ -0,0 +1,79 @@
+use crate::bundle::{self, BundleImport};
+use crate::server::{ImportMode, ServerEntry};
+use egui::Ui;
+
+pub enum BundleImportAction {
+    None,
+    Cancel,
+    Import(ImportMode),
+}
+
+/// The "Import Server Bundle" window, where each bundled server gets a folder on this machine
+pub fn show(ui: &mut Ui, import: &mut BundleImport, current: &[ServerEntry]) -> BundleImportAction {
+    let mut action = BundleImportAction::None;
+
+    ui.set_max_width(600.0);
+    ui.label("Folders are stored as absolute paths and may not exist on this machine. Choose where each server lives here; unticked servers are left out.");
+    if ui.button("📂 Look in Folder...").on_hover_text("Find missing servers by folder name inside a folder you pick").clicked() {
+        if let Some(base) = rfd::FileDialog::new().pick_folder() {
+            bundle::remap_missing(&mut import.servers, &base);
+        }
+    }
+    ui.separator();
+
+    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+        for (i, server) in import.servers.iter_mut().enumerate() {
+            ui.push_id(i, |ui| {
+                ui.horizontal(|ui| {
+                    ui.add_enabled(server.is_importable(), egui::Checkbox::new(&mut server.include, ""));
+                    ui.strong(server.entry.display_name());
+                    if !server.folder_exists() {
+                        ui.colored_label(egui::Color32::YELLOW, "⚠ Folder not found");
+                    } else if !server.is_importable() {
+                        ui.colored_label(egui::Color32::YELLOW, "⚠ No ServerConfig.toml in this folder");
+                    } else if current.iter().any(|s| s.path == server.entry.path) {
+                        ui.colored_label(egui::Color32::GRAY, "Already in the list");
+                    }
+                    if ui.small_button("Choose Folder...").clicked() {
+                        if let Some(path) = rfd::FileDialog::new().pick_folder() {
+                            bundle::set_folder(server, path);
+                        }
+                    }
+                });
+                ui.label(egui::RichText::new(server.entry.path.display().to_string()).monospace().small());
+            });
+        }
+    });
+
+    let replaces_configs = import.servers.iter()
+        .filter(|s| s.include && s.config.is_some() && s.has_local_config())
+        .count();
+    if replaces_configs > 0 {
+        ui.checkbox(
+            &mut import.overwrite_configs,
+            format!("Replace the {} existing ServerConfig.toml file(s) with the bundled ones", replaces_configs),
+        ).on_hover_text("The current files are backed up first");
+    }
+
+    ui.separator();
+    let included = import.servers.iter().filter(|s| s.include).count();
+    ui.label(format!("{} of {} server(s) will be imported.", included, import.servers.len()));
+    ui.label(format!(
+        "Merge keeps your current servers; Replace removes all {} of them from the manager first.",
+        current.len()
+    ));
+    ui.horizontal(|ui| {
+        if ui.button("Cancel").clicked() {
+            action = BundleImportAction::Cancel;
+        }
+        ui.add_enabled_ui(included > 0, |ui| {
+            if ui.button("Merge").clicked() {
+                action = BundleImportAction::Import(ImportMode::Merge);
+            }
+            if ui.button("Replace").clicked() {
+                action = BundleImportAction::Import(ImportMode::Replace);
+            }
+        });
+    });
+    action
+}
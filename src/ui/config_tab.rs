@@ -1,26 +1,186 @@
-use crate::server::ServerEntry;
+use crate::config::{validate_auth_key, ServerConfig};
+use crate::mods::{map_level_name, AvailableLevel};
+use crate::process::{open_path, server_executable};
+use crate::server::{DiskUsage, ServerEntry};
 use crate::{StatusMessage};
 use egui::{ScrollArea, Ui};
 
-pub fn show(ui: &mut Ui, server: &mut ServerEntry, status: &mut Option<StatusMessage>) {
-    if let Some(error) = &server.config_error {
+pub enum ConfigAction {
+    None,
+    Apply,
+    PickFreePort,
+    ShowBackups,
+    LocateFolder,
+    RemoveServer,
+    CalculateSize,
+    /// Switch to the Raw view with the file as it is on disk
+    EditRaw,
+    CloseRaw,
+    SaveRaw,
+    ReloadRaw,
+}
+
+/// ServerConfig.toml being edited as text in the Raw view
+pub struct RawConfigEditor {
+    pub server_id: String,
+    pub text: String,
+    /// The text as last loaded or saved
+    saved: String,
+    /// Why `text` doesn't parse as a config, refreshed when it changes
+    parse_error: Option<String>,
+    /// Save was clicked while the text doesn't parse
+    confirm_invalid: bool,
+}
+
+impl RawConfigEditor {
+    pub fn new(server_id: String, text: String) -> Self {
+        let mut editor = Self {
+            server_id,
+            saved: text.clone(),
+            text,
+            parse_error: None,
+            confirm_invalid: false,
+        };
+        editor.check();
+        editor
+    }
+
+    /// Call after the text was written to disk
+    pub fn mark_saved(&mut self) {
+        self.saved = self.text.clone();
+        self.confirm_invalid = false;
+    }
+
+    fn check(&mut self) {
+        self.parse_error = toml::from_str::<ServerConfig>(&self.text).err().map(|e| {
+            let line = e.span().map(|span| self.text[..span.start.min(self.text.len())].matches('\n').count() + 1);
+            match line {
+                Some(line) => format!("Line {}: {}", line, e.message()),
+                None => e.message().to_string(),
+            }
+        });
+    }
+}
+
+/// What the Config tab shows about the rest of the server list
+pub struct OtherServers<'a> {
+    /// Names of servers configured with the same port
+    pub port_used_by: &'a [String],
+    /// Name and BeamMP-Server version of servers whose version differs from this one's
+    pub other_versions: &'a [(String, String)],
+}
+
+pub fn show(
+    ui: &mut Ui,
+    server: &mut ServerEntry,
+    others: &OtherServers,
+    levels: &[AvailableLevel],
+    disk_usage: Option<&DiskUsage>,
+    calculating_size: bool,
+    status: &mut Option<StatusMessage>,
+) -> ConfigAction {
+    let mut action = ConfigAction::None;
+
+    if server.folder_missing {
+        ui.colored_label(egui::Color32::YELLOW, "⚠ The server folder was not found:");
+        ui.label(egui::RichText::new(server.path.display().to_string()).monospace());
+        ui.label("It may have been moved, renamed or deleted, or be on a drive that isn't connected.");
+        ui.separator();
+        ui.horizontal(|ui| {
+            if ui.button("📂 Locate Folder...").clicked() {
+                action = ConfigAction::LocateFolder;
+            }
+            if ui.button("Check Again").clicked() {
+                server.load_config();
+            }
+            if ui.button("Remove from List").clicked() {
+                action = ConfigAction::RemoveServer;
+            }
+        });
+        return action;
+    }
+
+    if let Some(error) = server.config_error.clone() {
+        let location = server.parse_error.as_ref().and_then(|p| p.location);
         ui.colored_label(egui::Color32::RED, format!("Error: {}", error));
+        if let Some((line, column)) = location {
+            ui.label(format!("Line {}, column {}", line, column));
+        }
         ui.separator();
-        if ui.button("Reload Config").clicked() {
-            server.load_config();
+
+        let jump_id = ui.id().with("config_jump_to_error");
+        ui.horizontal(|ui| {
+            if ui.button("Reload Config").clicked() {
+                server.load_config();
+            }
+            if ui.button("Open in Editor").clicked() {
+                if let Err(e) = open_path(&server.path.join("ServerConfig.toml")) {
+                    *status = Some(StatusMessage {
+                        text: format!("Failed to open the config: {}", e),
+                        is_error: true,
+                    });
+                }
+            }
+            if location.is_some() && ui.button("Jump to Error").clicked() {
+                ui.data_mut(|d| d.insert_temp(jump_id, true));
+            }
+            if ui.button("Restore Backup...").clicked() {
+                action = ConfigAction::ShowBackups;
+            }
+            if server.parse_error.is_some() && ui.button("Edit Raw").on_hover_text("Fix the file here").clicked() {
+                action = ConfigAction::EditRaw;
+            }
+        });
+
+        if let Some(parse_error) = &server.parse_error {
+            ui.separator();
+            show_config_source(ui, &parse_error.contents, location.map(|(line, _)| line), jump_id);
         }
-        return;
+        return action;
+    }
+
+    if server.missing_executable {
+        ui.horizontal(|ui| {
+            let exe_path = server_executable(&server.path);
+            let exe_name = exe_path.file_name().unwrap_or_default().to_string_lossy();
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!("⚠ {} not found. Download BeamMP-Server into this folder before starting it.", exe_name),
+            );
+            if ui.button("Check Again").clicked() {
+                server.check_executable();
+            }
+        });
+        ui.separator();
     }
 
     let config = match &mut server.edited_config {
         Some(c) => c,
         None => {
             ui.label("No config loaded");
-            return;
+            return action;
         }
     };
 
     ScrollArea::vertical().show(ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.label("BeamMP-Server:");
+            match &server.server_version {
+                Some(version) => ui.label(egui::RichText::new(version).strong()),
+                None => ui.weak("version not known yet").on_hover_text("Read from the server's output the next time it starts"),
+            };
+        });
+        if !others.other_versions.is_empty() {
+            let list: Vec<String> = others
+                .other_versions
+                .iter()
+                .map(|(name, version)| format!("{} ({})", name, version))
+                .collect();
+            ui.colored_label(egui::Color32::YELLOW, format!("⚠ Other servers run a different version: {}", list.join(", ")))
+                .on_hover_text("Players may need a matching client for each, and mods or plugins can behave differently");
+        }
+        ui.add_space(10.0);
+
         ui.heading("General Settings");
         ui.separator();
 
@@ -32,11 +192,35 @@ pub fn show(ui: &mut Ui, server: &mut ServerEntry, status: &mut Option<StatusMes
         ui.horizontal(|ui| {
             ui.label("Port:");
             ui.add(egui::DragValue::new(&mut config.general.port).range(1..=65535));
+            if !others.port_used_by.is_empty() {
+                ui.colored_label(
+                    egui::Color32::YELLOW,
+                    format!("⚠ Also used by: {}", others.port_used_by.join(", ")),
+                );
+                if ui.button("Pick free port").clicked() {
+                    action = ConfigAction::PickFreePort;
+                }
+            }
         });
 
         ui.horizontal(|ui| {
             ui.label("Auth Key:");
-            ui.text_edit_singleline(&mut config.general.auth_key);
+
+            // Masked by default so the key doesn't leak on screen shares; not persisted
+            let visible_id = ui.id().with("auth_key_visible");
+            let mut visible = ui.data(|d| d.get_temp::<bool>(visible_id).unwrap_or(false));
+            ui.add(egui::TextEdit::singleline(&mut config.general.auth_key).password(!visible));
+            let toggle_hint = if visible { "Hide key" } else { "Show key" };
+            if ui.selectable_label(visible, "👁").on_hover_text(toggle_hint).clicked() {
+                visible = !visible;
+                ui.data_mut(|d| d.insert_temp(visible_id, visible));
+            }
+            if ui.button("📋").on_hover_text("Copy key").clicked() {
+                ui.ctx().copy_text(config.general.auth_key.clone());
+            }
+            if let Err(problem) = validate_auth_key(&config.general.auth_key) {
+                ui.colored_label(egui::Color32::RED, format!("⚠ {}", problem));
+            }
         });
 
         ui.horizontal(|ui| {
@@ -57,7 +241,14 @@ pub fn show(ui: &mut Ui, server: &mut ServerEntry, status: &mut Option<StatusMes
         ui.horizontal(|ui| {
             ui.label("Map:");
             ui.text_edit_singleline(&mut config.general.map);
+            level_picker(ui, &mut config.general.map, levels);
+            let level = map_level_name(&config.general.map);
+            let known = level.is_some_and(|name| levels.iter().any(|l| l.name.eq_ignore_ascii_case(name)));
+            if !known && !levels.is_empty() {
+                ui.colored_label(egui::Color32::RED, "⚠ No stock level or enabled mod provides this map");
+            }
         });
+        map_suggestions(ui, &mut config.general.map, levels);
 
         ui.horizontal(|ui| {
             ui.label("Tags:");
@@ -98,25 +289,76 @@ pub fn show(ui: &mut Ui, server: &mut ServerEntry, status: &mut Option<StatusMes
             ui.label("Update Reminder Time:");
             ui.text_edit_singleline(&mut config.misc.update_reminder_time);
         });
+
+        let extra_count = config.extra.len() + config.general.extra.len() + config.misc.extra.len();
+        if extra_count > 0 {
+            ui.add_space(10.0);
+            egui::CollapsingHeader::new(format!("Advanced ({} other setting{})", extra_count, if extra_count == 1 { "" } else { "s" }))
+                .id_salt("config_advanced")
+                .show(ui, |ui| {
+                    ui.label("Settings the form has no fields for, from newer BeamMP versions or plugins. They are saved back as they are.");
+                    show_extra_table(ui, "General", &mut config.general.extra);
+                    show_extra_table(ui, "Misc", &mut config.misc.extra);
+                    let sections: Vec<String> = config.extra.keys().cloned().collect();
+                    for name in sections {
+                        match config.extra.get_mut(&name) {
+                            Some(toml::Value::Table(table)) => show_extra_table(ui, &name, table),
+                            Some(value) => {
+                                ui.horizontal(|ui| {
+                                    ui.label(format!("{}:", name));
+                                    edit_toml_value(ui, value);
+                                });
+                            }
+                            None => {}
+                        }
+                    }
+                });
+        }
+
+        ui.add_space(10.0);
+        ui.heading("Disk Usage");
+        ui.separator();
+
+        if let Some(usage) = disk_usage {
+            let other = usage.total.saturating_sub(usage.client_mods + usage.server_mods + usage.disabled_mods);
+            egui::Grid::new("disk_usage").num_columns(2).show(ui, |ui| {
+                ui.strong("Total:");
+                ui.strong(format!("{} in {} files", super::format_bytes(usage.total), usage.files));
+                ui.end_row();
+                ui.label("Client mods:");
+                ui.label(super::format_bytes(usage.client_mods));
+                ui.end_row();
+                ui.label("Server mods:");
+                ui.label(super::format_bytes(usage.server_mods));
+                ui.end_row();
+                if usage.disabled_mods > 0 {
+                    ui.label("Disabled mods:");
+                    ui.label(super::format_bytes(usage.disabled_mods));
+                    ui.end_row();
+                }
+                ui.label("Everything else:");
+                ui.label(super::format_bytes(other));
+                ui.end_row();
+            });
+            ui.weak(format!("Calculated {}", super::format_timestamp(usage.calculated_at)));
+        }
+        ui.horizontal(|ui| {
+            if calculating_size {
+                ui.spinner();
+                ui.label("Calculating...");
+            } else {
+                let label = if disk_usage.is_some() { "Recalculate" } else { "Calculate Size" };
+                if ui.button(label).on_hover_text("Adds up every file in the server folder").clicked() {
+                    action = ConfigAction::CalculateSize;
+                }
+            }
+        });
     });
 
     ui.separator();
     ui.horizontal(|ui| {
         if ui.button("Apply").clicked() {
-            match server.save_config() {
-                Ok(_) => {
-                    *status = Some(StatusMessage {
-                        text: "Configuration saved!".to_string(),
-                        is_error: false,
-                    });
-                }
-                Err(e) => {
-                    *status = Some(StatusMessage {
-                        text: format!("Failed to save config: {}", e),
-                        is_error: true,
-                    });
-                }
-            }
+            action = ConfigAction::Apply;
         }
 
         let is_dirty = server.is_config_dirty();
@@ -130,9 +372,209 @@ pub fn show(ui: &mut Ui, server: &mut ServerEntry, status: &mut Option<StatusMes
             }
         });
 
+        if ui.button("Restore Backup...").on_hover_text("A copy of the config is kept every time you apply changes").clicked() {
+            action = ConfigAction::ShowBackups;
+        }
+
+        if ui.button("Edit Raw").on_hover_text("Edit ServerConfig.toml as text, including sections the form doesn't show").clicked() {
+            action = ConfigAction::EditRaw;
+        }
+
         if is_dirty {
             ui.colored_label(egui::Color32::YELLOW, "Unsaved changes");
         }
     });
+
+    action
+}
+
+/// Text editor for ServerConfig.toml. `form_dirty` warns that saving replaces unapplied form edits.
+pub fn show_raw(ui: &mut Ui, editor: &mut RawConfigEditor, form_dirty: bool) -> ConfigAction {
+    let mut action = ConfigAction::None;
+
+    ui.horizontal(|ui| {
+        if ui.button("⬅ Form").on_hover_text("Back to the form view").clicked() {
+            action = ConfigAction::CloseRaw;
+        }
+        ui.heading("ServerConfig.toml");
+    });
+    if form_dirty {
+        ui.colored_label(egui::Color32::YELLOW, "The form has changes that weren't applied. Saving here replaces them.");
+    }
+    match &editor.parse_error {
+        None => ui.colored_label(egui::Color32::GREEN, "✔ Valid config"),
+        Some(e) => ui.colored_label(egui::Color32::RED, format!("✖ {}", e)),
+    };
+    ui.separator();
+
+    let bottom_height = ui.spacing().interact_size.y * 2.5;
+    ScrollArea::both()
+        .max_height((ui.available_height() - bottom_height).max(100.0))
+        .auto_shrink([false, false])
+        .show(ui, |ui| {
+            let response = ui.add(
+                egui::TextEdit::multiline(&mut editor.text)
+                    .code_editor()
+                    .desired_width(f32::INFINITY)
+                    .desired_rows(30),
+            );
+            if response.changed() {
+                editor.check();
+                editor.confirm_invalid = false;
+            }
+        });
+
+    ui.separator();
+    let dirty = editor.text != editor.saved;
+    ui.horizontal(|ui| {
+        if editor.confirm_invalid {
+            ui.colored_label(egui::Color32::RED, "This doesn't parse; the server won't start with it.");
+            if ui.button("Save Anyway").clicked() {
+                action = ConfigAction::SaveRaw;
+            }
+            if ui.button("Keep Editing").clicked() {
+                editor.confirm_invalid = false;
+            }
+            return;
+        }
+
+        if ui.add_enabled(dirty, egui::Button::new("Save")).clicked() {
+            if editor.parse_error.is_some() {
+                editor.confirm_invalid = true;
+            } else {
+                action = ConfigAction::SaveRaw;
+            }
+        }
+        if ui.button("Reload File").on_hover_text("Discard these edits and read the file again").clicked() {
+            action = ConfigAction::ReloadRaw;
+        }
+        if dirty {
+            ui.colored_label(egui::Color32::YELLOW, "Unsaved changes");
+        }
+    });
+
+    action
 }
 
+/// Keys of one `[section]` the form doesn't know, edited by value type
+fn show_extra_table(ui: &mut Ui, section: &str, table: &mut toml::Table) {
+    if table.is_empty() {
+        return;
+    }
+    ui.add_space(5.0);
+    ui.strong(format!("[{}]", section));
+    egui::Grid::new(("config_extra", section)).num_columns(2).show(ui, |ui| {
+        for (key, value) in table.iter_mut() {
+            ui.label(format!("{}:", key));
+            edit_toml_value(ui, value);
+            ui.end_row();
+        }
+    });
+}
+
+/// Field for a single TOML value. Arrays, tables and dates are only shown; the Raw view edits them.
+fn edit_toml_value(ui: &mut Ui, value: &mut toml::Value) {
+    match value {
+        toml::Value::String(text) => {
+            ui.text_edit_singleline(text);
+        }
+        toml::Value::Integer(number) => {
+            ui.add(egui::DragValue::new(number));
+        }
+        toml::Value::Float(number) => {
+            ui.add(egui::DragValue::new(number).speed(0.1));
+        }
+        toml::Value::Boolean(flag) => {
+            ui.checkbox(flag, "");
+        }
+        other => {
+            ui.label(egui::RichText::new(other.to_string()).monospace().weak())
+                .on_hover_text("Use Edit Raw to change this value");
+        }
+    }
+}
+
+/// Read-only view of the config file with line numbers and the error line highlighted
+fn show_config_source(ui: &mut Ui, contents: &str, error_line: Option<usize>, jump_id: egui::Id) {
+    let jump = ui.data_mut(|d| d.remove_temp::<bool>(jump_id)).unwrap_or(false);
+    let digits = contents.lines().count().max(1).to_string().len();
+
+    ScrollArea::both().auto_shrink([false, false]).show(ui, |ui| {
+        for (i, line) in contents.lines().enumerate() {
+            let number = i + 1;
+            let is_error = error_line == Some(number);
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(format!("{:>width$}", number, width = digits)).monospace().weak());
+                let mut text = egui::RichText::new(line).monospace();
+                if is_error {
+                    text = text.background_color(egui::Color32::from_rgb(110, 30, 30)).color(egui::Color32::WHITE);
+                }
+                let response = ui.label(text);
+                if is_error && jump {
+                    response.scroll_to_me(Some(egui::Align::Center));
+                }
+            });
+        }
+    });
+}
+
+/// Drop-down of every known level next to the Map field. Picking one writes its full
+/// path; the text field stays for anything not listed.
+fn level_picker(ui: &mut Ui, map: &mut String, levels: &[AvailableLevel]) {
+    if levels.is_empty() {
+        return;
+    }
+    let current = map_level_name(map).map(str::to_string);
+    egui::ComboBox::from_id_salt("map_level_picker")
+        .selected_text("Pick level")
+        .height(300.0)
+        .show_ui(ui, |ui| {
+            for level in levels {
+                let selected = current.as_deref().is_some_and(|name| name.eq_ignore_ascii_case(&level.name));
+                let mut job = egui::text::LayoutJob::default();
+                job.append(&level.name, 0.0, egui::TextFormat::simple(egui::TextStyle::Body.resolve(ui.style()), ui.visuals().text_color()));
+                let source = level.mod_name.as_deref().unwrap_or("stock");
+                job.append(source, 8.0, egui::TextFormat::simple(egui::TextStyle::Small.resolve(ui.style()), ui.visuals().weak_text_color()));
+                if ui.selectable_label(selected, job).clicked() {
+                    *map = level.map_path();
+                }
+            }
+        });
+}
+
+// Suggestions shown under the Map field at once
+const MAX_MAP_SUGGESTIONS: usize = 8;
+
+/// Levels matching what is typed in the Map field, as buttons that fill it in. Shown
+/// while the value doesn't name a known level.
+fn map_suggestions(ui: &mut Ui, map: &mut String, levels: &[AvailableLevel]) {
+    let level = map_level_name(map);
+    if level.is_some_and(|name| levels.iter().any(|l| l.name.eq_ignore_ascii_case(name))) {
+        return;
+    }
+    let typed = level.unwrap_or(map.trim());
+    // Compare without case and punctuation, so "gridmapv2" still finds gridmap_v2
+    let simplify = |s: &str| s.chars().filter(|c| c.is_alphanumeric()).collect::<String>().to_lowercase();
+    let typed = simplify(typed);
+    let matches: Vec<&AvailableLevel> = levels
+        .iter()
+        .filter(|l| {
+            let name = simplify(&l.name);
+            typed.is_empty() || name.contains(&typed) || typed.contains(&name)
+        })
+        .take(MAX_MAP_SUGGESTIONS)
+        .collect();
+    if matches.is_empty() {
+        return;
+    }
+
+    ui.horizontal_wrapped(|ui| {
+        ui.weak("Did you mean:");
+        for level in matches {
+            let source = level.mod_name.as_deref().unwrap_or("stock level");
+            if ui.small_button(&level.name).on_hover_text(format!("{}\nfrom {}", level.map_path(), source)).clicked() {
+                *map = level.map_path();
+            }
+        }
+    });
+}
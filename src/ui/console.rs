@@ -0,0 +1,743 @@
+use crate::settings::HighlightRule;
+use egui::text::LayoutJob;
+use egui::{Galley, ScrollArea, Ui};
+use regex::Regex;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+// Above this the console shows a warning; the server is likely logging at debug level
+const HIGH_OUTPUT_RATE: f32 = 200.0;
+const MAX_HISTORY: usize = 100;
+
+/// Text style of the console output. Registered by `register_font` so its size can be
+/// set apart from other monospace text.
+pub fn text_style() -> egui::TextStyle {
+    egui::TextStyle::Name("console".into())
+}
+
+/// Add (or resize) the console text style. `size` None uses the normal monospace size.
+pub fn register_font(ctx: &egui::Context, size: Option<f32>) {
+    ctx.style_mut(|style| {
+        let mut font = egui::TextStyle::Monospace.resolve(style);
+        if let Some(size) = size {
+            font.size = size;
+        }
+        style.text_styles.insert(text_style(), font);
+    });
+}
+
+pub enum ConsoleAction {
+    None,
+    Clear,
+    ToggleDetached,
+    SendCommand(String),
+    ExportLog,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogLevel {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl LogLevel {
+    /// Level of a console line, from its leading `[...]` tags. BeamMP prefixes lines
+    /// with a timestamp tag before the level, so every leading tag is checked.
+    pub fn detect(line: &str) -> Option<Self> {
+        let mut rest = line.trim_start();
+        while let Some(tag) = rest.strip_prefix('[') {
+            let end = tag.find(']')?;
+            let level = match tag[..end].trim().to_ascii_uppercase().as_str() {
+                "ERROR" => Some(Self::Error),
+                "WARN" | "WARNING" => Some(Self::Warn),
+                "INFO" => Some(Self::Info),
+                "DEBUG" => Some(Self::Debug),
+                _ => None,
+            };
+            if level.is_some() {
+                return level;
+            }
+            rest = tag[end + 1..].trim_start();
+        }
+        None
+    }
+
+    /// Display color, or None to keep the theme's text color
+    pub fn color(self) -> Option<egui::Color32> {
+        match self {
+            Self::Error => Some(egui::Color32::from_rgb(230, 80, 80)),
+            Self::Warn => Some(egui::Color32::from_rgb(230, 190, 60)),
+            Self::Info => None,
+            Self::Debug => Some(egui::Color32::GRAY),
+        }
+    }
+}
+
+/// Which console lines are shown. Lines without a level tag (player tables, plugin
+/// prints) are only hidden by the text search, not by the level toggles.
+#[derive(Clone, PartialEq)]
+pub struct ConsoleFilter {
+    pub error: bool,
+    pub warn: bool,
+    pub info: bool,
+    pub debug: bool,
+    pub text: String,
+}
+
+impl Default for ConsoleFilter {
+    fn default() -> Self {
+        Self {
+            error: true,
+            warn: true,
+            info: true,
+            debug: true,
+            text: String::new(),
+        }
+    }
+}
+
+impl ConsoleFilter {
+    fn is_active(&self) -> bool {
+        !(self.error && self.warn && self.info && self.debug && self.text.trim().is_empty())
+    }
+
+    fn level_enabled(&self, level: LogLevel) -> bool {
+        match level {
+            LogLevel::Error => self.error,
+            LogLevel::Warn => self.warn,
+            LogLevel::Info => self.info,
+            LogLevel::Debug => self.debug,
+        }
+    }
+
+    /// Whether `line` passes. `needle` is the search text, trimmed and lowercased once
+    /// for all lines.
+    fn matches(&self, line: &str, needle: &str) -> bool {
+        LogLevel::detect(line).is_none_or(|level| self.level_enabled(level))
+            && (needle.is_empty() || line.to_lowercase().contains(needle))
+    }
+}
+
+/// Numbers the lines of one console buffer across trimming, so work done for a line can be
+/// kept by its number in the whole output rather than by its index, which shifts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineNumbering {
+    /// Different for every buffer, and again after it is cleared
+    source: u64,
+    /// Number of the first line still kept
+    first: u64,
+}
+
+impl LineNumbering {
+    pub fn new() -> Self {
+        static NEXT_SOURCE: AtomicU64 = AtomicU64::new(0);
+        Self {
+            source: NEXT_SOURCE.fetch_add(1, Ordering::Relaxed),
+            first: 0,
+        }
+    }
+
+    /// `count` lines were removed from the front
+    pub fn dropped(&mut self, count: usize) {
+        self.first += count as u64;
+    }
+}
+
+impl Default for LineNumbering {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Lines to show, with the local time each arrived when timestamps are turned on
+#[derive(Clone, Copy, Default)]
+pub struct ConsoleLines<'a> {
+    pub lines: &'a [String],
+    /// Same length as `lines`
+    pub received: Option<&'a [chrono::NaiveTime]>,
+    pub numbering: LineNumbering,
+}
+
+/// What the cached rows were laid out with; any change lays them out again
+#[derive(Clone, PartialEq)]
+struct RowStyle {
+    source: u64,
+    font: egui::FontId,
+    text: egui::Color32,
+    time: egui::Color32,
+    timestamps: bool,
+    highlight_generation: u64,
+}
+
+/// Work kept between frames, so an unchanged console is cheap to draw even with a long
+/// scrollback and an active filter. New lines are filtered as they arrive instead of the
+/// whole buffer every frame, and rows are laid out once while they stay on screen.
+#[derive(Default)]
+struct RenderCache {
+    /// Filter and buffer `visible` was built for
+    filter: Option<(ConsoleFilter, u64)>,
+    /// Numbers of the lines that pass the filter, ascending
+    visible: Vec<u64>,
+    /// Every line numbered below this has been checked against the filter
+    checked_until: u64,
+    style: Option<RowStyle>,
+    /// Rows laid out last frame, by line number
+    rows: HashMap<u64, Arc<Galley>>,
+}
+
+impl RenderCache {
+    /// Bring `visible` up to date with `lines`: drop lines trimmed from the front, check
+    /// the new ones, and start over when the filter or the buffer changed
+    fn update_visible(&mut self, filter: &ConsoleFilter, lines: &[String], numbering: LineNumbering) {
+        let stale = self
+            .filter
+            .as_ref()
+            .is_none_or(|(cached, source)| cached != filter || *source != numbering.source);
+        if stale {
+            self.filter = Some((filter.clone(), numbering.source));
+            self.visible.clear();
+            self.checked_until = numbering.first;
+        }
+
+        let trimmed = self.visible.partition_point(|&number| number < numbering.first);
+        self.visible.drain(..trimmed);
+
+        let needle = filter.text.trim().to_lowercase();
+        let end = numbering.first + lines.len() as u64;
+        for number in self.checked_until.max(numbering.first)..end {
+            if filter.matches(&lines[(number - numbering.first) as usize], &needle) {
+                self.visible.push(number);
+            }
+        }
+        self.checked_until = end;
+    }
+}
+
+/// The highlight rules from Settings, compiled. Rules whose pattern doesn't compile are
+/// left out; the settings page points them out.
+#[derive(Default)]
+pub struct Highlighter {
+    rules: Vec<HighlightRule>,
+    compiled: Vec<(Regex, egui::Color32, bool)>,
+    /// Bumped whenever the rules change, so cached rows are colored again
+    generation: u64,
+}
+
+impl Highlighter {
+    /// Recompile if the rules changed since the last call. Cheap otherwise; call every frame.
+    pub fn update(&mut self, rules: &[HighlightRule]) {
+        if self.rules == rules {
+            return;
+        }
+        self.rules = rules.to_vec();
+        self.generation += 1;
+        self.compiled = rules
+            .iter()
+            .filter(|rule| !rule.pattern.is_empty())
+            .filter_map(|rule| {
+                let [r, g, b] = rule.color;
+                Some((Regex::new(&rule.pattern).ok()?, egui::Color32::from_rgb(r, g, b), rule.notify))
+            })
+            .collect();
+    }
+
+    /// Color of the first rule matching `line`
+    pub fn color(&self, line: &str) -> Option<egui::Color32> {
+        self.compiled.iter().find(|(regex, ..)| regex.is_match(line)).map(|(_, color, _)| *color)
+    }
+
+    /// Whether `line` matches a rule that asks for a desktop notification
+    pub fn notifies(&self, line: &str) -> bool {
+        self.compiled.iter().any(|(regex, _, notify)| *notify && regex.is_match(line))
+    }
+}
+
+/// Display options shared by the docked and popped-out console
+pub struct ConsoleView {
+    /// Follow new output. While on, scrolling up pauses it until the view is back at the bottom.
+    pub auto_scroll: bool,
+    /// The view was scrolled away from the bottom last frame
+    scrolled_up: bool,
+    /// Scroll to the newest line on the next frame
+    jump_to_bottom: bool,
+    /// Show the server's own log file instead of the output of the process the panel started
+    pub server_log: bool,
+    pub highlighter: Highlighter,
+    /// Lines kept per console, from Settings
+    pub scrollback: usize,
+    cache: RenderCache,
+}
+
+impl Default for ConsoleView {
+    fn default() -> Self {
+        Self {
+            auto_scroll: true,
+            scrolled_up: false,
+            jump_to_bottom: false,
+            server_log: false,
+            highlighter: Highlighter::default(),
+            scrollback: 1000,
+            cache: RenderCache::default(),
+        }
+    }
+}
+
+/// Free-form command line under the console, with shell-style history
+#[derive(Default)]
+pub struct ConsoleInput {
+    pub text: String,
+    history: Vec<String>,
+    /// Position while browsing history with Up/Down, None when editing a new command
+    history_pos: Option<usize>,
+}
+
+impl ConsoleInput {
+    /// Record a submitted command, moving repeats to the end instead of storing them twice
+    pub fn push_history(&mut self, command: &str) {
+        self.history.retain(|c| c != command);
+        self.history.push(command.to_string());
+        if self.history.len() > MAX_HISTORY {
+            self.history.remove(0);
+        }
+        self.history_pos = None;
+    }
+
+    fn history_up(&mut self) {
+        if self.history.is_empty() {
+            return;
+        }
+        let pos = match self.history_pos {
+            Some(pos) => pos.saturating_sub(1),
+            None => self.history.len() - 1,
+        };
+        self.history_pos = Some(pos);
+        self.text = self.history[pos].clone();
+    }
+
+    fn history_down(&mut self) {
+        let Some(pos) = self.history_pos else {
+            return;
+        };
+        if pos + 1 < self.history.len() {
+            self.history_pos = Some(pos + 1);
+            self.text = self.history[pos + 1].clone();
+        } else {
+            // Past the newest entry: back to an empty prompt
+            self.history_pos = None;
+            self.text.clear();
+        }
+    }
+}
+
+/// Lines per second of server output, measured over one-second windows
+pub struct OutputRate {
+    window_start: Instant,
+    window_lines: usize,
+    lines_per_sec: f32,
+}
+
+impl Default for OutputRate {
+    fn default() -> Self {
+        Self {
+            window_start: Instant::now(),
+            window_lines: 0,
+            lines_per_sec: 0.0,
+        }
+    }
+}
+
+impl OutputRate {
+    /// Call once per frame with the number of lines read in that frame
+    pub fn record(&mut self, lines: usize) {
+        self.window_lines += lines;
+        let elapsed = self.window_start.elapsed();
+        if elapsed >= Duration::from_secs(1) {
+            self.lines_per_sec = self.window_lines as f32 / elapsed.as_secs_f32();
+            self.window_lines = 0;
+            self.window_start = Instant::now();
+        }
+    }
+
+    pub fn lines_per_sec(&self) -> f32 {
+        self.lines_per_sec
+    }
+}
+
+pub fn show(
+    ui: &mut Ui,
+    console_lines: ConsoleLines,
+    view: &mut ConsoleView,
+    detached: bool,
+    output_rate: &OutputRate,
+    input: &mut ConsoleInput,
+    filter: &mut ConsoleFilter,
+) -> ConsoleAction {
+    let mut action = ConsoleAction::None;
+    let lines = console_lines.lines;
+    let first = console_lines.numbering.first;
+    let cache = &mut view.cache;
+    // Only keep a list of line numbers when something is filtered; the common case reads
+    // `lines` directly
+    let filtered = filter.is_active();
+    if filtered {
+        cache.update_visible(filter, lines, console_lines.numbering);
+    } else {
+        cache.filter = None;
+        cache.visible.clear();
+    }
+    let visible = filtered.then_some(cache.visible.as_slice());
+
+    ui.horizontal(|ui| {
+        ui.heading("Server Console");
+
+        ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+            let detach_label = if detached { "⬇ Dock" } else { "⬈ Pop Out" };
+            if ui.button(detach_label).clicked() {
+                action = ConsoleAction::ToggleDetached;
+            }
+
+            if ui.button("Clear").clicked() {
+                action = ConsoleAction::Clear;
+            }
+
+            if ui.button("Copy All").on_hover_text("Copy the lines shown, as the server wrote them").clicked() {
+                let text = match visible {
+                    Some(visible) => visible
+                        .iter()
+                        .filter_map(|&number| lines.get((number - first) as usize))
+                        .cloned()
+                        .collect::<Vec<_>>()
+                        .join("\n"),
+                    None => lines.join("\n"),
+                };
+                ui.ctx().copy_text(text);
+            }
+
+            if ui.button("Export Log").on_hover_text("Save the full output of this run").clicked() {
+                action = ConsoleAction::ExportLog;
+            }
+
+            let auto_scroll = ui.checkbox(&mut view.auto_scroll, "Auto-scroll")
+                .on_hover_text("Follow new output. Scrolling up pauses it until you scroll back to the bottom.");
+            if auto_scroll.changed() && view.auto_scroll {
+                view.jump_to_bottom = true;
+            }
+            if view.auto_scroll && view.scrolled_up {
+                if ui.small_button("⬇ Jump to Bottom").clicked() {
+                    view.jump_to_bottom = true;
+                }
+                ui.weak("⏸ Paused");
+            }
+            ui.toggle_value(&mut view.server_log, crate::log_tail::SERVER_LOG_FILE).on_hover_text(
+                "Show the server's own log file instead of its console output. Also works for a server started outside the panel.",
+            );
+
+            let rate = output_rate.lines_per_sec();
+            if rate > HIGH_OUTPUT_RATE {
+                ui.colored_label(egui::Color32::YELLOW, format!("⚠ {:.0} lines/s", rate))
+                    .on_hover_text(format!(
+                        "Very heavy output. Only the last {} lines are kept; consider turning off Debug logging in the server config.",
+                        view.scrollback
+                    ));
+            }
+        });
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Show:");
+        for (enabled, label, level) in [
+            (&mut filter.error, "Error", LogLevel::Error),
+            (&mut filter.warn, "Warn", LogLevel::Warn),
+            (&mut filter.info, "Info", LogLevel::Info),
+            (&mut filter.debug, "Debug", LogLevel::Debug),
+        ] {
+            let mut text = egui::RichText::new(label);
+            if let Some(color) = level.color() {
+                text = text.color(color);
+            }
+            ui.toggle_value(enabled, text);
+        }
+
+        ui.separator();
+        ui.add(egui::TextEdit::singleline(&mut filter.text).hint_text("Search output").desired_width(200.0));
+        if !filter.text.is_empty() && ui.small_button("✖").on_hover_text("Clear search").clicked() {
+            filter.text.clear();
+        }
+
+        if let Some(visible) = visible {
+            ui.label(format!("Showing {} of {}", visible.len(), lines.len()));
+        }
+    });
+
+    ui.separator();
+
+    egui::TopBottomPanel::bottom("console_input").show_inside(ui, |ui| {
+        ui.horizontal(|ui| {
+            ui.label(">");
+            let edit = egui::TextEdit::singleline(&mut input.text)
+                .hint_text("Type a server command, Up/Down for history")
+                .font(egui::TextStyle::Monospace)
+                .desired_width(ui.available_width() - 60.0);
+            let output = edit.show(ui);
+            let response = output.response;
+
+            if response.has_focus() {
+                let (up, down) = ui.input(|i| (i.key_pressed(egui::Key::ArrowUp), i.key_pressed(egui::Key::ArrowDown)));
+                if up || down {
+                    if up {
+                        input.history_up();
+                    } else {
+                        input.history_down();
+                    }
+                    // Put the cursor after the recalled command
+                    let mut state = output.state;
+                    let end = egui::text::CCursor::new(input.text.chars().count());
+                    state.cursor.set_char_range(Some(egui::text::CCursorRange::one(end)));
+                    state.store(ui.ctx(), response.id);
+                }
+            }
+
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (submitted || ui.button("Send").clicked()) && !input.text.trim().is_empty() {
+                action = ConsoleAction::SendCommand(input.text.trim().to_string());
+                input.text.clear();
+                response.request_focus();
+            }
+        });
+    });
+
+    let text_style = text_style();
+    let row_height = ui.text_style_height(&text_style);
+    let style = RowStyle {
+        source: console_lines.numbering.source,
+        font: text_style.resolve(ui.style()),
+        text: ui.visuals().text_color(),
+        time: ui.visuals().weak_text_color(),
+        timestamps: console_lines.received.is_some(),
+        highlight_generation: view.highlighter.generation,
+    };
+    if cache.style.as_ref() != Some(&style) {
+        cache.rows.clear();
+        cache.style = Some(style.clone());
+    }
+
+    // Lines never wrap: every row then has exactly the height show_rows assumes, and only
+    // the visible rows are laid out. Long lines scroll horizontally instead.
+    let row_count = visible.map_or(lines.len(), <[u64]>::len);
+    let mut scroll_area = ScrollArea::both()
+        .auto_shrink([false, false])
+        .stick_to_bottom(view.auto_scroll);
+    if std::mem::take(&mut view.jump_to_bottom) {
+        // Clamped to the end of the content
+        scroll_area = scroll_area.vertical_scroll_offset(f32::MAX);
+    }
+    let output = scroll_area
+        .show_rows(ui, row_height, row_count, |ui, row_range| {
+            let mut rows = HashMap::with_capacity(row_range.len());
+            for row in row_range {
+                let number = match visible {
+                    Some(visible) => visible[row],
+                    None => first + row as u64,
+                };
+                let index = (number - first) as usize;
+                if let Some(line) = lines.get(index) {
+                    let galley = cache.rows.remove(&number).unwrap_or_else(|| {
+                        let time = console_lines.received.and_then(|times| times.get(index));
+                        layout_row(ui, line, time, &style, &view.highlighter)
+                    });
+                    // Selectable, also across lines, to copy part of the output
+                    ui.add(egui::Label::new(galley.clone()).selectable(true))
+                        .context_menu(|ui| {
+                            if ui.button("Copy Line").clicked() {
+                                ui.ctx().copy_text(line.clone());
+                                ui.close_menu();
+                            }
+                        });
+                    rows.insert(number, galley);
+                }
+            }
+            // Rows scrolled out of view are laid out again if they come back
+            cache.rows = rows;
+        });
+
+    // egui stops following the bottom when the user scrolls up and picks it up again at the
+    // bottom; this only tracks where the view is, to say so above the output
+    let max_offset = output.content_size.y - output.inner_rect.height();
+    view.scrolled_up = output.state.offset.y < max_offset - row_height / 2.0;
+
+    action
+}
+
+/// One console row as a single unwrapped line: long lines scroll horizontally instead
+fn layout_row(ui: &Ui, line: &str, time: Option<&chrono::NaiveTime>, style: &RowStyle, highlighter: &Highlighter) -> Arc<Galley> {
+    let color = LogLevel::detect(line).and_then(LogLevel::color).unwrap_or(style.text);
+    let mut job = LayoutJob::default();
+    // Monospace and fixed width, so the text after it lines up
+    if let Some(time) = time {
+        let format = egui::TextFormat::simple(style.font.clone(), style.time);
+        job.append(&time.format("%H:%M:%S%.3f ").to_string(), 0.0, format);
+    }
+    let mut format = egui::TextFormat::simple(style.font.clone(), color);
+    // Behind the text, so the level color still shows
+    if let Some(highlight) = highlighter.color(line) {
+        format.background = highlight.gamma_multiply(0.35);
+    }
+    job.append(line, 0.0, format);
+    ui.fonts(|fonts| fonts.layout_job(job))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn search(text: &str) -> ConsoleFilter {
+        ConsoleFilter {
+            text: text.to_string(),
+            ..ConsoleFilter::default()
+        }
+    }
+
+    fn lines(texts: &[&str]) -> Vec<String> {
+        texts.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn detects_level_of_server_lines() {
+        let cases = [
+            ("[16/03/24 12:00:00] [INFO] Vehicle 0 spawned", Some(LogLevel::Info)),
+            ("[16/03/24 12:00:00] [WARN] Lua reload failed", Some(LogLevel::Warn)),
+            ("[16/03/24 12:00:00] [WARNING] Port is in use", Some(LogLevel::Warn)),
+            ("[16/03/24 12:00:00] [ERROR] Failed to authenticate", Some(LogLevel::Error)),
+            ("[16/03/24 12:00:00] [DEBUG] heartbeat sent", Some(LogLevel::Debug)),
+            ("[16/03/24 12:00:00] [LUA] [info] from a plugin", Some(LogLevel::Info)),
+            ("[16/03/24 12:00:00] [CHAT] (0) <Player> hello [ERROR]", None),
+            ("Name                     ID    Cars", None),
+            ("INFO without brackets", None),
+            ("", None),
+        ];
+        for (line, level) in cases {
+            assert_eq!(LogLevel::detect(line), level, "{:?}", line);
+        }
+    }
+
+    #[test]
+    fn filter_ignores_case_and_hidden_levels() {
+        let buffer = lines(&[
+            "[16/03/24 12:00:00] [INFO] A Fox",
+            "[16/03/24 12:00:00] [DEBUG] fox debug",
+            "untagged FOX",
+            "[16/03/24 12:00:00] [INFO] a dog",
+        ]);
+        let mut cache = RenderCache::default();
+        let numbering = LineNumbering::new();
+        let mut filter = search("  fOx ");
+        cache.update_visible(&filter, &buffer, numbering);
+        assert_eq!(cache.visible, [0, 1, 2]);
+
+        // Untagged lines stay, only the search hides them
+        filter.debug = false;
+        cache.update_visible(&filter, &buffer, numbering);
+        assert_eq!(cache.visible, [0, 2]);
+    }
+
+    #[test]
+    fn filters_new_lines_and_forgets_trimmed_ones() {
+        let mut cache = RenderCache::default();
+        let filter = search("fox");
+        let mut numbering = LineNumbering::new();
+        let mut buffer = lines(&["a fox", "a dog", "another fox"]);
+        cache.update_visible(&filter, &buffer, numbering);
+        assert_eq!(cache.visible, [0, 2]);
+
+        // Two lines trimmed from the front, two appended
+        buffer.drain(..2);
+        numbering.dropped(2);
+        buffer.extend(lines(&["no match", "last fox"]));
+        cache.update_visible(&filter, &buffer, numbering);
+        assert_eq!(cache.visible, [2, 4]);
+    }
+
+    #[test]
+    fn starts_over_when_filter_or_buffer_changes() {
+        let mut cache = RenderCache::default();
+        let buffer = lines(&["a fox", "a dog"]);
+        let numbering = LineNumbering::new();
+        cache.update_visible(&search("fox"), &buffer, numbering);
+        assert_eq!(cache.visible, [0]);
+
+        cache.update_visible(&search("dog"), &buffer, numbering);
+        assert_eq!(cache.visible, [1]);
+
+        // A cleared buffer gets a new numbering that starts at 0 again
+        let buffer = lines(&["a dog", "a fox"]);
+        cache.update_visible(&search("dog"), &buffer, LineNumbering::new());
+        assert_eq!(cache.visible, [0]);
+    }
+
+    /// Average time to draw one frame of a 50k line console in a 1200x800 window, with
+    /// `grow` appending 10 lines (and trimming 10) per frame
+    fn frame_time(filter_text: &str, grow: bool) -> Duration {
+        const LINES: usize = 50_000;
+        const FRAMES: u32 = 100;
+        const WARMUP: u32 = 5;
+
+        let ctx = egui::Context::default();
+        register_font(&ctx, None);
+        let mut buffer: Vec<String> = (0..LINES)
+            .map(|i| {
+                let word = if i % 7 == 0 { "jumps" } else { "sleeps" };
+                format!("[16/03/24 12:00:00] [INFO] line {} the quick brown fox {}", i, word)
+            })
+            .collect();
+        let mut numbering = LineNumbering::new();
+        let mut view = ConsoleView {
+            scrollback: LINES,
+            ..ConsoleView::default()
+        };
+        let mut input = ConsoleInput::default();
+        let mut filter = search(filter_text);
+        let rate = OutputRate::default();
+
+        let mut total = Duration::ZERO;
+        for frame in 0..FRAMES {
+            if grow {
+                buffer.drain(..10);
+                numbering.dropped(10);
+                buffer.extend((0..10).map(|i| format!("[INFO] frame {} line {} jumps", frame, i)));
+            }
+            let raw = egui::RawInput {
+                screen_rect: Some(egui::Rect::from_min_size(egui::Pos2::ZERO, egui::vec2(1200.0, 800.0))),
+                ..Default::default()
+            };
+            let start = Instant::now();
+            let _ = ctx.run(raw, |ctx| {
+                egui::CentralPanel::default().show(ctx, |ui| {
+                    let console_lines = ConsoleLines { lines: &buffer, received: None, numbering };
+                    show(ui, console_lines, &mut view, false, &rate, &mut input, &mut filter);
+                });
+            });
+            if frame >= WARMUP {
+                total += start.elapsed();
+            }
+        }
+        if filter.is_active() {
+            assert_eq!(view.cache.visible.len(), buffer.iter().filter(|l| l.contains(filter_text)).count());
+        }
+        total / (FRAMES - WARMUP)
+    }
+
+    /// Stress test for console drawing. Run with
+    /// `cargo test --release console_frame_time -- --ignored --nocapture`
+    #[test]
+    #[ignore]
+    fn console_frame_time() {
+        for (filter_text, grow) in [("", false), ("", true), ("jumps", false), ("jumps", true)] {
+            let time = frame_time(filter_text, grow);
+            println!("filter {:?}, growing {}: {:.3} ms/frame", filter_text, grow, time.as_secs_f64() * 1000.0);
+        }
+    }
+}
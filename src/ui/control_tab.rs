@@ -1,19 +1,72 @@
+use crate::players::{ChatLog, PlayerHistory};
+use crate::process::{ResourceUsage, USAGE_HISTORY_LEN};
+use crate::server::CommandMacro;
 use egui::{ScrollArea, Ui};
+use std::collections::VecDeque;
+use std::time::Duration;
 
 pub enum ControlAction {
     None,
     SendCommand(String),
     RefreshPlayers,
+    KickPlayer(String),
+    BanPlayer(String),
+    /// Send a chat message only the named player sees
+    WhisperPlayer { name: String, message: String },
+    ExportPlayerHistory,
+    /// The server's broadcast sender label was edited and should be saved
+    BroadcastPrefixChanged,
+    RestartWithWarning,
+    CancelRestart,
+    /// Run Lua code in the given state of the server's Lua console
+    RunLua { state: String, code: String },
+    /// Leave the Lua console, so commands reach the server console again
+    ExitLua,
+    EditMacros,
+}
+
+/// Text typed into the Control tab, kept while switching tabs
+#[derive(Default)]
+pub struct ControlInputs {
+    pub kick_player_name: String,
+    pub kick_reason: String,
+    pub broadcast_message: String,
+    /// Player a broadcast goes to privately; `None` for everyone
+    pub broadcast_to: Option<String>,
+    pub chat_message: String,
+    /// Plugin whose Lua state the code runs in; empty for the server's own
+    pub lua_state: String,
+    pub lua_code: String,
+    /// Player picked for "Whisper..." and the message being typed
+    pub whisper: Option<(String, String)>,
+    /// Player Management shows the joins and leaves of this run instead of who's online
+    pub show_player_history: bool,
+}
+
+/// Details of the running server process shown in the header
+pub struct ProcessInfo<'a> {
+    pub uptime: Duration,
+    pub pid: u32,
+    /// CPU and memory samples, oldest first; empty until the first sample is taken
+    pub usage_history: &'a VecDeque<ResourceUsage>,
+    /// Time left of a restart countdown players have been told about
+    pub restart_countdown: Option<Duration>,
+    /// Console input currently goes to the Lua console
+    pub in_lua_console: bool,
+    pub player_history: &'a PlayerHistory,
+    pub chat: &'a ChatLog,
 }
 
 pub fn show(
     ui: &mut Ui,
-    is_server_running: bool,
-    player_list: &mut Vec<String>,
-    kick_player_name: &mut String,
-    kick_reason: &mut String,
-    broadcast_message: &mut String,
+    process_info: Option<ProcessInfo<'_>>,
+    is_server_ready: bool,
+    player_list: &mut [String],
+    inputs: &mut ControlInputs,
+    macros: &[CommandMacro],
+    broadcast_prefix: &mut String,
 ) -> ControlAction {
+    let is_server_running = process_info.is_some();
     if !is_server_running {
         ui.vertical_centered(|ui| {
             ui.add_space(50.0);
@@ -26,15 +79,54 @@ pub fn show(
 
     let mut action = ControlAction::None;
 
+    if !is_server_ready {
+        ui.horizontal(|ui| {
+            ui.spinner();
+            ui.label("Server is starting, controls unlock once it is ready...");
+        });
+        ui.separator();
+        // Commands sent before the server is listening just produce confusing errors
+        ui.disable();
+    }
+
     ScrollArea::vertical().show(ui, |ui| {
-        ui.heading("Server Control Panel");
+        ui.horizontal(|ui| {
+            ui.heading("Server Control Panel");
+            if let Some(info) = &process_info {
+                ui.separator();
+                ui.label(format!("Uptime: {}", super::format_duration(info.uptime)));
+                ui.separator();
+                ui.label(format!("PID: {}", info.pid));
+                if let Some(usage) = info.usage_history.back() {
+                    ui.separator();
+                    ui.label(format!("CPU: {:.1}%", usage.cpu_percent))
+                        .on_hover_text("Share of all CPU cores, sampled once per second");
+                    cpu_graph(ui, info.usage_history);
+                    ui.separator();
+                    ui.label(format!("Memory: {}", super::format_bytes(usage.memory_bytes)));
+                }
+            }
+        });
         ui.separator();
 
         // Player Management Section
         ui.group(|ui| {
-            ui.heading("Player Management");
+            ui.horizontal(|ui| {
+                ui.heading("Player Management");
+                ui.separator();
+                ui.selectable_value(&mut inputs.show_player_history, false, "Online");
+                ui.selectable_value(&mut inputs.show_player_history, true, "History")
+                    .on_hover_text("Who joined and left since the server started");
+            });
             ui.add_space(5.0);
 
+            if let Some(info) = process_info.as_ref().filter(|_| inputs.show_player_history) {
+                if player_history(ui, info.player_history) {
+                    action = ControlAction::ExportPlayerHistory;
+                }
+                return;
+            }
+
             ui.horizontal(|ui| {
                 if ui.button("🔄 Refresh Player List").clicked() {
                     action = ControlAction::RefreshPlayers;
@@ -46,15 +138,71 @@ pub fn show(
             ui.add_space(5.0);
 
             if player_list.is_empty() {
-                ui.label("Click 'Refresh Player List' to see connected players");
+                ui.label("No players connected. Click 'Refresh Player List' to update.");
             } else {
                 ui.label("Connected Players:");
                 ui.indent("player_list", |ui| {
                     for player in player_list.iter() {
-                        ui.label(format!("• {}", player));
+                        ui.horizontal(|ui| {
+                            ui.add(egui::Label::new(format!("• {}", player)).sense(egui::Sense::click()))
+                                .on_hover_text("Right-click for more")
+                                .context_menu(|ui| {
+                                    if ui.button("Kick").clicked() {
+                                        action = ControlAction::KickPlayer(player.clone());
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("Ban").clicked() {
+                                        action = ControlAction::BanPlayer(player.clone());
+                                        ui.close_menu();
+                                    }
+                                    if ui.button("Whisper...").clicked() {
+                                        inputs.whisper = Some((player.clone(), String::new()));
+                                        ui.close_menu();
+                                    }
+                                    ui.separator();
+                                    if ui.button("Copy Name").clicked() {
+                                        ui.ctx().copy_text(player.clone());
+                                        ui.close_menu();
+                                    }
+                                });
+                            if ui.add_enabled(is_server_running, egui::Button::new("Kick")).clicked() {
+                                action = ControlAction::KickPlayer(player.clone());
+                            }
+                            if ui
+                                .add_enabled(is_server_running, egui::Button::new("Ban"))
+                                .on_hover_text("BeamMP-Server has no built-in ban command; this needs a plugin that provides `ban`")
+                                .clicked()
+                            {
+                                action = ControlAction::BanPlayer(player.clone());
+                            }
+                        });
+                    }
+                });
+            }
+
+            // The player may have left since "Whisper..." was picked
+            if inputs.whisper.as_ref().is_some_and(|(name, _)| !player_list.contains(name)) {
+                inputs.whisper = None;
+            }
+            let mut close_whisper = false;
+            if let Some((name, message)) = &mut inputs.whisper {
+                ui.add_space(5.0);
+                ui.horizontal(|ui| {
+                    ui.label(format!("Whisper to {}:", name));
+                    let response = ui.text_edit_singleline(message);
+                    let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+                    if (submitted || ui.button("Send").clicked()) && !message.trim().is_empty() {
+                        action = ControlAction::WhisperPlayer { name: name.clone(), message: message.trim().to_string() };
+                        close_whisper = true;
+                    }
+                    if ui.button("Cancel").clicked() {
+                        close_whisper = true;
                     }
                 });
             }
+            if close_whisper {
+                inputs.whisper = None;
+            }
         });
 
         ui.add_space(10.0);
@@ -66,50 +214,89 @@ pub fn show(
 
             ui.horizontal(|ui| {
                 ui.label("Player Name:");
-                ui.text_edit_singleline(kick_player_name);
+                ui.text_edit_singleline(&mut inputs.kick_player_name);
             });
 
             ui.horizontal(|ui| {
                 ui.label("Reason (optional):");
-                ui.text_edit_singleline(kick_reason);
+                ui.text_edit_singleline(&mut inputs.kick_reason);
             });
 
             ui.horizontal(|ui| {
-                if ui.button("⚠ Kick Player").clicked() {
-                    if !kick_player_name.is_empty() {
-                        let cmd = if kick_reason.is_empty() {
-                            format!("kick {}", kick_player_name)
-                        } else {
-                            format!("kick {} {}", kick_player_name, kick_reason)
-                        };
-                        action = ControlAction::SendCommand(cmd);
-                        kick_player_name.clear();
-                        kick_reason.clear();
-                    }
+                if ui.button("⚠ Kick Player").clicked() && !inputs.kick_player_name.is_empty() {
+                    let cmd = if inputs.kick_reason.is_empty() {
+                        format!("kick {}", inputs.kick_player_name)
+                    } else {
+                        format!("kick {} {}", inputs.kick_player_name, inputs.kick_reason)
+                    };
+                    action = ControlAction::SendCommand(cmd);
+                    inputs.kick_player_name.clear();
+                    inputs.kick_reason.clear();
                 }
 
                 if ui.button("Clear").clicked() {
-                    kick_player_name.clear();
-                    kick_reason.clear();
+                    inputs.kick_player_name.clear();
+                    inputs.kick_reason.clear();
                 }
             });
         });
 
         ui.add_space(10.0);
 
+        if let Some(info) = &process_info {
+            if let Some(message) = chat(ui, info.chat, &mut inputs.chat_message) {
+                action = ControlAction::SendCommand(format!("say {}", with_prefix(broadcast_prefix, &message)));
+            }
+            ui.add_space(10.0);
+        }
+
         // Chat/Broadcast Section
         ui.group(|ui| {
             ui.heading("Broadcast Message");
             ui.add_space(5.0);
 
-            ui.label("Message to all players:");
-            ui.text_edit_singleline(broadcast_message);
+            // A player who left can't be messaged anymore
+            if inputs.broadcast_to.as_ref().is_some_and(|name| !player_list.contains(name)) {
+                inputs.broadcast_to = None;
+            }
 
-            if ui.button("📢 Send Message").clicked() {
-                if !broadcast_message.is_empty() {
-                    action = ControlAction::SendCommand(format!("say {}", broadcast_message));
-                    broadcast_message.clear();
+            egui::Grid::new("broadcast").num_columns(2).show(ui, |ui| {
+                ui.label("Sender:");
+                let response = ui
+                    .add(egui::TextEdit::singleline(broadcast_prefix).hint_text("[Admin]").desired_width(120.0))
+                    .on_hover_text("Put before every message sent from this tab, so players know it's from staff. Saved for this server.");
+                if response.changed() {
+                    action = ControlAction::BroadcastPrefixChanged;
                 }
+                ui.end_row();
+
+                ui.label("To:");
+                egui::ComboBox::from_id_salt("broadcast_to")
+                    .selected_text(inputs.broadcast_to.as_deref().unwrap_or("All players"))
+                    .show_ui(ui, |ui| {
+                        ui.selectable_value(&mut inputs.broadcast_to, None, "All players");
+                        for name in player_list.iter() {
+                            ui.selectable_value(&mut inputs.broadcast_to, Some(name.clone()), name);
+                        }
+                    });
+                ui.end_row();
+
+                ui.label("Message:");
+                ui.text_edit_singleline(&mut inputs.broadcast_message);
+                ui.end_row();
+            });
+
+            let preview = with_prefix(broadcast_prefix, &inputs.broadcast_message);
+            if !inputs.broadcast_message.is_empty() {
+                ui.weak(format!("Players see: {}", preview));
+            }
+            let button = if inputs.broadcast_to.is_some() { "✉ Send Privately" } else { "📢 Send Message" };
+            if ui.button(button).clicked() && !inputs.broadcast_message.is_empty() {
+                action = match &inputs.broadcast_to {
+                    Some(name) => ControlAction::WhisperPlayer { name: name.clone(), message: preview },
+                    None => ControlAction::SendCommand(format!("say {}", preview)),
+                };
+                inputs.broadcast_message.clear();
             }
         });
 
@@ -139,20 +326,97 @@ pub fn show(
                     action = ControlAction::SendCommand("help".to_string());
                 }
             });
+
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                match process_info.as_ref().and_then(|info| info.restart_countdown) {
+                    Some(left) => {
+                        ui.spinner();
+                        ui.label(format!("Restarting in {}", super::format_duration(left)));
+                        if ui.button("Cancel Restart").clicked() {
+                            action = ControlAction::CancelRestart;
+                        }
+                    }
+                    None => {
+                        if ui
+                            .button("🔁 Restart with Warning")
+                            .on_hover_text("Count down in chat, then restart the server. The countdown is set in Settings.")
+                            .clicked()
+                        {
+                            action = ControlAction::RestartWithWarning;
+                        }
+                    }
+                }
+            });
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("Macros");
+            ui.add_space(5.0);
+
+            if macros.is_empty() {
+                ui.label("Buttons for commands you use often, such as your plugins' own commands.");
+            }
+            ui.horizontal_wrapped(|ui| {
+                for m in macros {
+                    if ui.button(&m.label).on_hover_text(&m.command).clicked() {
+                        action = ControlAction::SendCommand(m.command.clone());
+                    }
+                }
+                if ui.button("✏ Edit Macros...").clicked() {
+                    action = ControlAction::EditMacros;
+                }
+            });
         });
 
         ui.add_space(10.0);
 
         // Lua Console Section
         ui.group(|ui| {
-            ui.heading("Advanced");
+            ui.heading("🔧 Lua Console");
             ui.add_space(5.0);
 
-            if ui.button("🔧 Lua Console").clicked() {
-                action = ControlAction::SendCommand("lua".to_string());
+            if process_info.as_ref().is_some_and(|info| info.in_lua_console) {
+                ui.horizontal(|ui| {
+                    ui.colored_label(egui::Color32::YELLOW, "The console input is in Lua mode.");
+                    if ui.button("Exit Lua Console").on_hover_text("Sends exit()").clicked() {
+                        action = ControlAction::ExitLua;
+                    }
+                });
             }
 
-            ui.label("Opens the Lua interactive console");
+            ui.horizontal(|ui| {
+                ui.label("State:");
+                ui.add(egui::TextEdit::singleline(&mut inputs.lua_state).hint_text("server").desired_width(160.0))
+                    .on_hover_text("Name of the plugin whose Lua state runs the code; leave empty for the server's own");
+            });
+            ui.add(
+                egui::TextEdit::multiline(&mut inputs.lua_code)
+                    .code_editor()
+                    .desired_rows(5)
+                    .desired_width(f32::INFINITY)
+                    .hint_text("print(MP.GetPlayerCount())"),
+            );
+            ui.horizontal(|ui| {
+                let run_shortcut = egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Enter);
+                let run = ui
+                    .add_enabled(!inputs.lua_code.trim().is_empty(), egui::Button::new("▶ Run"))
+                    .on_hover_text(format!("Run the code ({})", ui.ctx().format_shortcut(&run_shortcut)))
+                    .clicked()
+                    || (!inputs.lua_code.trim().is_empty() && ui.input_mut(|i| i.consume_shortcut(&run_shortcut)));
+                if run {
+                    action = ControlAction::RunLua {
+                        state: inputs.lua_state.clone(),
+                        code: inputs.lua_code.clone(),
+                    };
+                }
+                if ui.button("Clear").clicked() {
+                    inputs.lua_code.clear();
+                }
+            });
+            ui.label("Runs in the server's Lua console and returns to the normal console afterwards. Output appears in the console below.");
         });
 
         ui.add_space(10.0);
@@ -164,10 +428,204 @@ pub fn show(
             
             ui.label("All commands are executed in the server console.");
             ui.label("Output will appear in the Server Console panel below.");
-            ui.label("Note: Player list parsing is basic - check console for full details.");
         });
     });
     
     action
 }
 
+/// The "Edit Macros" window: a working copy of one server's macros
+pub struct MacroEditor {
+    pub server_id: String,
+    pub macros: Vec<CommandMacro>,
+}
+
+impl MacroEditor {
+    pub fn new(server_id: String, macros: &[CommandMacro]) -> Self {
+        Self { server_id, macros: macros.to_vec() }
+    }
+
+    /// Macros to save: rows without a command are dropped, and an empty label shows the command
+    pub fn finished(self) -> Vec<CommandMacro> {
+        self.macros
+            .into_iter()
+            .filter(|m| !m.command.trim().is_empty())
+            .map(|m| CommandMacro {
+                label: if m.label.trim().is_empty() { m.command.trim().to_string() } else { m.label.trim().to_string() },
+                command: m.command.trim().to_string(),
+            })
+            .collect()
+    }
+}
+
+pub enum MacroEditorAction {
+    None,
+    Save,
+    Cancel,
+}
+
+pub fn show_macro_editor(ui: &mut Ui, editor: &mut MacroEditor) -> MacroEditorAction {
+    let mut action = MacroEditorAction::None;
+    let count = editor.macros.len();
+    let mut move_up = None;
+    let mut remove = None;
+
+    if count == 0 {
+        ui.label("No macros yet.");
+    } else {
+        egui::Grid::new("macro_editor").num_columns(3).show(ui, |ui| {
+            ui.strong("Label");
+            ui.strong("Command");
+            ui.end_row();
+            for (i, m) in editor.macros.iter_mut().enumerate() {
+                ui.add(egui::TextEdit::singleline(&mut m.label).desired_width(120.0).hint_text("Button text"));
+                ui.add(egui::TextEdit::singleline(&mut m.command).desired_width(220.0).code_editor());
+                ui.horizontal(|ui| {
+                    if ui.add_enabled(i > 0, egui::Button::new("⬆").small()).on_hover_text("Move up").clicked() {
+                        move_up = Some(i);
+                    }
+                    if ui.add_enabled(i + 1 < count, egui::Button::new("⬇").small()).on_hover_text("Move down").clicked() {
+                        move_up = Some(i + 1);
+                    }
+                    if ui.small_button("✖").on_hover_text("Remove").clicked() {
+                        remove = Some(i);
+                    }
+                });
+                ui.end_row();
+            }
+        });
+    }
+    if let Some(i) = move_up {
+        editor.macros.swap(i - 1, i);
+    }
+    if let Some(i) = remove {
+        editor.macros.remove(i);
+    }
+    if ui.button("+ Add Macro").clicked() {
+        editor.macros.push(CommandMacro { label: String::new(), command: String::new() });
+    }
+    ui.label("Each command is sent to the server console as if typed there. Rows without a command are dropped.");
+
+    ui.separator();
+    ui.horizontal(|ui| {
+        if ui.button("Save").clicked() {
+            action = MacroEditorAction::Save;
+        }
+        if ui.button("Cancel").clicked() || ui.input(|i| i.key_pressed(egui::Key::Escape)) {
+            action = MacroEditorAction::Cancel;
+        }
+    });
+    action
+}
+
+/// `message` with the sender label in front, if there is one
+fn with_prefix(prefix: &str, message: &str) -> String {
+    match prefix.trim() {
+        "" => message.to_string(),
+        prefix => format!("{} {}", prefix, message),
+    }
+}
+
+/// The chat of this run apart from the rest of the console, with a box to answer in chat.
+/// Returns a message to send.
+fn chat(ui: &mut Ui, log: &ChatLog, message: &mut String) -> Option<String> {
+    let mut send = None;
+    ui.group(|ui| {
+        ui.heading("Chat");
+        ui.add_space(5.0);
+
+        if log.messages.is_empty() {
+            ui.label("No chat yet. Chat shows up here when Log Chat is on in the server config.");
+        } else {
+            ScrollArea::vertical()
+                .id_salt("chat")
+                .max_height(200.0)
+                .auto_shrink([false, true])
+                .stick_to_bottom(true)
+                .show(ui, |ui| {
+                    for (at, line) in &log.messages {
+                        ui.horizontal_wrapped(|ui| {
+                            ui.weak(at.format("%H:%M:%S").to_string());
+                            if !line.sender.is_empty() {
+                                ui.strong(format!("{}:", line.sender));
+                            }
+                            ui.label(&line.text);
+                        });
+                    }
+                });
+        }
+
+        ui.horizontal(|ui| {
+            let response = ui.add(egui::TextEdit::singleline(message).hint_text("Message to all players"));
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if (submitted || ui.button("Send").clicked()) && !message.trim().is_empty() {
+                send = Some(message.trim().to_string());
+                message.clear();
+                response.request_focus();
+            }
+        });
+    });
+    send
+}
+
+/// Joins and leaves of this run, newest first. Returns whether "Export CSV" was clicked.
+fn player_history(ui: &mut Ui, history: &PlayerHistory) -> bool {
+    let mut export = false;
+    ui.horizontal(|ui| {
+        let joins = history.entries.iter().filter(|e| e.joined).count();
+        ui.label(format!(
+            "{} unique player(s), {} join(s), {} leave(s) this session",
+            history.unique_players(),
+            joins,
+            history.entries.len() - joins
+        ));
+        if ui.add_enabled(!history.entries.is_empty(), egui::Button::new("Export CSV...")).clicked() {
+            export = true;
+        }
+    });
+
+    if history.entries.is_empty() {
+        ui.label("Nobody has joined or left since the server started.");
+        return export;
+    }
+    ScrollArea::vertical().id_salt("player_history").max_height(200.0).show(ui, |ui| {
+        for entry in history.entries.iter().rev() {
+            ui.horizontal(|ui| {
+                ui.weak(entry.at.format("%H:%M:%S").to_string());
+                if entry.joined {
+                    ui.colored_label(egui::Color32::GREEN, "➡ Joined");
+                } else {
+                    ui.colored_label(egui::Color32::GRAY, "⬅ Left");
+                }
+                ui.label(&entry.name);
+            });
+        }
+    });
+    export
+}
+
+/// Small CPU usage graph over the last minute, scaled to the busiest sample
+fn cpu_graph(ui: &mut Ui, history: &VecDeque<ResourceUsage>) {
+    let (rect, response) = ui.allocate_exact_size(egui::vec2(90.0, 18.0), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 2.0, ui.visuals().extreme_bg_color);
+
+    let peak = history.iter().map(|u| u.cpu_percent).fold(0.0_f32, f32::max);
+    // Never scale below 5%, so an idle server doesn't look busy
+    let scale = peak.clamp(5.0, 100.0);
+    let step = rect.width() / (USAGE_HISTORY_LEN - 1) as f32;
+    // Newest sample on the right edge
+    let offset = (USAGE_HISTORY_LEN - history.len()) as f32 * step;
+    let points: Vec<egui::Pos2> = history
+        .iter()
+        .enumerate()
+        .map(|(i, u)| {
+            let x = rect.left() + offset + i as f32 * step;
+            let y = rect.bottom() - (u.cpu_percent / scale).min(1.0) * rect.height();
+            egui::pos2(x, y)
+        })
+        .collect();
+    painter.add(egui::Shape::line(points, egui::Stroke::new(1.0, ui.visuals().selection.bg_fill)));
+
+    response.on_hover_text(format!("CPU over the last minute (peak {:.1}%)", peak));
+}
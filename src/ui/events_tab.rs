@@ -0,0 +1,90 @@
+use crate::events::{EventKind, ServerEvent};
+use crate::server::ServerEntry;
+use crate::EventFilter;
+use egui::{ScrollArea, Ui};
+use std::time::Duration;
+
+fn kind_color(kind: EventKind) -> egui::Color32 {
+    match kind {
+        EventKind::Started => egui::Color32::GREEN,
+        EventKind::Stopped => egui::Color32::GRAY,
+        EventKind::Crashed => egui::Color32::RED,
+        EventKind::ConfigApplied => egui::Color32::from_rgb(100, 200, 255),
+        EventKind::ModChanged => egui::Color32::from_rgb(255, 180, 100),
+        EventKind::Updated => egui::Color32::from_rgb(180, 140, 255),
+        EventKind::PlayerJoined => egui::Color32::from_rgb(120, 220, 160),
+        EventKind::PlayerLeft => egui::Color32::from_rgb(160, 160, 120),
+    }
+}
+
+pub fn show(
+    ui: &mut Ui,
+    server: &ServerEntry,
+    current_run: Option<Duration>,
+    events: &[ServerEvent],
+    filter: &mut EventFilter,
+) {
+    ui.horizontal(|ui| {
+        let last_started = server
+            .last_started
+            .map(super::format_timestamp)
+            .unwrap_or_else(|| "Never".to_string());
+        ui.label(format!("Last started: {}", last_started));
+        ui.separator();
+
+        let total = Duration::from_secs(server.total_runtime_secs) + current_run.unwrap_or_default();
+        ui.label(format!("Total runtime: {}", super::format_duration(total)));
+    });
+    ui.separator();
+
+    ui.horizontal(|ui| {
+        ui.label("Search:");
+        ui.text_edit_singleline(&mut filter.search);
+        if !filter.search.is_empty() && ui.button("✖").clicked() {
+            filter.search.clear();
+        }
+    });
+
+    ui.horizontal(|ui| {
+        ui.label("Show:");
+        for kind in EventKind::ALL {
+            let mut visible = !filter.hidden_kinds.contains(&kind);
+            if ui.checkbox(&mut visible, kind.label()).changed() {
+                if visible {
+                    filter.hidden_kinds.remove(&kind);
+                } else {
+                    filter.hidden_kinds.insert(kind);
+                }
+            }
+        }
+    });
+
+    ui.separator();
+
+    let search = filter.search.to_lowercase();
+    // Newest first
+    let visible: Vec<&ServerEvent> = events
+        .iter()
+        .rev()
+        .filter(|event| !filter.hidden_kinds.contains(&event.kind))
+        .filter(|event| search.is_empty() || event.message.to_lowercase().contains(&search))
+        .collect();
+
+    if events.is_empty() {
+        ui.label("No events recorded for this server yet");
+        return;
+    }
+
+    ui.label(format!("Showing {} of {} events", visible.len(), events.len()));
+    ui.separator();
+
+    ScrollArea::vertical().auto_shrink([false, false]).show(ui, |ui| {
+        for event in visible {
+            ui.horizontal(|ui| {
+                ui.label(egui::RichText::new(super::format_timestamp(event.timestamp)).monospace());
+                ui.colored_label(kind_color(event.kind), format!("[{}]", event.kind.label()));
+                ui.label(&event.message);
+            });
+        }
+    });
+}
@@ -1,4 +1,76 @@
+pub mod bundle_import;
 pub mod config_tab;
 pub mod mods_tab;
+pub mod mod_copy;
+pub mod mod_downloads;
+pub mod mod_trash;
 pub mod control_tab;
+pub mod events_tab;
+pub mod schedule_tab;
+pub mod server_update;
+pub mod settings_tab;
+pub mod setup_wizard;
+pub mod share;
+pub mod zip_tree;
 
+use std::time::Duration;
+
+/// Compact human-readable duration, e.g. "2d 3h", "1h 23m", "45s"
+pub fn format_duration(duration: Duration) -> String {
+    let secs = duration.as_secs();
+    let (days, hours, minutes) = (secs / 86_400, (secs % 86_400) / 3_600, (secs % 3_600) / 60);
+
+    if days > 0 {
+        format!("{}d {}h", days, hours)
+    } else if hours > 0 {
+        format!("{}h {}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {}s", minutes, secs % 60)
+    } else {
+        format!("{}s", secs)
+    }
+}
+
+/// Human-readable byte count, e.g. "512 B", "14.2 MB"
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["KB", "MB", "GB", "TB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    // Compared with what rounds to 1024.0, so 1048575 bytes reads "1.0 MB" and not "1024.0 KB"
+    while value >= 1023.95 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+/// Local date and time for a unix timestamp in seconds
+pub fn format_timestamp(timestamp: i64) -> String {
+    chrono::DateTime::from_timestamp(timestamp, 0)
+        .map(|t| t.with_timezone(&chrono::Local).format("%Y-%m-%d %H:%M:%S").to_string())
+        .unwrap_or_default()
+}
+pub mod console;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn formats_byte_counts() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KB");
+        assert_eq!(format_bytes(1024 * 1024 - 1), "1.0 MB");
+        assert_eq!(format_bytes(1024 * 1024), "1.0 MB");
+        assert_eq!(format_bytes(1024 * 1024 * 1024 - 1), "1.0 GB");
+        assert_eq!(format_bytes(1024 * 1024 * 1024), "1.0 GB");
+        assert_eq!(format_bytes(1536 * 1024 * 1024), "1.5 GB");
+        // Past the largest unit the number just grows
+        assert_eq!(format_bytes(u64::MAX), "16777216.0 TB");
+    }
+}
@@ -0,0 +1,83 @@
+use crate::server::ServerEntry;
+use crate::{ModCopy, ModType};
+use egui::Ui;
+
+pub enum CopyAction {
+    None,
+    Cancel,
+    /// Copy was clicked; look for mods the target already has first
+    Check,
+    /// Copy, replacing the target's own copies or leaving them alone
+    Copy { overwrite: bool },
+}
+
+/// The "Copy Mods" window for sending mods to one of the other `servers`
+pub fn show(ui: &mut Ui, copy: &mut ModCopy, servers: &[ServerEntry]) -> CopyAction {
+    let mut action = CopyAction::None;
+    let kind = match copy.mod_type {
+        ModType::Client => "client",
+        ModType::Server => "server",
+    };
+
+    match copy.mods.as_slice() {
+        [(name, _)] => ui.label(format!("Copy the {} mod {} to:", kind, name)),
+        mods => ui.label(format!("Copy {} {} mods to:", mods.len(), kind)),
+    };
+    let targets: Vec<&ServerEntry> = servers.iter()
+        .filter(|s| s.id != copy.source_server_id && !s.folder_missing)
+        .collect();
+    if targets.is_empty() {
+        ui.label("There is no other server to copy to.");
+    }
+    let selected_name = copy.target_server_id.as_ref()
+        .and_then(|id| targets.iter().find(|s| &s.id == id))
+        .map_or("Choose a server", |s| s.display_name());
+    let previous = copy.target_server_id.clone();
+    egui::ComboBox::from_id_salt("mod_copy_target")
+        .selected_text(selected_name)
+        .show_ui(ui, |ui| {
+            for server in &targets {
+                ui.selectable_value(&mut copy.target_server_id, Some(server.id.clone()), server.display_name());
+            }
+        });
+    if copy.target_server_id != previous {
+        copy.existing = None;
+    }
+
+    ui.separator();
+    match &copy.existing {
+        Some(existing) => {
+            ui.colored_label(
+                egui::Color32::YELLOW,
+                format!("The server already has {} of these mod(s):", existing.len()),
+            );
+            egui::ScrollArea::vertical().max_height(150.0).show(ui, |ui| {
+                for name in existing {
+                    ui.label(format!("  • {}", name));
+                }
+            });
+            ui.horizontal(|ui| {
+                if ui.button("Overwrite").clicked() {
+                    action = CopyAction::Copy { overwrite: true };
+                }
+                if ui.button("Skip These").clicked() {
+                    action = CopyAction::Copy { overwrite: false };
+                }
+                if ui.button("Cancel").clicked() {
+                    action = CopyAction::Cancel;
+                }
+            });
+        }
+        None => {
+            ui.horizontal(|ui| {
+                if ui.add_enabled(copy.target_server_id.is_some(), egui::Button::new("Copy")).clicked() {
+                    action = CopyAction::Check;
+                }
+                if ui.button("Cancel").clicked() {
+                    action = CopyAction::Cancel;
+                }
+            });
+        }
+    }
+    action
+}
@@ -0,0 +1,176 @@
+use crate::download::{DownloadBatch, DownloadState};
+use crate::mod_search::{ModSearch, SearchState};
+use egui::Ui;
+
+pub enum DownloadsAction {
+    None,
+    Search,
+    Download(Vec<String>),
+    CancelBatch,
+    Back,
+    Close,
+}
+
+/// The "Download Client Mods" window: URLs being entered, then the batch downloading them
+pub struct ModDownloads {
+    /// Server the mods are installed into, fixed when the window opens
+    pub server_id: String,
+    pub urls: String,
+    pub batch: Option<DownloadBatch>,
+}
+
+pub fn show(
+    ui: &mut Ui,
+    downloads: &mut ModDownloads,
+    search: &mut ModSearch,
+    searchable: bool,
+    server_name: &str,
+) -> DownloadsAction {
+    ui.set_max_width(600.0);
+    match &downloads.batch {
+        None => show_input(ui, &mut downloads.urls, search, searchable, server_name),
+        Some(batch) => show_batch(ui, batch),
+    }
+}
+
+fn show_input(
+    ui: &mut Ui,
+    urls: &mut String,
+    search: &mut ModSearch,
+    searchable: bool,
+    server_name: &str,
+) -> DownloadsAction {
+    let mut action = DownloadsAction::None;
+
+    if searchable {
+        ui.horizontal(|ui| {
+            let response = ui.add(egui::TextEdit::singleline(&mut search.query).hint_text("Search mods"));
+            let submitted = response.lost_focus() && ui.input(|i| i.key_pressed(egui::Key::Enter));
+            if ui.button("🔍 Search").clicked() || submitted {
+                action = DownloadsAction::Search;
+            }
+        });
+        match &search.state {
+            SearchState::Idle => {}
+            SearchState::Searching(_) => {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Searching...");
+                });
+            }
+            SearchState::Failed(e) => {
+                ui.colored_label(egui::Color32::RED, e);
+            }
+            SearchState::Done(results) if results.is_empty() => {
+                ui.colored_label(egui::Color32::GRAY, "No mods found");
+            }
+            SearchState::Done(results) => {
+                egui::ScrollArea::vertical().id_salt("mod_search_results").max_height(250.0).show(ui, |ui| {
+                    for (i, result) in results.iter().enumerate() {
+                        ui.push_id(i, |ui| {
+                            ui.horizontal(|ui| {
+                                if ui.button("Install").on_hover_text(&result.download_url).clicked() {
+                                    action = DownloadsAction::Download(vec![result.download_url.clone()]);
+                                }
+                                ui.strong(&result.name);
+                                if let Some(version) = &result.version {
+                                    ui.weak(version);
+                                }
+                                if let Some(author) = &result.author {
+                                    ui.weak(format!("by {}", author));
+                                }
+                                if let Some(size) = result.size {
+                                    ui.weak(super::format_bytes(size));
+                                }
+                            });
+                            if let Some(description) = &result.description {
+                                ui.label(egui::RichText::new(description).small());
+                            }
+                        });
+                    }
+                });
+            }
+        }
+        ui.separator();
+    }
+
+    ui.label(format!("Paste download links to ZIP files, one per line. Each is downloaded, checked and added to {}.", server_name));
+    egui::ScrollArea::vertical().max_height(200.0).show(ui, |ui| {
+        ui.add(
+            egui::TextEdit::multiline(urls)
+                .hint_text("https://example.com/mod.zip")
+                .desired_rows(6)
+                .desired_width(f32::INFINITY),
+        );
+    });
+    ui.separator();
+    ui.horizontal(|ui| {
+        if ui.button("Cancel").clicked() {
+            action = DownloadsAction::Close;
+        }
+        let has_urls = urls.lines().any(|l| !l.trim().is_empty());
+        if ui.add_enabled(has_urls, egui::Button::new("⬇ Download")).clicked() {
+            action = DownloadsAction::Download(
+                urls.lines()
+                    .map(str::trim)
+                    .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                    .map(str::to_string)
+                    .collect(),
+            );
+        }
+    });
+    action
+}
+
+fn show_batch(ui: &mut Ui, batch: &DownloadBatch) -> DownloadsAction {
+    let mut action = DownloadsAction::None;
+
+    egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+        for (i, item) in batch.items.iter().enumerate() {
+            ui.push_id(i, |ui| {
+                ui.label(egui::RichText::new(&item.url).monospace().small());
+                match &item.state {
+                    DownloadState::Queued => {
+                        ui.colored_label(egui::Color32::GRAY, "Waiting");
+                    }
+                    DownloadState::Downloading { received, total } => {
+                        let text = match total {
+                            Some(total) => format!("{} of {}", super::format_bytes(*received), super::format_bytes(*total)),
+                            None => super::format_bytes(*received),
+                        };
+                        let fraction = total.map_or(0.0, |total| *received as f32 / total.max(1) as f32);
+                        ui.add(egui::ProgressBar::new(fraction).text(text).animate(total.is_none()));
+                    }
+                    DownloadState::Downloaded => {
+                        ui.label("Installing...");
+                    }
+                    DownloadState::Installed(name) => {
+                        ui.colored_label(egui::Color32::GREEN, format!("✔ Installed {}", name));
+                    }
+                    DownloadState::Failed(e) => {
+                        ui.colored_label(egui::Color32::RED, format!("✖ {}", e));
+                    }
+                }
+            });
+            ui.add_space(4.0);
+        }
+    });
+    ui.separator();
+    if batch.is_finished() {
+        let installed = batch.items.iter()
+            .filter(|item| matches!(item.state, DownloadState::Installed(_)))
+            .count();
+        ui.label(format!("{} of {} mod(s) installed.", installed, batch.items.len()));
+        ui.horizontal(|ui| {
+            if ui.button("Close").clicked() {
+                action = DownloadsAction::Close;
+            }
+            if ui.button("Back").on_hover_text("Download or search for more").clicked() {
+                action = DownloadsAction::Back;
+            }
+        });
+    } else if ui.button("Cancel").on_hover_text("Stop after the current download").clicked() {
+        action = DownloadsAction::CancelBatch;
+    }
+    action
+}
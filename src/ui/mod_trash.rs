@@ -0,0 +1,76 @@
+use crate::mods::{self, TrashedMod};
+use egui::{ScrollArea, Ui};
+use std::path::{Path, PathBuf};
+
+pub enum TrashAction {
+    None,
+    Restore(PathBuf),
+    Purge(PathBuf),
+    EmptyTrash,
+}
+
+/// The "Mod Trash" window listing a server's deleted mods
+pub struct TrashView {
+    pub server_id: String,
+    pub items: Result<Vec<TrashedMod>, String>,
+}
+
+impl TrashView {
+    pub fn new(server_id: String, server_path: &Path) -> Self {
+        let mut view = Self { server_id, items: Ok(Vec::new()) };
+        view.refresh(server_path);
+        view
+    }
+
+    pub fn refresh(&mut self, server_path: &Path) {
+        self.items = mods::list_trash(server_path).map_err(|e| e.to_string());
+    }
+}
+
+pub fn show(ui: &mut Ui, view: &TrashView) -> TrashAction {
+    let mut action = TrashAction::None;
+
+    let items = match &view.items {
+        Ok(items) => items,
+        Err(e) => {
+            ui.colored_label(egui::Color32::RED, format!("Could not read the trash: {}", e));
+            return action;
+        }
+    };
+    if items.is_empty() {
+        ui.label("The trash is empty.");
+        return action;
+    }
+
+    let total: u64 = items.iter().map(|item| item.size).sum();
+    ui.label(format!("{} deleted mod(s), {}", items.len(), super::format_bytes(total)));
+    ui.separator();
+
+    ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+        for item in items {
+            ui.horizontal(|ui| {
+                ui.label(item.name());
+                let from = item.original.parent().map(|p| p.display().to_string()).unwrap_or_default();
+                ui.weak(from);
+                ui.weak(super::format_bytes(item.size));
+                if let Some(deleted_at) = item.deleted_at {
+                    ui.weak(deleted_at.format("%Y-%m-%d %H:%M").to_string());
+                }
+                ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                    if ui.button("Delete Forever").clicked() {
+                        action = TrashAction::Purge(item.path.clone());
+                    }
+                    if ui.button("Restore").on_hover_text("Move it back to where it was deleted from").clicked() {
+                        action = TrashAction::Restore(item.path.clone());
+                    }
+                });
+            });
+        }
+    });
+
+    ui.separator();
+    if ui.button("Empty Trash").on_hover_text("Permanently delete everything listed here").clicked() {
+        action = TrashAction::EmptyTrash;
+    }
+    action
+}
@@ -1,7 +1,10 @@
 use crate::mods;
-use crate::server::ServerEntry;
-use crate::{DeleteConfirmation, ModsCache, StatusMessage, ModType, ModFilter};
+use crate::{DeleteConfirmation, ModsCache, ModType, ModFilter};
 use egui::{ScrollArea, Ui};
+use std::path::PathBuf;
+
+/// Drag and drop payload of a mod row, the mod's index in the cache
+struct DraggedMod(usize);
 
 pub enum ModsAction {
     None,
@@ -9,112 +12,186 @@ pub enum ModsAction {
     SwitchToClient,
     ViewDetails(usize), // Index of the mod to view details for
     ChangeFilter(ModFilter),
+    SetEnabled(usize, bool), // Index of the mod and whether it should end up enabled
+    SetEnabledMany(Vec<usize>, bool),
+    AddClientMods(Vec<PathBuf>),
+    PackageFolder(PathBuf), // Folder to zip into the Client directory
+    DownloadMods,
+    ResolveSplit(usize, bool), // Index of a mod in both trees and whether to keep the enabled copy
+    CopyToServer(Vec<usize>), // Indices of the mods to copy to another server
+    Undo,
+    ShowTrash,
+    CreateResourceFolder,
+    UseDefaultResourceFolder(bool), // Whether to save the config right away
 }
 
 pub fn show(
     ui: &mut Ui,
-    server: &ServerEntry,
     mods_cache: &mut Option<ModsCache>,
     current_mod_type: ModType,
     current_mod_filter: ModFilter,
-    status: &mut Option<StatusMessage>,
+    search: &mut String,
     delete_confirmation: &mut Option<DeleteConfirmation>,
+    undo: Option<&str>, // Description of the last mod change that can be undone
 ) -> ModsAction {
     let mut action = ModsAction::None;
-    
+
     // Mod type selector
     ui.horizontal(|ui| {
         ui.label("View:");
-        if ui.selectable_label(current_mod_type == ModType::Client, "📦 Client").clicked() {
-            if current_mod_type != ModType::Client {
-                action = ModsAction::SwitchToClient;
-            }
+        if ui.selectable_label(current_mod_type == ModType::Client, "📦 Client").clicked() && current_mod_type != ModType::Client {
+            action = ModsAction::SwitchToClient;
         }
-        if ui.selectable_label(current_mod_type == ModType::Server, "📁 Server").clicked() {
-            if current_mod_type != ModType::Server {
-                action = ModsAction::SwitchToServer;
-            }
+        if ui.selectable_label(current_mod_type == ModType::Server, "📁 Server").clicked() && current_mod_type != ModType::Server {
+            action = ModsAction::SwitchToServer;
         }
-        
+
         // Show filter options only for Client mods
         if current_mod_type == ModType::Client {
             ui.separator();
             ui.label("Filter:");
-            if ui.selectable_label(current_mod_filter == ModFilter::All, "All").clicked() {
-                if current_mod_filter != ModFilter::All {
-                    action = ModsAction::ChangeFilter(ModFilter::All);
-                }
+            if ui.selectable_label(current_mod_filter == ModFilter::All, "All").clicked() && current_mod_filter != ModFilter::All {
+                action = ModsAction::ChangeFilter(ModFilter::All);
             }
-            if ui.selectable_label(current_mod_filter == ModFilter::LevelsOnly, "Levels").clicked() {
-                if current_mod_filter != ModFilter::LevelsOnly {
-                    action = ModsAction::ChangeFilter(ModFilter::LevelsOnly);
-                }
+            if ui.selectable_label(current_mod_filter == ModFilter::LevelsOnly, "Levels").clicked() && current_mod_filter != ModFilter::LevelsOnly {
+                action = ModsAction::ChangeFilter(ModFilter::LevelsOnly);
             }
-            if ui.selectable_label(current_mod_filter == ModFilter::VehiclesOnly, "Vehicles").clicked() {
-                if current_mod_filter != ModFilter::VehiclesOnly {
-                    action = ModsAction::ChangeFilter(ModFilter::VehiclesOnly);
-                }
+            if ui.selectable_label(current_mod_filter == ModFilter::VehiclesOnly, "Vehicles").clicked() && current_mod_filter != ModFilter::VehiclesOnly {
+                action = ModsAction::ChangeFilter(ModFilter::VehiclesOnly);
             }
         }
     });
-    
+
     ui.separator();
-    
+
     ui.horizontal(|ui| {
         // Only show Add Mod button for Client mods
         if current_mod_type == ModType::Client {
             if ui.button("Add Client Mod...").clicked() {
                 if let Some(files) = rfd::FileDialog::new()
                     .add_filter("ZIP files", &["zip"])
-                    .pick_files() 
+                    .pick_files()
                 {
-                    let mut added_count = 0;
-                    let mut errors = Vec::new();
-
-                    for file in files {
-                        let resource_folder = server.get_resource_folder();
-                        match mods::add_client_mod(&server.path, &resource_folder, &file) {
-                            Ok(_) => added_count += 1,
-                            Err(e) => errors.push(format!("{}: {}", file.display(), e)),
-                        }
-                    }
-
-                    if added_count > 0 {
-                        *status = Some(StatusMessage {
-                            text: format!("Added {} client mod(s)", added_count),
-                            is_error: false,
-                        });
-                        *mods_cache = None; // Force reload
-                    }
-
-                    if !errors.is_empty() {
-                        *status = Some(StatusMessage {
-                            text: format!("Errors: {}", errors.join(", ")),
-                            is_error: true,
-                        });
-                    }
+                    action = ModsAction::AddClientMods(files);
+                }
+            }
+            if ui.button("Download...").on_hover_text("Download client mods from a list of links").clicked() {
+                action = ModsAction::DownloadMods;
+            }
+            if ui.button("Package Folder...")
+                .on_hover_text("Zip a mod folder into the Client directory")
+                .clicked()
+            {
+                if let Some(folder) = rfd::FileDialog::new().pick_folder() {
+                    action = ModsAction::PackageFolder(folder);
                 }
             }
-            
+
             ui.label("ℹ Client mods must be ZIP files");
+        } else if mods_cache.as_ref().is_some_and(|cache| cache.nested) {
+            ui.label("ℹ Server mods are folders with Lua scripts in Resources/Server/ or in group folders inside it")
+                .on_hover_text(format!(
+                    "A folder without Lua scripts of its own is a group, the folders in it are the mods (up to {} levels deep). Change this in Settings > Server Mods.",
+                    mods::SERVER_MOD_MAX_DEPTH
+                ));
         } else {
-            ui.label("ℹ Server mods are folders - add them manually to Resources/Server/");
+            ui.label("ℹ Server mods are folders - add them manually to Resources/Server/")
+                .on_hover_text("Each folder directly in Resources/Server/ is one mod. Settings > Server Mods can look inside group folders.");
         }
 
         if ui.button("Refresh").clicked() {
             *mods_cache = None; // Force reload
         }
+        if ui.button("🗑 Trash").on_hover_text("Restore or permanently delete deleted mods").clicked() {
+            action = ModsAction::ShowTrash;
+        }
+        if let Some(undo) = undo {
+            if ui.button("↶ Undo").on_hover_text(format!("Undo: {}", undo)).clicked() {
+                action = ModsAction::Undo;
+            }
+        }
     });
 
-    ui.separator();
+    ui.horizontal(|ui| {
+        ui.label("Search:");
+        ui.text_edit_singleline(search);
+        if !search.is_empty() && ui.button("✖").clicked() {
+            search.clear();
+        }
+    });
 
-    // Use a flag to track if we need to reload after the UI is done
-    let mut needs_reload = false;
+    ui.separator();
 
     match mods_cache {
         Some(cache) => {
+            // A wrong ResourceFolder would otherwise just look like a server without mods
+            match cache.folder_issue {
+                Some(mods::ResourceFolderIssue::Missing) => {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(
+                            egui::Color32::RED,
+                            format!("⚠ The resource folder \"{}\" from the config doesn't exist, so no mods can be found", cache.resource_folder),
+                        );
+                        if ui.button("Create Folder").clicked() {
+                            action = ModsAction::CreateResourceFolder;
+                        }
+                    });
+                }
+                Some(mods::ResourceFolderIssue::AlsoDefault) => {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        format!(
+                            "⚠ Both \"{}\" (from the config) and \"{}\" exist; the server only loads mods from \"{}\"",
+                            cache.resource_folder,
+                            mods::DEFAULT_RESOURCE_FOLDER,
+                            cache.resource_folder,
+                        ),
+                    );
+                }
+                None if cache.resource_folder != mods::DEFAULT_RESOURCE_FOLDER => {
+                    ui.label(format!("ℹ Using the resource folder \"{}\" from the config", cache.resource_folder));
+                }
+                None => {}
+            }
+
+            if !cache.default_folder_mods.is_empty() {
+                ui.group(|ui| {
+                    ui.colored_label(
+                        egui::Color32::YELLOW,
+                        format!(
+                            "⚠ No mods in \"{}\", but \"{}\" has {}. The config's ResourceFolder probably points at the wrong folder.",
+                            cache.resource_folder,
+                            mods::DEFAULT_RESOURCE_FOLDER,
+                            cache.default_folder_mods.len(),
+                        ),
+                    );
+                    egui::CollapsingHeader::new(format!("Mods in {}", mods::DEFAULT_RESOURCE_FOLDER))
+                        .id_salt("default_folder_mods")
+                        .show(ui, |ui| {
+                            for mod_entry in &cache.default_folder_mods {
+                                let state = if mod_entry.enabled { "" } else { " (disabled)" };
+                                ui.label(format!("{}{}", mod_entry.relative_path, state));
+                            }
+                        });
+                    ui.horizontal(|ui| {
+                        if ui.button(format!("Scan {} Instead", mods::DEFAULT_RESOURCE_FOLDER))
+                            .on_hover_text("Change ResourceFolder in the config without saving it, so you can check the mods first")
+                            .clicked()
+                        {
+                            action = ModsAction::UseDefaultResourceFolder(false);
+                        }
+                        if ui.button(format!("Use {} and Save Config", mods::DEFAULT_RESOURCE_FOLDER))
+                            .on_hover_text("Set ResourceFolder to Resources and save ServerConfig.toml")
+                            .clicked()
+                        {
+                            action = ModsAction::UseDefaultResourceFolder(true);
+                        }
+                    });
+                });
+            }
+
             // Apply filter for client mods
-            let filtered_mods: Vec<(usize, &mods::ModEntry)> = if current_mod_type == ModType::Client {
+            let mut filtered_mods: Vec<(usize, &mods::ModEntry)> = if current_mod_type == ModType::Client {
                 cache.mods.iter().enumerate().filter(|(_, mod_entry)| {
                     match current_mod_filter {
                         ModFilter::All => true,
@@ -125,135 +202,136 @@ pub fn show(
             } else {
                 cache.mods.iter().enumerate().collect()
             };
-            
-            if filtered_mods.is_empty() {
+
+            // Indices stay those of cache.mods, so actions hit the right mod while filtered
+            let search_lower = search.to_lowercase();
+            if !search_lower.is_empty() {
+                filtered_mods.retain(|(_, mod_entry)| mod_entry.relative_path.to_lowercase().contains(&search_lower));
+            }
+
+            if filtered_mods.is_empty() && !cache.mods.is_empty() {
+                ui.label(format!("No mods match the current filter (0 of {})", cache.mods.len()));
+            } else if filtered_mods.is_empty() {
                 ui.label("No mods found");
             } else {
-                ui.label(format!("Showing: {} / Total: {}", filtered_mods.len(), cache.mods.len()));
+                ui.label(format!("Showing {} of {}", filtered_mods.len(), cache.mods.len()));
+
+                // Bulk actions; "Select All" only covers the mods the filter currently shows
+                ui.horizontal(|ui| {
+                    let visible: Vec<usize> = filtered_mods.iter().map(|(idx, _)| *idx).collect();
+                    let mut all_selected = visible.iter().all(|idx| cache.selected.contains(idx));
+                    if ui.checkbox(&mut all_selected, "Select All").changed() {
+                        if all_selected {
+                            cache.selected.extend(visible);
+                        } else {
+                            for idx in &visible {
+                                cache.selected.remove(idx);
+                            }
+                        }
+                    }
+
+                    let mut selected: Vec<usize> = cache.selected.iter().copied().collect();
+                    selected.sort_unstable();
+                    ui.add_enabled_ui(!selected.is_empty(), |ui| {
+                        ui.label(format!("{} selected", selected.len()));
+                        if ui.button("Enable Selected").clicked() {
+                            action = ModsAction::SetEnabledMany(selected.clone(), true);
+                        }
+                        if ui.button("Disable Selected").clicked() {
+                            action = ModsAction::SetEnabledMany(selected.clone(), false);
+                        }
+                        if ui.button("Delete Selected").clicked() {
+                            *delete_confirmation = Some(DeleteConfirmation::Mods(selected.clone()));
+                        }
+                        if ui.button("Copy Selected To...").clicked() {
+                            action = ModsAction::CopyToServer(selected.clone());
+                        }
+                    });
+                });
+
+                if !cache.conflicts.is_empty() {
+                    egui::CollapsingHeader::new(
+                        egui::RichText::new(format!("⚠ {} level/vehicle conflict(s) between enabled mods", cache.conflicts.len()))
+                            .color(egui::Color32::YELLOW),
+                    )
+                    .id_salt("mod_conflicts")
+                    .show(ui, |ui| {
+                        for conflict in &cache.conflicts {
+                            let names: Vec<&str> = conflict.mods.iter()
+                                .filter_map(|idx| cache.mods.get(*idx))
+                                .map(|m| m.relative_path.as_str())
+                                .collect();
+                            ui.label(format!("{} \"{}\" in: {}", conflict.kind.label(), conflict.name, names.join(", ")));
+                        }
+                        ui.label("Only one copy is loaded by the game; disable the mods you don't need.");
+                    });
+                }
+
+                let split_count = cache.mods.iter().filter(|m| m.disabled_copy.is_some()).count();
+                if split_count > 0 {
+                    ui.colored_label(
+                        egui::Color32::RED,
+                        format!("⚠ {} mod(s) exist in both the enabled and disabled folders, choose which copy to keep", split_count),
+                    );
+                }
+
+                let extras: Vec<usize> = cache
+                    .duplicates
+                    .iter()
+                    .flat_map(|group| group.iter().skip(1).copied())
+                    .collect();
+                if !extras.is_empty() {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!("⚠ {} duplicate mod(s) with identical content", extras.len()),
+                        );
+                        if ui.button("Remove Duplicates").clicked() {
+                            *delete_confirmation = Some(DeleteConfirmation::Mods(extras.clone()));
+                        }
+                    });
+                }
                 ui.separator();
 
+                // Indices of the enabled and the disabled mods shown
+                let sections = [true, false].map(|enabled| {
+                    filtered_mods.iter()
+                        .filter(|(_, m)| m.enabled == enabled)
+                        .map(|(idx, _)| *idx)
+                        .collect::<Vec<usize>>()
+                });
+
                 ScrollArea::vertical().show(ui, |ui| {
-                    for (idx, mod_entry) in filtered_mods {
-                        ui.group(|ui| {
-                            ui.horizontal(|ui| {
-                                {
-                                    // Both Server and Client mods show enable/disable
-                                    let status_text = if mod_entry.enabled {
-                                        "✓ Enabled"
-                                    } else {
-                                        "✗ Disabled"
-                                    };
-                                    let status_color = if mod_entry.enabled {
-                                        egui::Color32::GREEN
-                                    } else {
-                                        egui::Color32::GRAY
-                                    };
-
-                                    ui.colored_label(status_color, status_text);
-                                    
-                                    // Show icon based on mod type
-                                    let icon = if current_mod_type == ModType::Server {
-                                        "📁" // Folder for server mods
-                                    } else {
-                                        "📦" // Package for client mods (ZIP)
-                                    };
-                                    ui.label(icon);
-                                    
-                                    // Show level indicator for client mods
-                                    if current_mod_type == ModType::Client && mod_entry.is_level {
-                                        ui.colored_label(egui::Color32::from_rgb(100, 200, 255), "Level");
-                                    }
-                                    
-                                    // Show vehicle indicator for client mods
-                                    if current_mod_type == ModType::Client && mod_entry.is_vehicle {
-                                        ui.colored_label(egui::Color32::from_rgb(255, 180, 100), "Vehicle");
-                                    }
-                                    
-                                    ui.label(&mod_entry.relative_path);
-
-                                    ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
-                                        if ui.button("Delete").clicked() {
-                                            *delete_confirmation = Some(DeleteConfirmation::Mod(idx));
-                                        }
-                                        
-                                        // Show Info button only for client mods
-                                        if current_mod_type == ModType::Client {
-                                            if ui.button("Info").clicked() {
-                                                action = ModsAction::ViewDetails(idx);
-                                            }
-                                        }
-
-                                        let resource_folder = server.get_resource_folder();
-                                        
-                                        if mod_entry.enabled {
-                                            if ui.button("Disable").clicked() {
-                                                let result = if current_mod_type == ModType::Server {
-                                                    mods::disable_server_mod(
-                                                        &server.path,
-                                                        &resource_folder,
-                                                        &mod_entry.relative_path,
-                                                    )
-                                                } else {
-                                                    mods::disable_client_mod(
-                                                        &server.path,
-                                                        &resource_folder,
-                                                        &mod_entry.relative_path,
-                                                    )
-                                                };
-                                                
-                                                match result {
-                                                    Ok(_) => {
-                                                        *status = Some(StatusMessage {
-                                                            text: "Mod disabled".to_string(),
-                                                            is_error: false,
-                                                        });
-                                                        needs_reload = true;
-                                                    }
-                                                    Err(e) => {
-                                                        *status = Some(StatusMessage {
-                                                            text: format!("Failed to disable: {}", e),
-                                                            is_error: true,
-                                                        });
-                                                    }
-                                                }
-                                            }
-                                        } else {
-                                            if ui.button("Enable").clicked() {
-                                                let result = if current_mod_type == ModType::Server {
-                                                    mods::enable_server_mod(
-                                                        &server.path,
-                                                        &resource_folder,
-                                                        &mod_entry.relative_path,
-                                                    )
-                                                } else {
-                                                    mods::enable_client_mod(
-                                                        &server.path,
-                                                        &resource_folder,
-                                                        &mod_entry.relative_path,
-                                                    )
-                                                };
-                                                
-                                                match result {
-                                                    Ok(_) => {
-                                                        *status = Some(StatusMessage {
-                                                            text: "Mod enabled".to_string(),
-                                                            is_error: false,
-                                                        });
-                                                        needs_reload = true;
-                                                    }
-                                                    Err(e) => {
-                                                        *status = Some(StatusMessage {
-                                                            text: format!("Failed to enable: {}", e),
-                                                            is_error: true,
-                                                        });
-                                                    }
-                                                }
-                                            }
-                                        }
-                                    });
+                    // Enabled and disabled mods in their own sections; dragging a mod into the
+                    // other section moves it there (along with the other selected mods)
+                    for (section_enabled, rows) in [true, false].into_iter().zip(sections) {
+                        let frame = egui::Frame::group(ui.style());
+                        let (_, dropped) = ui.dnd_drop_zone::<DraggedMod, ()>(frame, |ui| {
+                            ui.set_min_width(ui.available_width());
+                            let title = if section_enabled { "Enabled" } else { "Disabled" };
+                            ui.strong(format!("{} ({})", title, rows.len()));
+                            if rows.is_empty() {
+                                ui.weak(format!("Drag mods here to {} them", if section_enabled { "enable" } else { "disable" }));
+                            }
+                            for idx in rows {
+                                let row_action = mod_row(ui, cache, idx, current_mod_type, delete_confirmation);
+                                if !matches!(row_action, ModsAction::None) {
+                                    action = row_action;
                                 }
-                            });
+                            }
                         });
+
+                        if let Some(dragged) = dropped {
+                            let DraggedMod(idx) = *dragged;
+                            if cache.selected.contains(&idx) {
+                                let mut selected: Vec<usize> = cache.selected.iter().copied().collect();
+                                selected.sort_unstable();
+                                action = ModsAction::SetEnabledMany(selected, section_enabled);
+                            } else if cache.mods.get(idx).is_some_and(|m| m.enabled != section_enabled) {
+                                action = ModsAction::SetEnabled(idx, section_enabled);
+                            }
+                        }
+                        ui.add_space(5.0);
                     }
                 });
             }
@@ -263,11 +341,132 @@ pub fn show(
         }
     }
 
-    // Reload if needed after all UI is done
-    if needs_reload {
-        *mods_cache = None;
-    }
-    
     action
 }
 
+/// One mod in the list: selection box, drag handle, badges and its own buttons
+fn mod_row(
+    ui: &mut Ui,
+    cache: &mut ModsCache,
+    idx: usize,
+    current_mod_type: ModType,
+    delete_confirmation: &mut Option<DeleteConfirmation>,
+) -> ModsAction {
+    let mut action = ModsAction::None;
+    let Some(mod_entry) = cache.mods.get(idx) else {
+        return action;
+    };
+
+    ui.group(|ui| {
+        ui.horizontal(|ui| {
+            let mut checked = cache.selected.contains(&idx);
+            if ui.checkbox(&mut checked, "").changed() {
+                if checked {
+                    cache.selected.insert(idx);
+                } else {
+                    cache.selected.remove(&idx);
+                }
+            }
+
+            // The handle to drag the mod into the other section
+            ui.dnd_drag_source(egui::Id::new(("mod_row", idx)), DraggedMod(idx), |ui| {
+                ui.label("☰");
+            })
+            .response
+            .on_hover_text(if mod_entry.enabled { "Drag to Disabled to disable" } else { "Drag to Enabled to enable" });
+
+            // Both Server and Client mods show enable/disable
+            let (status_text, status_color) = if mod_entry.enabled {
+                ("✓ Enabled", egui::Color32::GREEN)
+            } else {
+                ("✗ Disabled", egui::Color32::GRAY)
+            };
+            ui.colored_label(status_color, status_text);
+
+            // Show icon based on mod type
+            let icon = if current_mod_type == ModType::Server {
+                "📁" // Folder for server mods
+            } else {
+                "📦" // Package for client mods (ZIP)
+            };
+            ui.label(icon);
+
+            // Show level and vehicle indicators for client mods
+            if current_mod_type == ModType::Client && mod_entry.is_level {
+                ui.colored_label(egui::Color32::from_rgb(100, 200, 255), "Level");
+            }
+            if current_mod_type == ModType::Client && mod_entry.is_vehicle {
+                ui.colored_label(egui::Color32::from_rgb(255, 180, 100), "Vehicle");
+            }
+
+            ui.label(&mod_entry.relative_path);
+
+            let size_label = ui.weak(super::format_bytes(mod_entry.size));
+            if let Some(Some(details)) = cache.details.get(idx) {
+                size_label.on_hover_text(format!(
+                    "{} files, {} uncompressed",
+                    details.total_files,
+                    super::format_bytes(details.total_size)
+                ));
+            }
+
+            let row_conflicts: Vec<String> = cache.conflicts.iter()
+                .filter(|c| c.mods.contains(&idx))
+                .map(|c| format!("{} \"{}\"", c.kind.label(), c.name))
+                .collect();
+            if !row_conflicts.is_empty() {
+                ui.colored_label(egui::Color32::YELLOW, "Conflict")
+                    .on_hover_text(format!("Also provided by another enabled mod: {}", row_conflicts.join(", ")));
+            }
+
+            if let Some(disabled_copy) = &mod_entry.disabled_copy {
+                ui.colored_label(egui::Color32::RED, "In both folders")
+                    .on_hover_text(format!(
+                        "Also found at {}. Keeping one copy deletes the other.",
+                        disabled_copy.display()
+                    ));
+            }
+
+            // Flag extra copies, pointing at the one that would be kept
+            let original = cache
+                .duplicates
+                .iter()
+                .find(|group| group.iter().skip(1).any(|dup| *dup == idx))
+                .and_then(|group| cache.mods.get(group[0]));
+            if let Some(original) = original {
+                ui.colored_label(egui::Color32::YELLOW, "Duplicate")
+                    .on_hover_text(format!("Same content as {}", original.relative_path));
+            }
+
+            ui.with_layout(egui::Layout::right_to_left(egui::Align::Center), |ui| {
+                if ui.button("Delete").clicked() {
+                    *delete_confirmation = Some(DeleteConfirmation::Mod(idx));
+                }
+                if ui.button("Copy To...").on_hover_text("Copy this mod to another server").clicked() {
+                    action = ModsAction::CopyToServer(vec![idx]);
+                }
+
+                // Show Info button only for client mods
+                if current_mod_type == ModType::Client && ui.button("Info").clicked() {
+                    action = ModsAction::ViewDetails(idx);
+                }
+
+                if mod_entry.disabled_copy.is_some() {
+                    if ui.button("Keep Disabled").clicked() {
+                        action = ModsAction::ResolveSplit(idx, false);
+                    }
+                    if ui.button("Keep Enabled").clicked() {
+                        action = ModsAction::ResolveSplit(idx, true);
+                    }
+                } else if mod_entry.enabled {
+                    if ui.button("Disable").clicked() {
+                        action = ModsAction::SetEnabled(idx, false);
+                    }
+                } else if ui.button("Enable").clicked() {
+                    action = ModsAction::SetEnabled(idx, true);
+                }
+            });
+        });
+    });
+    action
+}
@@ -0,0 +1,103 @@
+use crate::schedule::{RestartSchedule, RestartSettings};
+use chrono::{DateTime, Local};
+use egui::{ScrollArea, Ui};
+
+pub enum ScheduleAction {
+    None,
+    /// The settings were edited and should be saved
+    Changed,
+    SkipNext,
+}
+
+pub fn show(
+    ui: &mut Ui,
+    restart: &mut RestartSettings,
+    is_server_running: bool,
+    next_restart: Option<DateTime<Local>>,
+) -> ScheduleAction {
+    let mut action = ScheduleAction::None;
+    let mut changed = false;
+
+    ScrollArea::vertical().show(ui, |ui| {
+        ui.heading("Scheduled Restarts");
+        ui.label("Long-running servers slow down over time. Restart this one automatically while the panel is open.");
+        ui.separator();
+
+        changed |= ui.checkbox(&mut restart.enabled, "Restart automatically").changed();
+        ui.add_space(5.0);
+
+        ui.add_enabled_ui(restart.enabled, |ui| {
+            let (mut hour, mut minute) = match restart.schedule {
+                RestartSchedule::Daily { hour, minute } => (hour, minute),
+                RestartSchedule::Interval { .. } => (4, 0),
+            };
+            let mut hours = match restart.schedule {
+                RestartSchedule::Interval { hours } => hours,
+                RestartSchedule::Daily { .. } => 12,
+            };
+            let mut daily = matches!(restart.schedule, RestartSchedule::Daily { .. });
+
+            ui.horizontal(|ui| {
+                changed |= ui.radio_value(&mut daily, true, "Every day at").changed();
+                ui.add_enabled_ui(daily, |ui| {
+                    changed |= ui.add(egui::DragValue::new(&mut hour).range(0..=23).custom_formatter(|v, _| format!("{:02}", v))).changed();
+                    ui.label(":");
+                    changed |= ui.add(egui::DragValue::new(&mut minute).range(0..=59).custom_formatter(|v, _| format!("{:02}", v))).changed();
+                });
+            });
+            ui.horizontal(|ui| {
+                changed |= ui.radio_value(&mut daily, false, "Every").changed();
+                ui.add_enabled_ui(!daily, |ui| {
+                    changed |= ui.add(egui::DragValue::new(&mut hours).range(1..=168)).changed();
+                    ui.label("hours after the server starts");
+                });
+            });
+
+            restart.schedule = if daily {
+                RestartSchedule::Daily { hour, minute }
+            } else {
+                RestartSchedule::Interval { hours }
+            };
+
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Warn players");
+                changed |= ui.add(egui::DragValue::new(&mut restart.warning_minutes).range(0..=60)).changed();
+                ui.label("minutes before restarting");
+            });
+            ui.label("Players get a chat message then, and again shortly before the restart. Set to 0 to restart without warning.");
+        });
+
+        ui.separator();
+
+        if !restart.enabled {
+            ui.colored_label(egui::Color32::GRAY, "Automatic restarts are off.");
+        } else if !is_server_running {
+            ui.colored_label(egui::Color32::GRAY, "The schedule starts counting when the server is started.");
+        } else if let Some(next) = next_restart {
+            ui.horizontal(|ui| {
+                let until = (next - Local::now()).to_std().unwrap_or_default();
+                ui.label(format!(
+                    "Next restart: {} (in {})",
+                    next.format("%Y-%m-%d %H:%M"),
+                    super::format_duration(until)
+                ));
+                if ui.button("Skip").on_hover_text("Skip this restart and wait for the one after").clicked() {
+                    action = ScheduleAction::SkipNext;
+                }
+            });
+        }
+
+        ui.add_space(5.0);
+        ui.label(
+            egui::RichText::new("Restarts only happen while the panel is running. One missed while it was closed is not made up later.")
+                .small()
+                .weak(),
+        );
+    });
+
+    if changed {
+        action = ScheduleAction::Changed;
+    }
+    action
+}
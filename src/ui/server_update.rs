@@ -0,0 +1,82 @@
+use crate::download::DownloadState;
+use crate::updates::{ServerUpdate, UpdateStage};
+use egui::Ui;
+
+pub enum ServerUpdateAction {
+    None,
+    Start,
+}
+
+/// The "Update BeamMP-Server" window. `attached` servers weren't started by the panel, so
+/// the update can't stop them.
+pub fn show(ui: &mut Ui, update: &mut ServerUpdate, installed: Option<&str>, attached: bool) -> ServerUpdateAction {
+    let mut action = ServerUpdateAction::None;
+
+    ui.set_max_width(420.0);
+    egui::Grid::new("server_update_versions").num_columns(2).show(ui, |ui| {
+        ui.label("Installed:");
+        ui.label(installed.unwrap_or("unknown"));
+        ui.end_row();
+        ui.label("Latest:");
+        ui.label(&update.release.tag_name);
+        ui.end_row();
+    });
+    if !update.release.html_url.is_empty() {
+        ui.hyperlink_to("Release notes", &update.release.html_url);
+    }
+    ui.separator();
+
+    let binaries = update.release.binaries();
+    match &update.stage {
+        UpdateStage::Confirm if binaries.is_empty() => {
+            ui.colored_label(egui::Color32::YELLOW, "This release has no BeamMP-Server build for this system.");
+        }
+        UpdateStage::Confirm => {
+            if binaries.len() > 1 {
+                let selected = binaries.get(update.asset).map_or("", |a| a.name.as_str());
+                egui::ComboBox::from_label("Build")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (i, asset) in binaries.iter().enumerate() {
+                            let text = format!("{}  ({})", asset.name, super::format_bytes(asset.size));
+                            ui.selectable_value(&mut update.asset, i, text);
+                        }
+                    });
+            }
+            ui.label("The server is stopped first if it is running. The current binary is kept next to the new one as a .bak file.");
+            if attached {
+                ui.colored_label(egui::Color32::YELLOW, "This server was started outside the panel; stop it there first.");
+            }
+            if ui
+                .add_enabled(!attached, egui::Button::new("Download and Replace"))
+                .clicked()
+            {
+                action = ServerUpdateAction::Start;
+            }
+        }
+        UpdateStage::Stopping => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Waiting for the server to stop...");
+            });
+        }
+        UpdateStage::Downloading(batch) => {
+            if let Some(DownloadState::Downloading { received, total }) = batch.items.first().map(|item| &item.state) {
+                let fraction = total.map_or(0.0, |total| *received as f32 / total.max(1) as f32);
+                ui.add(egui::ProgressBar::new(fraction).text(super::format_bytes(*received)).animate(total.is_none()));
+            } else {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Downloading...");
+                });
+            }
+        }
+        UpdateStage::Finished(Ok(message)) => {
+            ui.colored_label(egui::Color32::GREEN, format!("✔ {}", message));
+        }
+        UpdateStage::Finished(Err(e)) => {
+            ui.colored_label(egui::Color32::RED, e);
+        }
+    }
+    action
+}
@@ -0,0 +1,490 @@
+use crate::api::ApiHost;
+use crate::process;
+use crate::schedule;
+use crate::server;
+use crate::settings::{AppSettings, HighlightRule};
+use crate::StatusMessage;
+use egui::{ScrollArea, Ui};
+
+// Starting point when a custom console size is first enabled, a step up from egui's 12pt monospace
+const DEFAULT_CONSOLE_FONT_SIZE: f32 = 14.0;
+
+pub enum SettingsAction {
+    None,
+    ExportBundle { include_configs: bool },
+    ImportBundle,
+    CheckServerUpdates,
+    EmptyModTrash,
+    OpenSetupWizard,
+    /// Start the local API again with the port and token from the settings
+    RestartApi,
+}
+
+pub fn show(ui: &mut Ui, settings: &mut AppSettings, status: &mut Option<StatusMessage>, api: &ApiHost) -> SettingsAction {
+    let mut action = SettingsAction::None;
+    let mut changed = false;
+
+    ScrollArea::vertical().show(ui, |ui| {
+        ui.heading("Settings");
+        ui.separator();
+
+        ui.group(|ui| {
+            ui.heading("Game Client");
+            ui.add_space(5.0);
+            ui.label("Used by \"Connect with BeamNG\" to test-join a running server.");
+
+            ui.horizontal(|ui| {
+                ui.label("BeamMP Launcher:");
+                match &settings.client_path {
+                    Some(path) => {
+                        ui.label(egui::RichText::new(path.display().to_string()).monospace());
+                    }
+                    None => {
+                        ui.colored_label(egui::Color32::GRAY, "Not set");
+                    }
+                }
+            });
+
+            ui.horizontal(|ui| {
+                if ui.button("Browse...").clicked() {
+                    if let Some(path) = rfd::FileDialog::new().pick_file() {
+                        settings.client_path = Some(path);
+                        changed = true;
+                    }
+                }
+                if settings.client_path.is_some() && ui.button("Clear").clicked() {
+                    settings.client_path = None;
+                    changed = true;
+                }
+            });
+
+            ui.horizontal(|ui| {
+                ui.label("Launch arguments:");
+                changed |= ui.text_edit_singleline(&mut settings.client_launch_args).changed();
+            });
+            ui.label("{ip} and {port} are replaced with the local server address.");
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("Server Control");
+            ui.add_space(5.0);
+
+            ui.horizontal(|ui| {
+                ui.label("Auto-refresh player list every");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut settings.player_refresh_interval_secs).range(0..=3600))
+                    .changed();
+                ui.label("seconds");
+            });
+            ui.label("For the server whose Control tab is open. Other running servers, and this one when set to 0, are checked once a minute for the player counts in the server list.");
+
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Kill a stopping server after");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut settings.stop_grace_secs).range(1..=300))
+                    .changed();
+                ui.label("seconds");
+            });
+            ui.label("Stop Server asks the server to exit cleanly first, and only kills it if it is still running after this long.");
+
+            ui.add_space(5.0);
+            ui.horizontal_wrapped(|ui| {
+                ui.label("Restart countdown at");
+                let mut remove = None;
+                for (i, secs) in settings.restart_countdown_secs.iter_mut().enumerate() {
+                    changed |= ui.add(egui::DragValue::new(secs).range(1..=3600).suffix(" s")).changed();
+                    if ui.small_button("✖").on_hover_text("Remove this message").clicked() {
+                        remove = Some(i);
+                    }
+                }
+                if let Some(i) = remove {
+                    settings.restart_countdown_secs.remove(i);
+                    changed = true;
+                }
+                if ui.small_button("+").on_hover_text("Add a message").clicked() {
+                    let next = settings.restart_countdown_secs.iter().min().map_or(60, |&min| (min / 2).max(1));
+                    settings.restart_countdown_secs.push(next);
+                    changed = true;
+                }
+                if ui.small_button("Reset").clicked() {
+                    settings.restart_countdown_secs = schedule::DEFAULT_COUNTDOWN.to_vec();
+                    changed = true;
+                }
+            });
+            ui.label("Seconds before a restart at which players get a chat message, for Restart with Warning and scheduled restarts. With none, Restart with Warning restarts right away.");
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("Server Config");
+            ui.add_space(5.0);
+            changed |= ui.checkbox(&mut settings.autosave_config, "Save config changes automatically").changed();
+            ui.label("Edits in the Config tab are saved a couple of seconds after you stop typing, without clicking Apply. A port already used by another server is never saved automatically.");
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("Server Updates");
+            ui.add_space(5.0);
+            changed |= ui
+                .checkbox(&mut settings.check_server_updates, "Check for BeamMP-Server updates at startup")
+                .changed();
+            ui.label("Asks GitHub for the latest release and each server's binary for its version, and marks servers that are behind. Servers with \"I'm Scared of Updates\" in their config are never marked.");
+            ui.horizontal(|ui| {
+                ui.label("Release address:");
+                changed |= ui.text_edit_singleline(&mut settings.server_release_url).changed();
+            });
+            if ui.button("Check Now").clicked() {
+                action = SettingsAction::CheckServerUpdates;
+            }
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("Client Mods");
+            ui.add_space(5.0);
+            ui.label("New client mods are checked before they are installed: the ZIP must be readable in full and contain a levels/ or vehicles/ folder.");
+            changed |= ui
+                .checkbox(&mut settings.allow_unknown_mod_structure, "Allow mods with an unknown structure")
+                .on_hover_text("Still rejects corrupt archives")
+                .changed();
+
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Give up on a download after");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut settings.download_timeout_secs).range(5..=600))
+                    .changed();
+                ui.label("seconds without data");
+            });
+
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Mod search address:");
+                changed |= ui
+                    .add(egui::TextEdit::singleline(&mut settings.mod_search_url).hint_text("https://.../search?q={query}"))
+                    .changed();
+            });
+            ui.label("Adds a search box to Download in the Mods tab. {query} is replaced with the search text; the service must answer with a JSON list of mods with \"name\" and \"download_url\".");
+
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Keep up to");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut settings.mod_trash_limit_mb).range(0..=1_000_000).suffix(" MB"))
+                    .changed();
+                ui.label("of deleted mods per server");
+                if ui.button("Empty Trash").on_hover_text("Permanently delete the trashed mods of every server").clicked() {
+                    action = SettingsAction::EmptyModTrash;
+                }
+            });
+            ui.label(format!(
+                "Deleted mods are moved to a {} folder in the server's directory so they can be restored. Past this size the oldest ones are removed when you next delete a mod.",
+                crate::mods::TRASH_FOLDER
+            ));
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("Server Mods");
+            ui.add_space(5.0);
+            changed |= ui
+                .checkbox(&mut settings.nested_server_mods, "Find server mods in nested folders")
+                .changed();
+            ui.label(format!(
+                "Normally every folder in Resources/Server is a mod. With this on, a folder with Lua scripts directly inside is a mod, and a folder without any is treated as a group of mods and searched, up to {} levels deep.",
+                crate::mods::SERVER_MOD_MAX_DEPTH
+            ));
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("Sharing");
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Public IP service:");
+                changed |= ui.text_edit_singleline(&mut settings.public_ip_service).changed();
+            });
+            ui.label("Asked for your public IP only when you click Look Up Public IP in the Share window. It must answer with just the address. Leave empty to never ask.");
+            ui.add_space(5.0);
+            ui.horizontal(|ui| {
+                ui.label("Server list:");
+                changed |= ui.text_edit_singleline(&mut settings.server_list_url).changed();
+            });
+            ui.label("Searched by Check Status in the Share window to see if the server is publicly listed. Leave empty to hide the check.");
+        });
+
+        ui.add_space(10.0);
+
+                ui.group(|ui| {
+            ui.heading("Appearance");
+            ui.add_space(5.0);
+
+            // Zooming while the slider is dragged would move it under the pointer,
+            // so the new scale is only applied once the drag ends
+            let scale_id = ui.id().with("ui_scale_drag");
+            let mut scale = ui.data(|d| d.get_temp::<f32>(scale_id)).unwrap_or(settings.ui_scale);
+            ui.horizontal(|ui| {
+                ui.label("UI Scale:");
+                let response = ui.add(egui::Slider::new(&mut scale, 0.75..=2.0).step_by(0.05));
+                if response.dragged() {
+                    ui.data_mut(|d| d.insert_temp(scale_id, scale));
+                } else {
+                    ui.data_mut(|d| d.remove::<f32>(scale_id));
+                    if scale != settings.ui_scale {
+                        settings.ui_scale = scale;
+                        changed = true;
+                    }
+                }
+                if ui.button("Reset").clicked() {
+                    settings.ui_scale = 1.0;
+                    changed = true;
+                }
+            });
+
+            ui.horizontal(|ui| {
+                let mut custom = settings.console_font_size.is_some();
+                if ui.checkbox(&mut custom, "Console font size:").changed() {
+                    settings.console_font_size = custom.then_some(DEFAULT_CONSOLE_FONT_SIZE);
+                    changed = true;
+                }
+                if let Some(size) = &mut settings.console_font_size {
+                    changed |= ui.add(egui::DragValue::new(size).range(8.0..=32.0).suffix(" pt")).changed();
+                }
+            });
+
+            changed |= ui
+                .checkbox(&mut settings.console_timestamps, "Timestamp console lines")
+                .on_hover_text("The time each line arrived, shown in front of it. Exported logs keep the lines as the server wrote them.")
+                .changed();
+
+            ui.horizontal(|ui| {
+                ui.label("Console scrollback:");
+                changed |= ui
+                    .add(egui::DragValue::new(&mut settings.console_scrollback_lines).range(crate::settings::SCROLLBACK_RANGE).speed(50))
+                    .changed();
+                ui.label("lines per server");
+            })
+            .response
+            .on_hover_text("Older lines leave the console but stay in the run's log on disk, which Export Log saves. More lines use more memory.");
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("Console Highlights");
+            ui.add_space(5.0);
+            ui.label("Console lines matching a regular expression get a background in its color. The first matching rule wins.");
+
+            let mut remove = None;
+            for (i, rule) in settings.highlight_rules.iter_mut().enumerate() {
+                ui.horizontal(|ui| {
+                    changed |= ui
+                        .add(egui::TextEdit::singleline(&mut rule.pattern).hint_text("e.g. joined|left").code_editor())
+                        .changed();
+                    changed |= ui.color_edit_button_srgb(&mut rule.color).changed();
+                    changed |= ui
+                        .checkbox(&mut rule.notify, "🔔")
+                        .on_hover_text("Desktop notification when a line matches")
+                        .changed();
+                    if ui.small_button("✖").on_hover_text("Remove this rule").clicked() {
+                        remove = Some(i);
+                    }
+                });
+                if let Err(e) = regex::Regex::new(&rule.pattern) {
+                    ui.colored_label(egui::Color32::RED, format!("Not used, the pattern is invalid: {}", e));
+                }
+            }
+            if let Some(i) = remove {
+                settings.highlight_rules.remove(i);
+                changed = true;
+            }
+            if ui.button("+ Add Rule").clicked() {
+                settings.highlight_rules.push(HighlightRule {
+                    pattern: String::new(),
+                    color: [200, 160, 0],
+                    notify: false,
+                });
+                changed = true;
+            }
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("Notifications");
+            ui.add_space(5.0);
+            ui.label("Desktop notifications show up even while the panel is minimized.");
+            changed |= ui.checkbox(&mut settings.notify_on_crash, "When a server crashes").changed();
+            ui.label("Highlight rules with 🔔 ticked also notify, at most every 30 seconds per server.");
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("System Tray");
+            ui.add_space(5.0);
+            changed |= ui.checkbox(&mut settings.tray_icon, "Show an icon in the system tray").changed();
+            ui.add_enabled_ui(settings.tray_icon, |ui| {
+                changed |= ui
+                    .checkbox(&mut settings.minimize_to_tray, "Minimize to the tray when the window is closed")
+                    .on_hover_text("Running servers keep running. Use Quit in the tray menu to exit the panel.")
+                    .changed();
+                changed |= ui
+                    .checkbox(&mut settings.start_minimized, "Start minimized to the tray")
+                    .changed();
+            });
+            ui.label("The icon's menu has Show, to bring the window back, and Quit. On Linux it needs a desktop with StatusNotifierItem support, such as KDE, or GNOME with the AppIndicator extension.");
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("Local API");
+            ui.add_space(5.0);
+            ui.label("Lets your own scripts and dashboards list, start and stop servers and send commands over HTTP.");
+
+            if ui.checkbox(&mut settings.api_enabled, "Enable local API").changed() {
+                if settings.api_enabled && settings.api_token.is_empty() {
+                    settings.api_token = uuid::Uuid::new_v4().simple().to_string();
+                }
+                changed = true;
+            }
+
+            ui.add_enabled_ui(settings.api_enabled, |ui| {
+                ui.horizontal(|ui| {
+                    ui.label("Port:");
+                    changed |= ui
+                        .add(egui::DragValue::new(&mut settings.api_port).range(1024..=65535))
+                        .changed();
+                });
+
+                ui.horizontal(|ui| {
+                    ui.label("Token:");
+                    ui.label(egui::RichText::new(&settings.api_token).monospace());
+                    if ui.button("📋").on_hover_text("Copy token").clicked() {
+                        ui.ctx().copy_text(settings.api_token.clone());
+                    }
+                    if ui.button("Regenerate").clicked() {
+                        settings.api_token = uuid::Uuid::new_v4().simple().to_string();
+                        changed = true;
+                        action = SettingsAction::RestartApi;
+                    }
+                });
+
+                if let Some(error) = api.error() {
+                    ui.colored_label(egui::Color32::RED, error);
+                    if ui.button("Retry").clicked() {
+                        action = SettingsAction::RestartApi;
+                    }
+                } else if api.is_outdated(settings.api_port, &settings.api_token) {
+                    ui.horizontal(|ui| {
+                        ui.colored_label(
+                            egui::Color32::YELLOW,
+                            format!("Still listening on port {}.", api.running_port().unwrap_or_default()),
+                        );
+                        if ui.button("Apply").on_hover_text("Restart the API on the new port").clicked() {
+                            action = SettingsAction::RestartApi;
+                        }
+                    });
+                }
+
+                ui.label(format!(
+                    "Listens on http://127.0.0.1:{} only. Send the token as \"Authorization: Bearer <token>\".",
+                    settings.api_port
+                ));
+            });
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("Panel Data");
+            ui.add_space(5.0);
+            ui.label("The server list, event history and panel settings are stored in:");
+
+            match server::config_dir() {
+                Ok(dir) => {
+                    ui.horizontal(|ui| {
+                        ui.label(egui::RichText::new(dir.display().to_string()).monospace());
+                        if ui.button("📋").on_hover_text("Copy path").clicked() {
+                            ui.ctx().copy_text(dir.display().to_string());
+                        }
+                    });
+
+                    let remembered = server::remembered_config_dir().is_some_and(|remembered| remembered == dir);
+                    if remembered {
+                        ui.label("This folder was picked in the panel and is used after a restart too.");
+                    } else if server::config_dir_is_overridden() {
+                        ui.label(format!(
+                            "This folder was chosen for this session only. Set {} to keep using it.",
+                            server::CONFIG_DIR_ENV
+                        ));
+                    }
+
+                    if ui.button("📂 Open Config Directory").clicked() {
+                        if let Err(e) = process::open_path(&dir) {
+                            *status = Some(StatusMessage {
+                                text: format!("Failed to open config directory: {}", e),
+                                is_error: true,
+                            });
+                        }
+                    }
+                }
+                Err(e) => {
+                    ui.colored_label(egui::Color32::RED, format!("Unavailable: {}", e));
+                }
+            }
+
+            ui.add_space(5.0);
+            changed |= ui
+                .checkbox(&mut settings.show_setup_wizard, "Show the setup wizard at startup while the server list is empty")
+                .changed();
+            if ui.button("Open Setup Wizard").clicked() {
+                action = SettingsAction::OpenSetupWizard;
+            }
+        });
+
+        ui.add_space(10.0);
+
+        ui.group(|ui| {
+            ui.heading("Server Bundle");
+            ui.add_space(5.0);
+            ui.label("Move your setup to another machine: export the server list to a zip, then import it there.");
+
+            let include_id = ui.id().with("bundle_include_configs");
+            let mut include_configs = ui.data(|d| d.get_temp::<bool>(include_id)).unwrap_or(true);
+            if ui.checkbox(&mut include_configs, "Include each server's ServerConfig.toml").changed() {
+                ui.data_mut(|d| d.insert_temp(include_id, include_configs));
+            }
+
+            ui.horizontal(|ui| {
+                if ui.button("📦 Export Servers...").clicked() {
+                    action = SettingsAction::ExportBundle { include_configs };
+                }
+                if ui.button("Import Servers...").clicked() {
+                    action = SettingsAction::ImportBundle;
+                }
+            });
+            ui.label("Server files and mods are not included. On import you pick where each server's folder is on this machine.");
+        });
+    });
+
+    if changed {
+        if let Err(e) = settings.save() {
+            *status = Some(StatusMessage {
+                text: format!("Failed to save settings: {}", e),
+                is_error: true,
+            });
+        }
+    }
+    action
+}
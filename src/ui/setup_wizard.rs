@@ -0,0 +1,345 @@
+use crate::config::{self, ServerConfig};
+use crate::download::{DownloadBatch, DownloadState};
+use crate::process;
+use crate::settings::AppSettings;
+use crate::updates::{self, Release};
+use egui::Ui;
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+use std::time::Duration;
+
+/// Official BeamMP-Server downloads, for getting the binary by hand
+const RELEASES_PAGE: &str = "https://github.com/BeamMP/BeamMP-Server/releases";
+/// Where AuthKeys for public servers come from
+const KEYMASTER: &str = "https://keymaster.beammp.com";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WizardStep {
+    Welcome,
+    Config,
+    Binary,
+    Done,
+}
+
+enum BinaryStage {
+    NotFetched,
+    Fetching(Receiver<Result<Release, String>>),
+    Choose { release: Release, asset: usize },
+    Downloading { tag: String, batch: DownloadBatch },
+    Finished(Result<String, String>),
+}
+
+pub enum WizardAction {
+    None,
+    /// Add a folder that already holds a server, the usual way
+    AddExistingServer,
+    PickFolder,
+    /// Write `config` to the chosen folder unless it has a config already, and add the server
+    CreateServer,
+    /// The server binary of this release was installed into the new server's folder
+    BinaryInstalled(String),
+    Finish,
+}
+
+/// The first-run window that sets up a server for people with an empty server list
+pub struct SetupWizard {
+    pub step: WizardStep,
+    pub folder: Option<PathBuf>,
+    /// Config written for a new server, or the one found in `folder`; only `general` is edited here
+    pub config: ServerConfig,
+    /// Id of the server once it's in the list
+    pub server_id: Option<String>,
+    pub dont_show_again: bool,
+    binary: BinaryStage,
+}
+
+impl SetupWizard {
+    pub fn new() -> Self {
+        Self {
+            step: WizardStep::Welcome,
+            folder: None,
+            config: ServerConfig::default(),
+            server_id: None,
+            dont_show_again: false,
+            binary: BinaryStage::NotFetched,
+        }
+    }
+
+    /// Whether the chosen folder has a ServerConfig.toml that is used instead of writing one
+    pub fn has_existing_config(&self) -> bool {
+        self.folder.as_ref().is_some_and(|folder| folder.join("ServerConfig.toml").exists())
+    }
+
+    fn fetch_release(&mut self, settings: &AppSettings, ctx: &egui::Context) {
+        let (tx, rx) = channel();
+        let url = settings.server_release_url.trim().to_string();
+        let timeout = Duration::from_secs(settings.download_timeout_secs.max(1) as u64);
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = tx.send(updates::latest_release(&url, timeout).map_err(|e| e.to_string()));
+            ctx.request_repaint();
+        });
+        self.binary = BinaryStage::Fetching(rx);
+    }
+
+    /// Pick up the release lookup and download, and install the binary once it's downloaded.
+    /// Returns the installed release's tag.
+    fn poll(&mut self) -> Option<String> {
+        match &mut self.binary {
+            BinaryStage::Fetching(rx) => {
+                if let Ok(result) = rx.try_recv() {
+                    self.binary = match result {
+                        Ok(release) if release.binaries().is_empty() => BinaryStage::Finished(Err(format!(
+                            "BeamMP-Server {} has no build for this system",
+                            release.tag_name
+                        ))),
+                        Ok(release) => BinaryStage::Choose { release, asset: 0 },
+                        Err(e) => BinaryStage::Finished(Err(e)),
+                    };
+                }
+                None
+            }
+            BinaryStage::Downloading { tag, batch } => {
+                let tag = tag.clone();
+                if let Some((_, path)) = batch.poll().into_iter().next() {
+                    let folder = self.folder.clone().unwrap_or_default();
+                    let result = updates::replace_server_binary(&folder, &path);
+                    // Lets the batch remove its temporary folder when dropped
+                    batch.items[0].state = DownloadState::Installed(tag.clone());
+                    self.binary = BinaryStage::Finished(match result {
+                        Ok(_) => Ok(format!("Installed BeamMP-Server {}.", tag)),
+                        Err(e) => Err(e.to_string()),
+                    });
+                    return self.binary_installed().then_some(tag);
+                }
+                if let Some(DownloadState::Failed(e)) = batch.items.first().map(|item| &item.state) {
+                    self.binary = BinaryStage::Finished(Err(format!("Download failed: {}", e)));
+                }
+                None
+            }
+            _ => None,
+        }
+    }
+
+    fn binary_installed(&self) -> bool {
+        matches!(self.binary, BinaryStage::Finished(Ok(_)))
+    }
+}
+
+impl Default for SetupWizard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+pub fn show(ui: &mut Ui, wizard: &mut SetupWizard, settings: &AppSettings) -> WizardAction {
+    let mut action = match wizard.poll() {
+        Some(tag) => WizardAction::BinaryInstalled(tag),
+        None => WizardAction::None,
+    };
+
+    match wizard.step {
+        WizardStep::Welcome => {
+            ui.heading("Welcome to BeamMP Panel");
+            ui.label(
+                "The panel manages BeamMP servers on this computer. Each server is a folder with the \
+                 BeamMP-Server program and its ServerConfig.toml, plus a Resources folder for mods.",
+            );
+            ui.add_space(5.0);
+            ui.label("If you already have a server folder, add it. Otherwise this wizard makes a new one.");
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button("Add Existing Server...").clicked() {
+                    action = WizardAction::AddExistingServer;
+                }
+                if ui.button("Set Up a New Server").clicked() {
+                    wizard.step = WizardStep::Config;
+                }
+            });
+            ui.separator();
+            ui.checkbox(&mut wizard.dont_show_again, "Don't show this again")
+                .on_hover_text("It can be turned back on in Settings > Panel Data");
+        }
+        WizardStep::Config => {
+            ui.heading("Server Folder and Config");
+            ui.label("Pick an empty folder for the new server, or one that has the BeamMP-Server program in it already.");
+            ui.horizontal(|ui| {
+                let folder = wizard.folder.as_ref().map_or("No folder chosen".to_string(), |f| f.display().to_string());
+                ui.label(folder);
+                if ui.button("Browse...").clicked() {
+                    action = WizardAction::PickFolder;
+                }
+            });
+            ui.separator();
+
+            let has_config = wizard.has_existing_config();
+            if has_config {
+                ui.label("This folder already has a ServerConfig.toml. It is used as it is; edit it later in the Config tab.");
+            } else {
+                config_fields(ui, &mut wizard.config);
+            }
+
+            let key_problem = config::validate_auth_key(&wizard.config.general.auth_key).err()
+                .filter(|_| !has_config && !wizard.config.general.auth_key.trim().is_empty());
+            if let Some(problem) = key_problem {
+                ui.colored_label(egui::Color32::YELLOW, problem);
+            }
+
+            ui.add_space(10.0);
+            ui.horizontal(|ui| {
+                if ui.button("Back").clicked() {
+                    wizard.step = WizardStep::Welcome;
+                }
+                let label = if has_config { "Add Server" } else { "Create Server" };
+                if ui.add_enabled(wizard.folder.is_some(), egui::Button::new(label)).clicked() {
+                    action = WizardAction::CreateServer;
+                }
+            });
+        }
+        WizardStep::Binary => {
+            ui.heading("BeamMP-Server Program");
+            let folder = wizard.folder.clone().unwrap_or_default();
+            if process::server_executable(&folder).exists() && !matches!(wizard.binary, BinaryStage::Finished(_)) {
+                ui.label("The folder already has the BeamMP-Server program.");
+            } else {
+                binary_download(ui, wizard, settings);
+            }
+            ui.add_space(10.0);
+            let busy = matches!(wizard.binary, BinaryStage::Fetching(_) | BinaryStage::Downloading { .. });
+            let next = if process::server_executable(&folder).exists() { "Next" } else { "Skip" };
+            if ui.add_enabled(!busy, egui::Button::new(next)).clicked() {
+                wizard.step = WizardStep::Done;
+            }
+        }
+        WizardStep::Done => {
+            ui.heading("All Set");
+            let folder = wizard.folder.clone().unwrap_or_default();
+            ui.label("The server is in the list on the left. Press Start Server to run it.");
+            if !process::server_executable(&folder).exists() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label(format!("Put the BeamMP-Server program into {} first, from", folder.display()));
+                    ui.hyperlink_to("the official releases", RELEASES_PAGE);
+                });
+            }
+            if wizard.config.general.auth_key.trim().is_empty() {
+                ui.horizontal_wrapped(|ui| {
+                    ui.label("The server still needs an AuthKey. Get one from");
+                    ui.hyperlink_to("the BeamMP Keymaster", KEYMASTER);
+                    ui.label("and enter it in the Config tab.");
+                });
+            }
+            ui.add_space(10.0);
+            if ui.button("Finish").clicked() {
+                action = WizardAction::Finish;
+            }
+        }
+    }
+    action
+}
+
+/// The ServerConfig.toml settings a new server needs; the rest keep BeamMP's defaults
+fn config_fields(ui: &mut Ui, config: &mut ServerConfig) {
+    let general = &mut config.general;
+    egui::Grid::new("setup_config").num_columns(2).show(ui, |ui| {
+        ui.label("Name:");
+        ui.text_edit_singleline(&mut general.name);
+        ui.end_row();
+
+        ui.label("Port:");
+        ui.add(egui::DragValue::new(&mut general.port).range(1..=65535));
+        ui.end_row();
+
+        ui.label("Max players:");
+        ui.add(egui::DragValue::new(&mut general.max_players).range(1..=100));
+        ui.end_row();
+
+        ui.label("AuthKey:");
+        ui.add(egui::TextEdit::singleline(&mut general.auth_key).hint_text("can be added later"))
+            .on_hover_text("Every BeamMP server needs a key from the BeamMP Keymaster");
+        ui.end_row();
+
+        ui.label("");
+        ui.checkbox(&mut general.private, "Private")
+            .on_hover_text("Keep the server out of the public server browser; players join by address");
+        ui.end_row();
+    });
+    ui.horizontal(|ui| {
+        ui.label("Get an AuthKey at");
+        ui.hyperlink_to("the BeamMP Keymaster", KEYMASTER);
+    });
+}
+
+fn binary_download(ui: &mut Ui, wizard: &mut SetupWizard, settings: &AppSettings) {
+    let mut start = None;
+    let mut fetch = false;
+    match &mut wizard.binary {
+        BinaryStage::NotFetched => {
+            ui.label("The folder has no BeamMP-Server program yet. The panel can download the latest release for this system.");
+            ui.horizontal(|ui| {
+                if ui.button("Download BeamMP-Server").clicked() {
+                    fetch = true;
+                }
+                ui.label("or get it from");
+                ui.hyperlink_to("the official releases", RELEASES_PAGE);
+            });
+        }
+        BinaryStage::Fetching(_) => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Looking up the latest release...");
+            });
+        }
+        BinaryStage::Choose { release, asset } => {
+            let binaries = release.binaries();
+            ui.label(format!("Latest release: {}", release.tag_name));
+            if binaries.len() > 1 {
+                let selected = binaries.get(*asset).map_or("", |a| a.name.as_str());
+                egui::ComboBox::from_label("Build")
+                    .selected_text(selected)
+                    .show_ui(ui, |ui| {
+                        for (i, binary) in binaries.iter().enumerate() {
+                            let text = format!("{}  ({})", binary.name, super::format_bytes(binary.size));
+                            ui.selectable_value(asset, i, text);
+                        }
+                    });
+            }
+            if ui.button("Download and Install").clicked() {
+                start = binaries.get(*asset).map(|binary| (release.tag_name.clone(), binary.browser_download_url.clone()));
+            }
+        }
+        BinaryStage::Downloading { batch, .. } => {
+            if let Some(DownloadState::Downloading { received, total }) = batch.items.first().map(|item| &item.state) {
+                let fraction = total.map_or(0.0, |total| *received as f32 / total.max(1) as f32);
+                ui.add(egui::ProgressBar::new(fraction).text(super::format_bytes(*received)).animate(total.is_none()));
+            } else {
+                ui.horizontal(|ui| {
+                    ui.spinner();
+                    ui.label("Downloading...");
+                });
+            }
+        }
+        BinaryStage::Finished(Ok(message)) => {
+            ui.colored_label(egui::Color32::GREEN, format!("✔ {}", message));
+        }
+        BinaryStage::Finished(Err(e)) => {
+            ui.colored_label(egui::Color32::RED, e.as_str());
+            ui.horizontal(|ui| {
+                if ui.button("Try Again").clicked() {
+                    fetch = true;
+                }
+                ui.label("or get it from");
+                ui.hyperlink_to("the official releases", RELEASES_PAGE);
+            });
+        }
+    }
+
+    if fetch {
+        wizard.fetch_release(settings, ui.ctx());
+    }
+    if let Some((tag, url)) = start {
+        let timeout = Duration::from_secs(settings.download_timeout_secs.max(1) as u64);
+        let batch = DownloadBatch::start(vec![url], timeout, ui.ctx());
+        wizard.binary = BinaryStage::Downloading { tag, batch };
+    }
+}
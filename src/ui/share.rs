@@ -0,0 +1,288 @@
+use crate::config::ServerConfig;
+use crate::listing::{self, ListedServer};
+use crate::settings::AppSettings;
+use egui::Ui;
+use std::net::{IpAddr, UdpSocket};
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+use std::time::Duration;
+
+enum PublicIp {
+    NotLooked,
+    Looking(Receiver<Result<IpAddr, String>>),
+    Found(IpAddr),
+    Failed(String),
+}
+
+enum ListingCheck {
+    NotChecked,
+    Checking(Receiver<Result<Option<ListedServer>, String>>),
+    Done(Result<Option<ListedServer>, String>),
+}
+
+/// The "Share Server" window: addresses players can join with, as text and QR code
+pub struct ShareView {
+    pub server_id: String,
+    lan_ip: Option<IpAddr>,
+    public_ip: PublicIp,
+    listing: ListingCheck,
+    /// QR code of the last join address drawn, so it isn't re-encoded every frame
+    qr: Option<(String, qrcode::QrCode)>,
+}
+
+impl ShareView {
+    pub fn new(server_id: String) -> Self {
+        Self {
+            server_id,
+            lan_ip: lan_ip(),
+            public_ip: PublicIp::NotLooked,
+            listing: ListingCheck::NotChecked,
+            qr: None,
+        }
+    }
+
+    fn look_up_public_ip(&mut self, service: &str, timeout: Duration, ctx: &egui::Context) {
+        let (tx, rx) = channel();
+        let service = service.to_string();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = tx.send(fetch_public_ip(&service, timeout).map_err(|e| e.to_string()));
+            ctx.request_repaint();
+        });
+        self.public_ip = PublicIp::Looking(rx);
+    }
+
+    fn check_listing(&mut self, config: &ServerConfig, settings: &AppSettings, ctx: &egui::Context) {
+        let (tx, rx) = channel();
+        let url = settings.server_list_url.trim().to_string();
+        let timeout = Duration::from_secs(settings.download_timeout_secs.max(1) as u64);
+        let name = config.general.name.clone();
+        let port = config.general.port;
+        let public_ip = match self.public_ip {
+            PublicIp::Found(ip) => Some(ip),
+            _ => None,
+        };
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let result = listing::find_server(&url, timeout, &name, port, public_ip);
+            let _ = tx.send(result.map_err(|e| e.to_string()));
+            ctx.request_repaint();
+        });
+        self.listing = ListingCheck::Checking(rx);
+    }
+
+    fn poll(&mut self) {
+        if let PublicIp::Looking(rx) = &self.public_ip {
+            if let Ok(result) = rx.try_recv() {
+                self.public_ip = match result {
+                    Ok(ip) => PublicIp::Found(ip),
+                    Err(e) => PublicIp::Failed(e),
+                };
+            }
+        }
+        if let ListingCheck::Checking(rx) = &self.listing {
+            if let Ok(result) = rx.try_recv() {
+                self.listing = ListingCheck::Done(result);
+            }
+        }
+    }
+}
+
+fn fetch_public_ip(service: &str, timeout: Duration) -> anyhow::Result<IpAddr> {
+    let body = crate::download::agent(timeout)
+        .get(service)
+        .call()
+        .map_err(|e| anyhow::anyhow!("Could not reach {} ({})", service, e))?
+        .into_string()?;
+    body.trim()
+        .parse()
+        .map_err(|_| anyhow::anyhow!("{} didn't answer with an IP address", service))
+}
+
+/// Address of this machine on the local network: the one the OS would use to reach the
+/// internet. Nothing is sent, connecting a UDP socket only picks the route.
+fn lan_ip() -> Option<IpAddr> {
+    let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
+    socket.connect("8.8.8.8:80").ok()?;
+    Some(socket.local_addr().ok()?.ip()).filter(|ip| !ip.is_unspecified())
+}
+
+/// "ip:port" as typed into BeamMP's direct connect, with brackets around IPv6 addresses
+fn join_address(ip: IpAddr, port: u16) -> String {
+    match ip {
+        IpAddr::V4(ip) => format!("{}:{}", ip, port),
+        IpAddr::V6(ip) => format!("[{}]:{}", ip, port),
+    }
+}
+
+/// Contents of the window. `auth_error` is the server's latest complaint about its auth key.
+pub fn show(ui: &mut Ui, view: &mut ShareView, config: &ServerConfig, settings: &AppSettings, running: bool, auth_error: Option<&str>) {
+    view.poll();
+    let ip_service = settings.public_ip_service.as_str();
+    let timeout = Duration::from_secs(settings.download_timeout_secs.max(1) as u64);
+    let general = &config.general;
+
+    ui.heading(&general.name);
+    ui.label(if general.private {
+        "Private: not listed in the BeamMP server browser. Players join with the address below."
+    } else {
+        "Public: listed in the BeamMP server browser under this name, or join with the address below."
+    });
+    ui.label(if general.allow_guests {
+        "Guests can join without a BeamMP account."
+    } else {
+        "Players need a BeamMP account; guests are turned away."
+    });
+    ui.label(format!("Up to {} players, {} car(s) each.", general.max_players, general.max_cars));
+    ui.separator();
+
+    let mut join = None;
+    egui::Grid::new("share_addresses").num_columns(3).show(ui, |ui| {
+        ui.label("Same network:");
+        match view.lan_ip {
+            Some(ip) => {
+                let address = join_address(ip, general.port);
+                ui.label(egui::RichText::new(&address).monospace());
+                if ui.button("📋").on_hover_text("Copy").clicked() {
+                    ui.ctx().copy_text(address.clone());
+                }
+                join = Some(address);
+            }
+            None => {
+                ui.colored_label(egui::Color32::GRAY, "Not connected to a network");
+            }
+        }
+        ui.end_row();
+
+        ui.label("Internet:");
+        match &view.public_ip {
+            PublicIp::NotLooked => {
+                if ip_service.trim().is_empty() {
+                    ui.colored_label(egui::Color32::GRAY, "Set a public IP service in Settings to look it up");
+                } else if ui.button("Look Up Public IP").on_hover_text(format!("Asks {}", ip_service)).clicked() {
+                    view.look_up_public_ip(ip_service.trim(), timeout, ui.ctx());
+                }
+            }
+            PublicIp::Looking(_) => {
+                ui.spinner();
+            }
+            PublicIp::Found(ip) => {
+                let address = join_address(*ip, general.port);
+                ui.label(egui::RichText::new(&address).monospace());
+                if ui.button("📋").on_hover_text("Copy").clicked() {
+                    ui.ctx().copy_text(address.clone());
+                }
+                // Friends elsewhere are the usual reason to share
+                join = Some(address);
+            }
+            PublicIp::Failed(e) => {
+                ui.colored_label(egui::Color32::RED, e);
+                if ui.button("Retry").clicked() {
+                    view.look_up_public_ip(ip_service.trim(), timeout, ui.ctx());
+                }
+            }
+        }
+        ui.end_row();
+    });
+    ui.label(
+        egui::RichText::new(format!(
+            "Players outside your network need port {} forwarded to this machine for both TCP and UDP.",
+            general.port
+        ))
+        .small()
+        .weak(),
+    );
+
+    if !settings.server_list_url.trim().is_empty() {
+        ui.separator();
+        show_listing(ui, view, config, settings, running, auth_error);
+    }
+
+    if let Some(address) = join {
+        ui.separator();
+        if view.qr.as_ref().is_none_or(|(encoded, _)| *encoded != address) {
+            view.qr = qrcode::QrCode::new(address.as_bytes()).ok().map(|code| (address.clone(), code));
+        }
+        if let Some((encoded, code)) = &view.qr {
+            ui.vertical_centered(|ui| {
+                draw_qr(ui, code, 180.0);
+                ui.label(egui::RichText::new(encoded).monospace());
+            });
+        }
+    }
+}
+
+/// Whether the server shows up in the public server list, and the likely reason if not
+fn show_listing(ui: &mut Ui, view: &mut ShareView, config: &ServerConfig, settings: &AppSettings, running: bool, auth_error: Option<&str>) {
+    let general = &config.general;
+    ui.horizontal(|ui| {
+        ui.label("Server list:");
+        let checking = matches!(view.listing, ListingCheck::Checking(_));
+        if checking {
+            ui.spinner();
+        }
+        if ui
+            .add_enabled(!checking, egui::Button::new("Check Status"))
+            .on_hover_text(format!("Looks for this server in {}", settings.server_list_url.trim()))
+            .clicked()
+        {
+            view.check_listing(config, settings, ui.ctx());
+        }
+    });
+
+    if let Some(line) = auth_error {
+        ui.colored_label(egui::Color32::RED, "Auth invalid: the BeamMP backend refused this server's auth key.");
+        ui.label(egui::RichText::new(line).monospace().small());
+    }
+
+    let ListingCheck::Done(result) = &view.listing else {
+        return;
+    };
+    match result {
+        Ok(Some(listed)) => {
+            ui.colored_label(
+                egui::Color32::GREEN,
+                format!("Listed as \"{}\" with {}/{} players", listed.name, listed.players, listed.max_players),
+            );
+            let map = crate::mods::map_level_name(&listed.map).unwrap_or(&listed.map);
+            ui.label(egui::RichText::new(format!("{} · BeamMP-Server {}", map, listed.version)).weak());
+        }
+        Ok(None) => {
+            ui.colored_label(egui::Color32::YELLOW, "Not listed: the server isn't in the public server list.");
+            let reason = if general.private {
+                "Private servers are never listed. Untick Private in the Config tab to list it."
+            } else if general.auth_key.trim().is_empty() {
+                "Public servers need an auth key from keymaster.beammp.com, set in the Config tab."
+            } else if auth_error.is_some() {
+                "Get a new auth key from keymaster.beammp.com; the current one was refused."
+            } else if !running {
+                "The server isn't running from the panel. It is listed while it runs."
+            } else {
+                "A server takes up to a minute to appear after starting. If it stays missing, check that the port is forwarded and the console shows no backend errors."
+            };
+            ui.label(reason);
+        }
+        Err(e) => {
+            ui.colored_label(egui::Color32::RED, format!("Unreachable: {}", e));
+        }
+    }
+}
+
+/// Paint a QR code as dark squares on white, with the quiet zone scanners need around it
+fn draw_qr(ui: &mut Ui, code: &qrcode::QrCode, size: f32) {
+    const QUIET_ZONE: usize = 4;
+    let width = code.width();
+    let module = size / (width + 2 * QUIET_ZONE) as f32;
+    let (rect, _) = ui.allocate_exact_size(egui::vec2(size, size), egui::Sense::hover());
+    let painter = ui.painter_at(rect);
+    painter.rect_filled(rect, 0.0, egui::Color32::WHITE);
+
+    for (i, color) in code.to_colors().into_iter().enumerate() {
+        if color != qrcode::Color::Dark {
+            continue;
+        }
+        let (x, y) = ((i % width + QUIET_ZONE) as f32, (i / width + QUIET_ZONE) as f32);
+        let min = rect.min + egui::vec2(x * module, y * module);
+        painter.rect_filled(egui::Rect::from_min_size(min, egui::vec2(module, module)), 0.0, egui::Color32::BLACK);
+    }
+}
@@ -0,0 +1,111 @@
+use crate::mods::{self, ZipDir};
+use egui::{CollapsingHeader, Ui};
+use std::path::PathBuf;
+use std::sync::mpsc::{channel, Receiver};
+use std::thread;
+
+// Files listed per folder before the rest are summarised, so huge folders stay responsive
+const MAX_FILES_PER_FOLDER: usize = 500;
+
+enum TreeState {
+    NotLoaded,
+    Loading(Receiver<Result<ZipDir, String>>),
+    Loaded(ZipDir),
+    Failed(String),
+}
+
+/// Contents of a ZIP shown as a folder tree. The archive is only read once the tree is
+/// first expanded, on a background thread.
+pub struct ZipTreeView {
+    path: PathBuf,
+    state: TreeState,
+}
+
+impl ZipTreeView {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            state: TreeState::NotLoaded,
+        }
+    }
+
+    fn start_loading(&mut self, ctx: &egui::Context) {
+        let (tx, rx) = channel();
+        let path = self.path.clone();
+        let ctx = ctx.clone();
+        thread::spawn(move || {
+            let _ = tx.send(mods::read_zip_tree(&path).map_err(|e| e.to_string()));
+            ctx.request_repaint();
+        });
+        self.state = TreeState::Loading(rx);
+    }
+
+    fn poll(&mut self) {
+        if let TreeState::Loading(rx) = &self.state {
+            match rx.try_recv() {
+                Ok(Ok(root)) => self.state = TreeState::Loaded(root),
+                Ok(Err(e)) => self.state = TreeState::Failed(e),
+                Err(std::sync::mpsc::TryRecvError::Empty) => {}
+                Err(std::sync::mpsc::TryRecvError::Disconnected) => {
+                    self.state = TreeState::Failed("Reading the archive stopped unexpectedly".to_string());
+                }
+            }
+        }
+    }
+}
+
+pub fn show(ui: &mut Ui, view: &mut ZipTreeView) {
+    view.poll();
+
+    let response = CollapsingHeader::new("Contents").default_open(false).show(ui, |ui| match &view.state {
+        TreeState::NotLoaded | TreeState::Loading(_) => {
+            ui.horizontal(|ui| {
+                ui.spinner();
+                ui.label("Reading archive...");
+            });
+        }
+        TreeState::Failed(e) => {
+            ui.colored_label(egui::Color32::RED, format!("Could not read the archive: {}", e));
+        }
+        TreeState::Loaded(root) => {
+            egui::ScrollArea::vertical().max_height(300.0).show(ui, |ui| {
+                if root.total_files == 0 {
+                    ui.weak("The archive is empty");
+                }
+                show_dir(ui, root, "");
+            });
+        }
+    });
+
+    if response.fully_open() && matches!(view.state, TreeState::NotLoaded) {
+        view.start_loading(ui.ctx());
+    }
+}
+
+/// Children of `dir`; `path` keeps the ids of same-named folders apart
+fn show_dir(ui: &mut Ui, dir: &ZipDir, path: &str) {
+    for (name, child) in &dir.dirs {
+        let child_path = format!("{}/{}", path, name);
+        let title = format!(
+            "📁 {}  ({} file{}, {})",
+            name,
+            child.total_files,
+            if child.total_files == 1 { "" } else { "s" },
+            super::format_bytes(child.total_size)
+        );
+        // Children are only laid out while a folder is open
+        CollapsingHeader::new(title)
+            .id_salt(&child_path)
+            .show(ui, |ui| show_dir(ui, child, &child_path));
+    }
+
+    for (name, size) in dir.files.iter().take(MAX_FILES_PER_FOLDER) {
+        ui.horizontal(|ui| {
+            ui.label(format!("📄 {}", name));
+            ui.weak(super::format_bytes(*size));
+        });
+    }
+    if dir.files.len() > MAX_FILES_PER_FOLDER {
+        ui.weak(format!("... and {} more files", dir.files.len() - MAX_FILES_PER_FOLDER));
+    }
+}
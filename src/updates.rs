@@ -0,0 +1,271 @@
+use crate::download;
+use crate::process;
+use crate::server::ServerEntry;
+use anyhow::{anyhow, Context, Result};
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+
+/// A BeamMP-Server release as described by the GitHub releases API
+#[derive(Debug, Clone, Deserialize)]
+pub struct Release {
+    pub tag_name: String,
+    /// Release page with the changelog
+    #[serde(default)]
+    pub html_url: String,
+    #[serde(default)]
+    pub assets: Vec<ReleaseAsset>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ReleaseAsset {
+    pub name: String,
+    pub browser_download_url: String,
+    #[serde(default)]
+    pub size: u64,
+}
+
+impl Release {
+    /// Assets that are a server binary for this OS, the best match for this machine first
+    pub fn binaries(&self) -> Vec<&ReleaseAsset> {
+        let mut binaries: Vec<&ReleaseAsset> = self
+            .assets
+            .iter()
+            .filter(|asset| asset.name.starts_with("BeamMP-Server"))
+            .filter(|asset| asset.name.ends_with(".exe") == cfg!(windows))
+            .filter(|asset| !asset.name.ends_with(".pdb") && !asset.name.ends_with(".zip"))
+            .collect();
+        binaries.sort_by_key(|asset| std::cmp::Reverse(asset_score(&asset.name)));
+        binaries
+    }
+}
+
+/// How well an asset name fits this machine's architecture and, on Linux, distribution
+fn asset_score(name: &str) -> u32 {
+    let name = name.to_lowercase();
+    let arm = name.contains("arm64") || name.contains("aarch64");
+    let mut score = match std::env::consts::ARCH {
+        "aarch64" if arm => 2,
+        "x86_64" if !arm => 2,
+        _ => 0,
+    };
+    if let Some((id, version)) = linux_distribution() {
+        if name.contains(&format!(".{}.{}", id, version)) {
+            score += 2;
+        } else if name.contains(&format!(".{}.", id)) {
+            score += 1;
+        }
+    }
+    score
+}
+
+/// `ID` and `VERSION_ID` from /etc/os-release, e.g. ("ubuntu", "22.04")
+fn linux_distribution() -> Option<(String, String)> {
+    if !cfg!(target_os = "linux") {
+        return None;
+    }
+    let release = fs::read_to_string("/etc/os-release").ok()?;
+    let value = |key: &str| {
+        release
+            .lines()
+            .find_map(|line| line.strip_prefix(key)?.strip_prefix('='))
+            .map(|v| v.trim().trim_matches('"').to_lowercase())
+    };
+    Some((value("ID")?, value("VERSION_ID")?))
+}
+
+pub fn latest_release(url: &str, timeout: Duration) -> Result<Release> {
+    let response = crate::download::agent(timeout).get(url).call().map_err(|e| match e {
+        ureq::Error::Status(code, response) => anyhow!("The release server answered HTTP {} {}", code, response.status_text()),
+        ureq::Error::Transport(e) => anyhow!("Could not reach the release server ({}). Check your internet connection.", e),
+    })?;
+    serde_json::from_str(&response.into_string()?).map_err(|e| anyhow!("The release server sent an unexpected answer: {}", e))
+}
+
+/// Latest release and the version each checked server's binary reports, by server id
+pub struct UpdateReport {
+    pub release: Result<Release, String>,
+    pub versions: HashMap<String, Result<String, String>>,
+}
+
+/// Look up the latest release at `url` and ask each of `servers`' binaries for its version.
+/// Slow (network plus one process per server), so run it on a background thread, and
+/// only pass servers whose version isn't known otherwise.
+pub fn check(url: &str, timeout: Duration, servers: Vec<(String, PathBuf)>) -> UpdateReport {
+    let release = latest_release(url, timeout).map_err(|e| e.to_string());
+    let versions = servers
+        .into_iter()
+        .filter(|(_, path)| process::server_executable(path).exists())
+        .map(|(id, path)| (id, process::server_version(&path).map_err(|e| e.to_string())))
+        .collect();
+    UpdateReport { release, versions }
+}
+
+/// Latest BeamMP-Server release and the version of each checked server's binary
+pub enum UpdateCheck {
+    NotChecked,
+    Checking(Receiver<UpdateReport>),
+    Checked(UpdateReport),
+}
+
+impl UpdateCheck {
+    /// Run `check` on a background thread, unless a check is already underway. Versions
+    /// come from the startup banner or the release a binary was installed from, so only
+    /// stopped servers without a known version should be in `servers`: `--version` starts
+    /// a second copy of the binary.
+    pub fn start(&mut self, url: String, timeout: Duration, servers: Vec<(String, PathBuf)>, ctx: &egui::Context) {
+        if matches!(self, Self::Checking(_)) {
+            return;
+        }
+        let (tx, rx) = std::sync::mpsc::channel();
+        let ctx = ctx.clone();
+        std::thread::spawn(move || {
+            let _ = tx.send(check(&url, timeout, servers));
+            ctx.request_repaint();
+        });
+        *self = Self::Checking(rx);
+    }
+
+    /// The report, returned once when the check finishes
+    pub fn poll(&mut self) -> Option<&UpdateReport> {
+        let Self::Checking(rx) = self else {
+            return None;
+        };
+        let report = rx.try_recv().ok()?;
+        *self = Self::Checked(report);
+        match self {
+            Self::Checked(report) => Some(report),
+            _ => None,
+        }
+    }
+
+    /// The latest release, if it is newer than the server's binary and the server's
+    /// config doesn't ask to be left alone
+    pub fn available_for(&self, server: &ServerEntry) -> Option<&Release> {
+        let Self::Checked(report) = self else {
+            return None;
+        };
+        let release = report.release.as_ref().ok()?;
+        let installed = server.server_version.as_deref()?;
+        if server.loaded_config.as_ref().is_some_and(|c| c.misc.im_scared_of_updates) {
+            return None;
+        }
+        is_newer(&release.tag_name, installed).then_some(release)
+    }
+}
+
+/// Replacing one server's binary with a release, as shown in the "Update BeamMP-Server" window
+pub struct ServerUpdate {
+    pub server_id: String,
+    pub release: Release,
+    /// Index into `release.binaries()`
+    pub asset: usize,
+    pub stage: UpdateStage,
+}
+
+pub enum UpdateStage {
+    Confirm,
+    /// Waiting for the server to exit before its binary is replaced
+    Stopping,
+    Downloading(download::DownloadBatch),
+    Finished(Result<String, String>),
+}
+
+impl ServerUpdate {
+    pub fn new(server_id: String, release: Release) -> Self {
+        Self {
+            server_id,
+            release,
+            asset: 0,
+            stage: UpdateStage::Confirm,
+        }
+    }
+
+    /// Move the update along: once the server has `stopped`, download the chosen binary,
+    /// then swap it in for the one in `server_path`. Returns true when the new binary was
+    /// just installed.
+    pub fn advance(&mut self, stopped: bool, server_path: &Path, timeout: Duration, ctx: &egui::Context) -> bool {
+        let mut result = None;
+        match &mut self.stage {
+            UpdateStage::Stopping if stopped => match self.release.binaries().get(self.asset) {
+                Some(asset) => {
+                    let batch = download::DownloadBatch::start(vec![asset.browser_download_url.clone()], timeout, ctx);
+                    self.stage = UpdateStage::Downloading(batch);
+                }
+                None => result = Some(Err("The release has no server binary for this system".to_string())),
+            },
+            UpdateStage::Downloading(batch) => {
+                if let Some((_, path)) = batch.poll().into_iter().next() {
+                    result = Some(replace_server_binary(server_path, &path).map_err(|e| e.to_string()));
+                    // Lets the batch remove its temporary folder when dropped
+                    batch.items[0].state = download::DownloadState::Installed(self.release.tag_name.clone());
+                } else if let Some(download::DownloadState::Failed(e)) = batch.items.first().map(|item| &item.state) {
+                    result = Some(Err(format!("Download failed: {}", e)));
+                }
+            }
+            _ => {}
+        }
+
+        let Some(result) = result else {
+            return false;
+        };
+        let tag = &self.release.tag_name;
+        let installed = result.is_ok();
+        self.stage = UpdateStage::Finished(match result {
+            Ok(Some(backup)) => Ok(format!("Updated to BeamMP-Server {}. The old binary is kept as {}.", tag, backup.display())),
+            Ok(None) => Ok(format!("Installed BeamMP-Server {}.", tag)),
+            Err(e) => Err(e),
+        });
+        installed
+    }
+}
+
+/// First `major.minor.patch` in `text`, e.g. from "v3.4.1" or "BeamMP-Server v3.4.1"
+pub fn parse_version(text: &str) -> Option<(u32, u32, u32)> {
+    text.split(|c: char| !c.is_ascii_digit() && c != '.')
+        .find_map(|part| {
+            let mut numbers = part.split('.').map(|n| n.parse::<u32>().ok());
+            let version = (numbers.next()??, numbers.next()??, numbers.next().flatten().unwrap_or(0));
+            Some(version)
+        })
+}
+
+/// Whether `latest` is a newer version than `installed`. Unparseable versions never are.
+pub fn is_newer(latest: &str, installed: &str) -> bool {
+    matches!((parse_version(latest), parse_version(installed)), (Some(latest), Some(installed)) if latest > installed)
+}
+
+/// Put a downloaded binary in place of the server's. The old one is kept next to it as
+/// `<name>.<timestamp>.bak`, whose path is returned if there was an old one.
+pub fn replace_server_binary(server_path: &Path, downloaded: &Path) -> Result<Option<PathBuf>> {
+    if let Some(reason) = process::platform_mismatch(downloaded) {
+        return Err(anyhow!(reason));
+    }
+
+    let exe_path = process::server_executable(server_path);
+    let exe_name = exe_path.file_name().and_then(|n| n.to_str()).unwrap_or("BeamMP-Server");
+    let backup = server_path.join(format!("{}.{}.bak", exe_name, chrono::Local::now().format("%Y-%m-%d_%H%M%S")));
+
+    let had_binary = exe_path.exists();
+    if had_binary {
+        fs::rename(&exe_path, &backup).context("Could not back up the old server binary")?;
+    }
+    if let Err(e) = fs::copy(downloaded, &exe_path) {
+        // Put the old binary back so the server still starts
+        if had_binary {
+            let _ = fs::rename(&backup, &exe_path);
+        }
+        return Err(anyhow!("Could not install the new server binary: {}", e));
+    }
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&exe_path, fs::Permissions::from_mode(0o755))?;
+    }
+
+    Ok(had_binary.then_some(backup))
+}